@@ -4,6 +4,7 @@ use std::{io, path::PathBuf, sync::Arc, time::Duration};
 
 use clap::Parser;
 use miette::IntoDiagnostic;
+use swegov_opendata::DatasetQuery;
 use tokio::signal;
 use tracing_subscriber::EnvFilter;
 use webcrawler::{crawler, CrawlerOptions};
@@ -19,17 +20,34 @@ async fn main() -> miette::Result<()> {
     let processing_concurrency = args.processing_concurrency;
     let state_path = args.state;
     let output = args.output;
+    let query = DatasetQuery {
+        typ: args.typ,
+        samling: args.samling,
+        riksmote_range: args.rm.unwrap_or_default(),
+        format: args.format,
+        uppdaterad_since: args.since,
+        min_storlek_bytes: args.min_size,
+        max_storlek_bytes: args.max_size,
+    };
 
     init_tracing()?;
     let output_path = output.unwrap_or_else(|| PathBuf::from("./output"));
 
+    let options = opendata_rd_spider::RdSpiderOptions {
+        user_agent: Some(APP_USER_AGENT.into()),
+        output_path,
+        query,
+        max_download_bytes: args.max_download_size,
+        min_free_disk_bytes: args.min_free_disk,
+    };
+    if args.dry_run {
+        let plan = opendata_rd_spider::RdSpider::plan(options).await?;
+        println!("{plan}");
+        return Ok(());
+    }
+
     tracing::info!("Creating spider");
-    let spider = Arc::new(opendata_rd_spider::RdSpider::new(
-        opendata_rd_spider::RdSpiderOptions {
-            user_agent: Some(APP_USER_AGENT.into()),
-            output_path,
-        },
-    )?);
+    let spider = Arc::new(opendata_rd_spider::RdSpider::new(options)?);
     crawler::run_with_options(
         spider.clone(),
         signal::ctrl_c(),
@@ -42,6 +60,12 @@ async fn main() -> miette::Result<()> {
     )
     .await;
     spider.close().await?;
+    for dataset in spider.deferred().await {
+        println!(
+            "deferred {} ({} bytes): {}",
+            dataset.samling, dataset.storlek_bytes, dataset.url
+        );
+    }
     Ok(())
 }
 
@@ -1,5 +1,8 @@
 use std::path::PathBuf;
 
+use chrono::NaiveDate;
+use swegov_opendata::{DataFormat, RiksmoteRange};
+
 #[derive(Debug, clap::Parser)]
 #[clap(author,version,about,long_about=None)]
 pub struct Args {
@@ -35,6 +38,46 @@ pub struct Args {
     #[clap(long, short = 's', default_value = "visited.json")]
     pub state: PathBuf,
 
+    /// Only fetch riksmöten in this range, e.g. "2018/19..2021/22" or "1990..".
+    #[clap(long)]
+    pub rm: Option<RiksmoteRange>,
+
+    /// Only fetch datasets of this typ, e.g. "prot". Can be given several times.
+    #[clap(long)]
+    pub typ: Vec<String>,
+
+    /// Only fetch datasets in this samling, e.g. "prot-2018-2021". Can be given several times.
+    #[clap(long)]
+    pub samling: Vec<String>,
+
+    /// Only fetch datasets in this format. Can be given several times.
+    #[clap(long, default_value = "json")]
+    pub format: Vec<DataFormat>,
+
+    /// Only fetch datasets updated on or after this date, e.g. "2024-01-01".
+    #[clap(long)]
+    pub since: Option<NaiveDate>,
+
+    /// Only fetch datasets of at least this size in bytes.
+    #[clap(long)]
+    pub min_size: Option<u64>,
+
+    /// Only fetch datasets of at most this size in bytes.
+    #[clap(long)]
+    pub max_size: Option<u64>,
+
+    /// Stop before the downloaded datasets add up to more than this many bytes.
+    #[clap(long)]
+    pub max_download_size: Option<u64>,
+
+    /// Stop before the free disk space of the output folder drops below this many bytes.
+    #[clap(long)]
+    pub min_free_disk: Option<u64>,
+
+    /// Only print the datasets that would be downloaded, without downloading or writing anything.
+    #[clap(long)]
+    pub dry_run: bool,
+
     /// Path to save downloaded artefacts
     pub output: Option<PathBuf>,
 }
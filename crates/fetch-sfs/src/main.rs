@@ -19,15 +19,25 @@ async fn main() -> miette::Result<()> {
     let processing_concurrency = args.processing_concurrency;
     let state_path = args.state;
     let output = args.output;
+    let riksmote_range = args.rm.unwrap_or_default();
 
     init_tracing()?;
 
-    let spider = Arc::new(opendata_spiders::sfs::SfsSpider::new(
-        opendata_spiders::sfs::SfsSpiderOptions {
-            user_agent: Some(APP_USER_AGENT.into()),
-            output_path: output.unwrap_or_else(|| PathBuf::from("./output")),
-        },
-    ));
+    let options = opendata_spiders::sfs::SfsSpiderOptions {
+        user_agent: Some(APP_USER_AGENT.into()),
+        output_path: output.unwrap_or_else(|| PathBuf::from("./output")),
+        riksmote_range,
+        keep_versions: args.keep_versions,
+    };
+    if args.dry_run {
+        let plan = opendata_spiders::sfs::SfsSpider::plan(options)
+            .await
+            .into_diagnostic()?;
+        println!("{plan}");
+        return Ok(());
+    }
+
+    let spider = Arc::new(opendata_spiders::sfs::SfsSpider::new(options));
     crawler::run_with_options(
         spider,
         signal::ctrl_c(),
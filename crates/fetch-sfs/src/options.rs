@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use swegov_opendata::RiksmoteRange;
+
 #[derive(Debug, clap::Parser)]
 #[clap(author,version,about,long_about=None)]
 pub struct Args {
@@ -31,6 +33,19 @@ pub struct Args {
     #[clap(long, short = 's', default_value = "visited.json")]
     pub state: PathBuf,
 
+    /// Only fetch riksmöten in this range, e.g. "2018/19..2021/22" or "1990..".
+    #[clap(long)]
+    pub rm: Option<RiksmoteRange>,
+
+    /// Only print how many documents would be downloaded, without downloading or writing anything.
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Overwrite a document that has changed since it was last fetched. By default the earlier
+    /// version is kept under `versions/` in the output folder.
+    #[clap(long = "no-keep-versions", action = clap::ArgAction::SetFalse)]
+    pub keep_versions: bool,
+
     /// Path to save downloaded artefacts
     pub output: Option<PathBuf>,
 }
@@ -0,0 +1,81 @@
+use std::{io, path::Path};
+
+use swegov_opendata::DataSet;
+
+#[cfg(test)]
+mod tests;
+
+/// Keeps track of what a crawl may still download.
+///
+/// The size of a dataset is taken from `storlek`, the free disk space is measured once,
+/// before the first dataset, and then reduced by every dataset that is scheduled.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Budget {
+    max_download_bytes: Option<u64>,
+    min_free_disk_bytes: Option<u64>,
+    scheduled_bytes: u64,
+    deferred: Vec<DataSet>,
+}
+
+impl Budget {
+    pub(crate) fn new(max_download_bytes: Option<u64>, min_free_disk_bytes: Option<u64>) -> Self {
+        Self {
+            max_download_bytes,
+            min_free_disk_bytes,
+            ..Default::default()
+        }
+    }
+
+    /// Schedule `dataset` if it fits in the budget, otherwise defer it.
+    ///
+    /// Once a dataset has been deferred every later dataset is deferred too, so that a
+    /// crawl stops at the first dataset that would exceed a limit.
+    pub(crate) fn reserve(&mut self, dataset: &DataSet, free_disk_bytes: Option<u64>) -> bool {
+        let scheduled_bytes = self.scheduled_bytes + dataset.storlek_bytes;
+        let exceeds_download = self
+            .max_download_bytes
+            .is_some_and(|max| scheduled_bytes > max);
+        let exceeds_disk = self
+            .min_free_disk_bytes
+            .zip(free_disk_bytes)
+            .is_some_and(|(min, free)| free.saturating_sub(scheduled_bytes) < min);
+        if !self.deferred.is_empty() || exceeds_download || exceeds_disk {
+            tracing::warn!(
+                samling = dataset.samling,
+                storlek = dataset.storlek_bytes,
+                scheduled_bytes = self.scheduled_bytes,
+                exceeds_download,
+                exceeds_disk,
+                "deferring dataset"
+            );
+            self.deferred.push(dataset.clone());
+            return false;
+        }
+        self.scheduled_bytes = scheduled_bytes;
+        true
+    }
+
+    /// `true` if the free disk space is needed to check the budget.
+    pub(crate) fn checks_disk(&self) -> bool {
+        self.min_free_disk_bytes.is_some()
+    }
+
+    pub(crate) fn deferred(&self) -> &[DataSet] {
+        &self.deferred
+    }
+}
+
+/// The free disk space in bytes on the file system of `path`.
+#[cfg(unix)]
+pub(crate) fn free_disk_bytes(path: &Path) -> io::Result<u64> {
+    let stat = rustix::fs::statvfs(path)?;
+    Ok(stat.f_bavail * stat.f_frsize)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn free_disk_bytes(_path: &Path) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "measuring free disk space is only supported on unix",
+    ))
+}
@@ -0,0 +1,81 @@
+use super::*;
+
+fn dataset(samling: &str, storlek: u64) -> DataSet {
+    serde_json::from_value(serde_json::json!({
+        "namn": "dokument",
+        "typ": "prot",
+        "samling": samling,
+        "rm": "2018-2021",
+        "filnamn": format!("{samling}.json.zip"),
+        "storlek": storlek,
+        "format": "json",
+        "filformat": "zip",
+        "uppdaterad": "2024-03-02 02:00:00",
+        "url": format!("/dataset/dokument/{samling}.json.zip"),
+        "description": null,
+        "beskrivning": null,
+        "upplysning": null,
+    }))
+    .unwrap()
+}
+
+fn reserve_all(budget: &mut Budget, free_disk_bytes: Option<u64>) -> Vec<bool> {
+    [("a", 40), ("b", 40), ("c", 10), ("d", 5)]
+        .into_iter()
+        .map(|(samling, storlek)| budget.reserve(&dataset(samling, storlek), free_disk_bytes))
+        .collect()
+}
+
+fn deferred(budget: &Budget) -> Vec<&str> {
+    budget
+        .deferred()
+        .iter()
+        .map(|dataset| dataset.samling.as_str())
+        .collect()
+}
+
+#[test]
+fn unlimited_budget_schedules_everything() {
+    let mut budget = Budget::default();
+
+    assert_eq!(reserve_all(&mut budget, None), [true; 4]);
+    assert!(budget.deferred().is_empty());
+    assert!(!budget.checks_disk());
+}
+
+#[test]
+fn max_download_bytes_defers_the_rest() {
+    let mut budget = Budget::new(Some(85), None);
+
+    assert_eq!(
+        reserve_all(&mut budget, None),
+        [true, true, false, false],
+        "'d' would fit, but the crawl stops at the first dataset over the limit"
+    );
+    assert_eq!(deferred(&budget), ["c", "d"]);
+}
+
+#[test]
+fn min_free_disk_bytes_defers_the_rest() {
+    let mut budget = Budget::new(None, Some(100));
+
+    assert!(budget.checks_disk());
+    assert_eq!(
+        reserve_all(&mut budget, Some(150)),
+        [true, false, false, false]
+    );
+    assert_eq!(deferred(&budget), ["b", "c", "d"]);
+}
+
+#[test]
+fn unknown_free_disk_space_is_not_checked() {
+    let mut budget = Budget::new(None, Some(100));
+
+    assert_eq!(reserve_all(&mut budget, None), [true; 4]);
+}
+
+#[cfg(unix)]
+#[test]
+fn measures_free_disk_bytes() {
+    assert!(free_disk_bytes(Path::new(".")).is_ok());
+}
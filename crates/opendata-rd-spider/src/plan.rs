@@ -0,0 +1,105 @@
+use std::fmt;
+
+use swegov_opendata::{DataSet, DatasetLista, DatasetQuery};
+
+use crate::{budget::Budget, rd_spider::Metadata};
+
+#[cfg(test)]
+mod tests;
+
+/// What a crawl would download, without downloading it.
+#[derive(Debug, Clone, Default)]
+pub struct RdPlan {
+    pub datasets: Vec<PlannedDataset>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedDataset {
+    pub dataset: DataSet,
+    /// `true` if the dataset is newer than the downloaded copy, or not downloaded yet.
+    pub stale: bool,
+    /// `true` if the dataset is stale but would exceed the download limits.
+    pub deferred: bool,
+}
+
+impl RdPlan {
+    pub(crate) fn new(
+        datasetlista: &DatasetLista,
+        query: &DatasetQuery,
+        metadata: &Metadata,
+        budget: &mut Budget,
+        free_disk_bytes: Option<u64>,
+    ) -> Self {
+        let datasets = datasetlista
+            .select(query)
+            .map(|dataset| {
+                let stale = metadata.should_be_updated(&dataset.url, dataset.uppdaterad.as_inner());
+                PlannedDataset {
+                    stale,
+                    deferred: stale && !budget.reserve(dataset, free_disk_bytes),
+                    dataset: dataset.clone(),
+                }
+            })
+            .collect();
+        Self { datasets }
+    }
+
+    /// The stale datasets that would be downloaded within the download limits.
+    pub fn stale(&self) -> impl Iterator<Item = &DataSet> {
+        self.datasets
+            .iter()
+            .filter(|planned| planned.stale && !planned.deferred)
+            .map(|planned| &planned.dataset)
+    }
+
+    /// The stale datasets that would be deferred by the download limits.
+    pub fn deferred(&self) -> impl Iterator<Item = &DataSet> {
+        self.datasets
+            .iter()
+            .filter(|planned| planned.deferred)
+            .map(|planned| &planned.dataset)
+    }
+
+    /// The expected number of bytes to download, according to `storlek`.
+    pub fn download_bytes(&self) -> u64 {
+        self.stale().map(|dataset| dataset.storlek_bytes).sum()
+    }
+}
+
+impl fmt::Display for RdPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for PlannedDataset {
+            dataset,
+            stale,
+            deferred,
+        } in &self.datasets
+        {
+            writeln!(
+                f,
+                "{} {} ({}, rm {}): {} bytes, uppdaterad {}",
+                match (stale, deferred) {
+                    (_, true) => "defer",
+                    (true, false) => "fetch",
+                    (false, false) => "skip ",
+                },
+                dataset.samling,
+                dataset.format.as_str(),
+                dataset.rm,
+                dataset.storlek_bytes,
+                dataset.uppdaterad,
+            )?;
+        }
+        write!(
+            f,
+            "{} of {} matching datasets are stale, {} bytes to download",
+            self.datasets.iter().filter(|planned| planned.stale).count(),
+            self.datasets.len(),
+            self.download_bytes(),
+        )?;
+        let deferred = self.deferred().count();
+        if deferred > 0 {
+            write!(f, ", {deferred} deferred by download limits")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use swegov_opendata::DataFormat;
+
+use super::*;
+
+fn dataset(samling: &str, format: &str, storlek: u64) -> DataSet {
+    let url = format!("/dataset/dokument/{samling}.{format}.zip");
+    serde_json::from_value(serde_json::json!({
+        "namn": "dokument",
+        "typ": samling.split('-').next().unwrap(),
+        "samling": samling,
+        "rm": "2018-2021",
+        "filnamn": format!("{samling}.{format}.zip"),
+        "storlek": storlek,
+        "format": format,
+        "filformat": "zip",
+        "uppdaterad": "2024-03-02 02:00:00",
+        "url": url,
+        "description": null,
+        "beskrivning": null,
+        "upplysning": null,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn plan_marks_downloaded_datasets_as_up_to_date() {
+    let datasetlista = DatasetLista {
+        dataset: vec![
+            dataset("prot-2018-2021", "json", 100),
+            dataset("bet-2018-2021", "json", 20),
+            dataset("mot-2018-2021", "json", 3),
+            dataset("prot-2018-2021", "xml", 4000),
+        ],
+    };
+    let query = DatasetQuery {
+        format: vec![DataFormat::Json],
+        ..Default::default()
+    };
+    let mut metadata = Metadata::default();
+    metadata.mark_as_updated(
+        "/dataset/dokument/bet-2018-2021.json.zip",
+        Path::new("bet-2018-2021.json.zip"),
+    );
+
+    let plan = RdPlan::new(
+        &datasetlista,
+        &query,
+        &metadata,
+        &mut Budget::default(),
+        None,
+    );
+
+    let stale: Vec<_> = plan
+        .stale()
+        .map(|dataset| dataset.samling.as_str())
+        .collect();
+    assert_eq!(stale, ["prot-2018-2021", "mot-2018-2021"]);
+    assert_eq!(plan.download_bytes(), 103);
+    assert_eq!(plan.datasets.len(), 3);
+    assert!(plan
+        .to_string()
+        .ends_with("2 of 3 matching datasets are stale, 103 bytes to download"));
+}
+
+#[test]
+fn plan_defers_datasets_over_the_download_limit() {
+    let datasetlista = DatasetLista {
+        dataset: vec![
+            dataset("mot-2018-2021", "json", 30),
+            dataset("prot-2018-2021", "json", 100),
+            dataset("bet-2018-2021", "json", 10),
+        ],
+    };
+    let mut budget = Budget::new(Some(50), None);
+
+    let plan = RdPlan::new(
+        &datasetlista,
+        &DatasetQuery::default(),
+        &Metadata::default(),
+        &mut budget,
+        None,
+    );
+
+    let samlingar = |datasets: Vec<&DataSet>| {
+        datasets
+            .into_iter()
+            .map(|dataset| dataset.samling.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(samlingar(plan.stale().collect()), ["mot-2018-2021"]);
+    assert_eq!(
+        samlingar(plan.deferred().collect()),
+        ["prot-2018-2021", "bet-2018-2021"]
+    );
+    assert_eq!(plan.download_bytes(), 30);
+    assert!(plan.to_string().ends_with(
+        "3 of 3 matching datasets are stale, 30 bytes to download, 2 deferred by download limits"
+    ));
+}
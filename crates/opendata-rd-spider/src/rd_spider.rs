@@ -9,15 +9,24 @@ use std::{
 use async_trait::async_trait;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use reqwest::Client;
-use swegov_opendata::{DataFormat, DatasetLista};
+use swegov_opendata::{DataFormat, DataSet, DatasetLista, DatasetQuery};
 use tokio::{io::AsyncWriteExt, sync::RwLock};
 
-use crate::{Error, Item};
+use crate::{
+    budget::{self, Budget},
+    Error, Item, RdPlan,
+};
 
 #[derive(Debug, Clone)]
 pub struct RdSpiderOptions {
     pub user_agent: Option<String>,
     pub output_path: PathBuf,
+    /// Only fetch datasets that match this query.
+    pub query: DatasetQuery,
+    /// Stop before the datasets of a crawl add up to more than this many bytes.
+    pub max_download_bytes: Option<u64>,
+    /// Stop before the free disk space of `output_path` would drop below this many bytes.
+    pub min_free_disk_bytes: Option<u64>,
 }
 
 impl Default for RdSpiderOptions {
@@ -25,6 +34,12 @@ impl Default for RdSpiderOptions {
         Self {
             user_agent: None,
             output_path: PathBuf::from("./output"),
+            query: DatasetQuery {
+                format: vec![DataFormat::Json],
+                ..Default::default()
+            },
+            max_download_bytes: None,
+            min_free_disk_bytes: None,
         }
     }
 }
@@ -32,7 +47,9 @@ impl Default for RdSpiderOptions {
 pub struct RdSpider {
     http_client: Client,
     output_path: PathBuf,
+    query: DatasetQuery,
     metadata: Arc<RwLock<Metadata>>,
+    budget: Arc<RwLock<Budget>>,
 }
 
 #[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -122,6 +139,9 @@ impl RdSpider {
         RdSpiderOptions {
             user_agent: user_agent_opt,
             output_path,
+            query,
+            max_download_bytes,
+            min_free_disk_bytes,
         }: RdSpiderOptions,
     ) -> Result<Self, Error> {
         let user_agent = user_agent_opt.as_deref().unwrap_or(crate::APP_USER_AGENT);
@@ -139,23 +159,118 @@ impl RdSpider {
                 })?;
         tracing::warn!(user_agent, "configuring SfsSpider {:?}", output_path);
         let metadata = Metadata::open_or_default(&Self::metadata_path(&output_path))?;
-        let http_client = reqwest::Client::builder()
-            .user_agent(user_agent)
-            .gzip(true)
-            .build()?;
+        let http_client = Self::http_client(user_agent)?;
 
         Ok(Self {
             http_client,
             output_path,
+            query,
             metadata: Arc::new(RwLock::new(metadata)),
+            budget: Arc::new(RwLock::new(Budget::new(
+                max_download_bytes,
+                min_free_disk_bytes,
+            ))),
         })
     }
 
+    /// Fetch the catalogue and report what a crawl with `options` would download,
+    /// and which datasets it would defer because of the download limits.
+    ///
+    /// Nothing is written, not even the output folder.
+    pub async fn plan(
+        RdSpiderOptions {
+            user_agent,
+            output_path,
+            query,
+            max_download_bytes,
+            min_free_disk_bytes,
+        }: RdSpiderOptions,
+    ) -> Result<RdPlan, Error> {
+        let user_agent = user_agent.as_deref().unwrap_or(crate::APP_USER_AGENT);
+        let http_client = Self::http_client(user_agent)?;
+        let metadata = Metadata::open_or_default(&Self::metadata_path(&output_path))?;
+        let response = Self::get(&http_client, Self::START_URL).await?;
+        let datasetlista = Self::parse_datasetlista(response.text().await?)?;
+        let mut budget = Budget::new(max_download_bytes, min_free_disk_bytes);
+        // The output folder may not exist yet, so measure the folder it would be created in
+        let existing_path = output_path
+            .ancestors()
+            .find(|path| path.exists())
+            .unwrap_or(&output_path);
+        let free_disk_bytes = Self::free_disk_bytes(existing_path, &budget);
+        Ok(RdPlan::new(
+            &datasetlista,
+            &query,
+            &metadata,
+            &mut budget,
+            free_disk_bytes,
+        ))
+    }
+
+    fn http_client(user_agent: &str) -> Result<Client, Error> {
+        Ok(reqwest::Client::builder()
+            .user_agent(user_agent)
+            .gzip(true)
+            .build()?)
+    }
+
+    async fn get(http_client: &Client, url: &str) -> Result<reqwest::Response, Error> {
+        tracing::debug!("calling {}", url);
+        let response = http_client.get(url).send().await?;
+        tracing::trace!("response status: {}", response.status());
+
+        if !response.status().is_success() {
+            let status_code = response.status();
+            let text = response.text().await?;
+
+            tracing::error!(
+                response.status = ?status_code,
+                response.text = text,
+                url = url,
+                "A request returned non-successful status"
+            );
+            return Err(Error::RequestReturnedError(status_code));
+        }
+        Ok(response)
+    }
+
+    fn parse_datasetlista(text: String) -> Result<DatasetLista, Error> {
+        let text = text.replace("\r\n", "");
+        yaserde::de::from_str(&text).map_err(|msg| Error::CouldNotParseXml { src: text, msg })
+    }
+
+    /// The free disk space of `path`, if the budget needs it.
+    fn free_disk_bytes(path: &Path, budget: &Budget) -> Option<u64> {
+        if !budget.checks_disk() {
+            return None;
+        }
+        match budget::free_disk_bytes(path) {
+            Ok(free_disk_bytes) => Some(free_disk_bytes),
+            Err(error) => {
+                tracing::warn!(%error, "could not measure free disk space, not checking it");
+                None
+            }
+        }
+    }
+
+    /// The datasets that were not fetched because they would exceed a limit.
+    pub async fn deferred(&self) -> Vec<DataSet> {
+        self.budget.read().await.deferred().to_vec()
+    }
+
     pub async fn close(&self) -> Result<(), Error> {
         self.metadata
             .read()
             .await
             .write(&Self::metadata_path(&self.output_path))?;
+        for dataset in self.budget.read().await.deferred() {
+            tracing::warn!(
+                samling = dataset.samling,
+                url = dataset.url,
+                storlek = dataset.storlek_bytes,
+                "dataset deferred by download limits"
+            );
+        }
         Ok(())
     }
 }
@@ -181,41 +296,24 @@ impl webcrawler::Spider for RdSpider {
 
     #[tracing::instrument]
     async fn scrape(&self, url: String) -> Result<(Vec<Self::Item>, Vec<String>), Self::Error> {
-        tracing::debug!("calling {}", url);
-        let response = self.http_client.get(&url).send().await?;
-        tracing::trace!("response status: {}", response.status());
-
-        if !response.status().is_success() {
-            let status_code = response.status();
-            let text = response.text().await?;
-
-            tracing::error!(
-                response.status = ?status_code,
-                response.text = text,
-                url = url,
-                "A request returned non-successful status"
-            );
-            return Err(Error::RequestReturnedError(status_code));
-        }
+        let response = Self::get(&self.http_client, &url).await?;
 
         let mut new_urls = Vec::new();
         let mut items = Vec::new();
         if url == Self::START_URL {
-            let text = response.text().await?;
-
-            let text = text.replace("\r\n", "");
-            let DatasetLista { dataset } = yaserde::de::from_str(&text)
-                .map_err(|msg| Error::CouldNotParseXml { src: text, msg })?;
-            for dataset in dataset {
-                if dataset.format == DataFormat::Json
-                    && self
-                        .metadata
-                        .read()
-                        .await
-                        .should_be_updated(&dataset.url, dataset.uppdaterad.as_inner())
+            let datasetlista = Self::parse_datasetlista(response.text().await?)?;
+            let mut budget = self.budget.write().await;
+            let free_disk_bytes = Self::free_disk_bytes(&self.output_path, &budget);
+            for dataset in datasetlista.select(&self.query) {
+                if self
+                    .metadata
+                    .read()
+                    .await
+                    .should_be_updated(&dataset.url, dataset.uppdaterad.as_inner())
+                    && budget.reserve(dataset, free_disk_bytes)
                 {
                     new_urls.push(format!("{}{}", Self::BASE_URL, dataset.url));
-                    items.push(Item::Metadata(dataset));
+                    items.push(Item::Metadata(dataset.clone()));
                 }
             }
         } else {
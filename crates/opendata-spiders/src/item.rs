@@ -1,12 +1,12 @@
-use swegov_opendata::{DokumentLista, DokumentStatus};
+use swegov_opendata::{DokumentLista, DokumentStatusPageRef};
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "snake_case")]
 #[allow(clippy::large_enum_variant)]
 pub enum Item {
+    /// A `dokumentstatus` page in JSON, kept as fetched so it is only borrowed when read.
     #[serde(rename = "dokumentstatus")]
-    // #[yaserde(rename = "dokumentstatus")]
-    DokumentStatus(DokumentStatus),
+    DokumentStatus(String),
     #[serde(rename = "dokumentlista")]
     // #[yaserde(rename = "dokumentlista")]
     DokumentLista(DokumentLista),
@@ -14,6 +14,21 @@ pub enum Item {
     Div(String),
 }
 
+impl Item {
+    /// Read a `dokumentstatus` page from the JSON text fetched from Riksdagen.
+    pub fn dokumentstatus(json: String) -> Result<Self, serde_json::Error> {
+        Self::parse_dokumentstatus(&json)?;
+        Ok(Self::DokumentStatus(json))
+    }
+
+    /// Borrow the `dokumentstatus` page from JSON text.
+    pub fn parse_dokumentstatus(
+        json: &str,
+    ) -> Result<DokumentStatusPageRef<'_>, serde_json::Error> {
+        serde_json::from_str(json.trim_start_matches('\u{feff}'))
+    }
+}
+
 impl yaserde::YaDeserialize for Item {
     fn deserialize<R: std::io::Read>(
         reader: &mut yaserde::de::Deserializer<R>,
@@ -23,9 +38,6 @@ impl yaserde::YaDeserialize for Item {
                 "dokumentlista" => {
                     return Ok(Item::DokumentLista(DokumentLista::deserialize(reader)?));
                 }
-                "dokumentstatus" => {
-                    return Ok(Item::DokumentStatus(DokumentStatus::deserialize(reader)?));
-                }
                 tag => return Err(format!("Unknown root '{tag}'")),
             }
         }
@@ -1,7 +1,13 @@
 use fs_err::PathExt;
-use std::{fmt::Debug, path::PathBuf};
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
 
 use async_trait::async_trait;
+use tracing::Instrument;
 
 use flate2::Compression;
 use reqwest::Client;
@@ -10,12 +16,16 @@ use reqwest::Client;
 use fs_err as fs;
 use ulid::Ulid;
 
+use swegov_opendata::{Riksmote, RiksmoteRange};
+
 use crate::item::Item;
 use crate::Error;
 
 pub struct SfsSpider {
     http_client: Client,
     output_path: PathBuf,
+    riksmote_range: RiksmoteRange,
+    keep_versions: bool,
 }
 
 impl SfsSpider {
@@ -24,6 +34,8 @@ impl SfsSpider {
         let SfsSpiderOptions {
             user_agent: user_agent_opt,
             output_path,
+            riksmote_range,
+            keep_versions,
         } = options;
         let user_agent = user_agent_opt.as_deref().unwrap_or(crate::APP_USER_AGENT);
         fs::create_dir_all(&output_path).expect("spiders/sfs: can't create output_path");
@@ -39,6 +51,8 @@ impl SfsSpider {
         Self {
             http_client,
             output_path,
+            riksmote_range,
+            keep_versions,
         }
     }
 }
@@ -59,6 +73,17 @@ impl Default for SfsSpider {
 pub struct SfsSpiderOptions {
     pub user_agent: Option<String>,
     pub output_path: PathBuf,
+    /// Only fetch documents from these riksmöten.
+    #[serde(default)]
+    pub riksmote_range: RiksmoteRange,
+    /// Keep the earlier versions of a document that has changed since it was last fetched,
+    /// see [`SfsSpider::versions_dir`], instead of overwriting them. On by default.
+    #[serde(default = "keep_versions_default")]
+    pub keep_versions: bool,
+}
+
+fn keep_versions_default() -> bool {
+    true
 }
 
 impl Default for SfsSpiderOptions {
@@ -66,9 +91,202 @@ impl Default for SfsSpiderOptions {
         Self {
             user_agent: None,
             output_path: "./output".into(),
+            riksmote_range: RiksmoteRange::default(),
+            keep_versions: keep_versions_default(),
         }
     }
 }
+
+impl SfsSpider {
+    /// The folder where the earlier versions of the document stored at `path` are kept.
+    ///
+    /// A document at `<output>/<typ>/<rm>/<name>.json.gz` keeps its versions in
+    /// `<output>/versions/<typ>/<rm>/<name>/`, named by a ULID of the time they were fetched,
+    /// so they sort from oldest to newest.
+    pub fn versions_dir(&self, path: &Path) -> Option<PathBuf> {
+        let relative = path.strip_prefix(&self.output_path).ok()?;
+        let name = relative.file_name()?.to_str()?.strip_suffix(".json.gz")?;
+        Some(
+            self.output_path
+                .join("versions")
+                .join(relative.parent()?)
+                .join(name),
+        )
+    }
+
+    /// Move the document at `path` to its versions folder, unless it holds the same `json`.
+    ///
+    /// Returns `false` if the document is unchanged and doesn't need to be written again.
+    async fn archive_version(&self, path: &Path, json: &[u8]) -> Result<bool, Error> {
+        if !fs_err::tokio::try_exists(path).await? {
+            return Ok(true);
+        }
+        let compressed = fs_err::tokio::read(path).await?;
+        let mut stored = Vec::new();
+        flate2::read::GzDecoder::new(compressed.as_slice()).read_to_end(&mut stored)?;
+        if stored == json {
+            return Ok(false);
+        }
+        let Some(versions_dir) = self.versions_dir(path) else {
+            return Err(Error::Internal(format!(
+                "no versions folder for '{}'",
+                path.display()
+            )));
+        };
+        fs_err::tokio::create_dir_all(&versions_dir).await?;
+        let fetched = fs_err::tokio::metadata(path).await?.modified()?;
+        let version_path = versions_dir.join(format!("{}.json.gz", Ulid::from_datetime(fetched)));
+        tracing::info!(version = %version_path.display(), "keeping earlier version");
+        fs_err::tokio::rename(path, version_path).await?;
+        Ok(true)
+    }
+
+    /// Write `item` as gzipped JSON to `path`, keeping the earlier version of a document.
+    async fn write_output(&self, url: &str, path: &Path, item: &Item) -> Result<(), Error> {
+        let json = match item {
+            Item::DokumentStatus(json) => Cow::Borrowed(json.as_bytes()),
+            _ => Cow::Owned(serde_json::to_vec(item).inspect_err(|err| {
+                tracing_log_error::log_error!(err, url = url, "failed serializing JSON");
+            })?),
+        };
+        let is_dokumentstatus = matches!(item, Item::DokumentStatus(_));
+        if self.keep_versions && is_dokumentstatus {
+            let changed = self.archive_version(path, &json).await.inspect_err(|err| {
+                tracing_log_error::log_error!(err, url = url, "failed keeping earlier version");
+            })?;
+            if !changed {
+                tracing::info!("unchanged since last fetched");
+                return Ok(());
+            }
+        } else if is_dokumentstatus && fs_err::tokio::try_exists(path).await? {
+            tracing::warn!("overwriting the earlier version");
+        }
+        let mut compress_writer = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+        compress_writer.write_all(&json)?;
+        tracing::info!("writing JSON");
+        fs_err::tokio::write(path, compress_writer.finish()?)
+            .await
+            .inspect_err(|err| {
+                tracing_log_error::log_error!(err, url = url, "failed writing JSON");
+            })?;
+        Ok(())
+    }
+
+    /// Fetch the first `dokumentlista` page of every start url and report what a crawl with
+    /// `options` would download.
+    ///
+    /// Nothing is written, not even the output folder.
+    pub async fn plan(options: SfsSpiderOptions) -> Result<SfsPlan, Error> {
+        let SfsSpiderOptions {
+            user_agent,
+            riksmote_range,
+            ..
+        } = options;
+        let http_client = reqwest::Client::builder()
+            .user_agent(user_agent.as_deref().unwrap_or(crate::APP_USER_AGENT))
+            .gzip(true)
+            .build()?;
+        let mut plan = SfsPlan::default();
+        for (years, url) in start_urls_in(&riksmote_range) {
+            tracing::info!("calling {}", url);
+            let response = http_client.get(&url).send().await?;
+            if !response.status().is_success() {
+                return Err(Error::RequestReturnedError(response.status()));
+            }
+            let text = response.text().await?;
+            match yaserde::de::from_str(&text).map_err(|msg| Error::XmlDe { msg })? {
+                Item::DokumentLista(dokumentlista) => plan.pages.push(PlannedDokumentLista {
+                    years,
+                    traffar: dokumentlista.traffar(),
+                    sidor: dokumentlista.sidor(),
+                    url,
+                }),
+                _ => {
+                    return Err(Error::XmlDe {
+                        msg: format!("expected a dokumentlista from '{url}'"),
+                    })
+                }
+            }
+        }
+        Ok(plan)
+    }
+}
+
+/// What a crawl would download, without downloading it.
+#[derive(Debug, Clone, Default)]
+pub struct SfsPlan {
+    pub pages: Vec<PlannedDokumentLista>,
+}
+
+/// The first page of a `dokumentlista` a crawl would start from.
+#[derive(Debug, Clone)]
+pub struct PlannedDokumentLista {
+    pub years: RiksmoteRange,
+    pub url: String,
+    /// The number of documents in the list.
+    pub traffar: u64,
+    /// The number of pages in the list.
+    pub sidor: u64,
+}
+
+impl SfsPlan {
+    /// The estimated number of documents to download.
+    ///
+    /// Documents outside a riksmöte range that doesn't cover whole years are skipped
+    /// while crawling, so this can be too high.
+    pub fn traffar(&self) -> u64 {
+        self.pages.iter().map(|page| page.traffar).sum()
+    }
+}
+
+impl fmt::Display for SfsPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for page in &self.pages {
+            writeln!(
+                f,
+                "{}: {} documents on {} pages",
+                page.years, page.traffar, page.sidor
+            )?;
+        }
+        write!(f, "about {} documents to download", self.traffar())
+    }
+}
+
+/// The first `dokumentlista` page of every span of years that overlaps `riksmote_range`.
+fn start_urls_in(riksmote_range: &RiksmoteRange) -> Vec<(RiksmoteRange, String)> {
+    let base_url = "https://data.riksdagen.se/dokumentlista/?sok=&doktyp=SFS&rm=";
+    // let from_to = "&from=1880-01-01&tom=1900-12-31";
+    let base_suffix = "&ts=&bet=&tempbet=&nr=&org=&iid=&avd=&webbtv=&talare=&exakt=&planering=&facets=&sort=rel&sortorder=desc&rapport=&utformat=xml&a=s;#soktraff";
+    // "https://data.riksdagen.se/dokumentlista/?sok=&doktyp=SFS&rm=&from=1880-01-01&tom=1900-12-31&ts=&bet=&tempbet=&nr=&org=&iid=&avd=&webbtv=&talare=&exakt=&planering=&facets=&sort=rel&sortorder=desc&rapport=&utformat=xml&a=s#soktraff";
+    // let base_url = "https://data.riksdagen.se/dokumentlista/?sok=&doktyp=SFS&rm=&ts=&bet=&tempbet=&nr=&org=&iid=&avd=&webbtv=&talare=&exakt=&planering=&facets=&sort=rel&sortorder=desc&rapport=&utformat=xml&from=1890-01-01&tom=1899-12-31&a=s#soktraff";
+    // let base_url = "https://data.riksdagen.se/dokumentlista/?sok=&doktyp=SFS&rm=&ts=&bet=&tempbet=&nr=&org=&iid=&avd=&webbtv=&talare=&exakt=&planering=&facets=&sort=rel&sortorder=desc&rapport=&utformat=xml";
+    let mut urls = Vec::new();
+
+    for (from_year, to_year) in [
+        (1880, 1900),
+        (1901, 1920),
+        (1921, 1940),
+        (1941, 1960),
+        (1961, 1980),
+        (1981, 2000),
+        (2001, 2020),
+        (2021, 2025),
+    ] {
+        let years = RiksmoteRange::new(
+            Some(Riksmote::calendar_year(from_year)),
+            Some(Riksmote::calendar_year(to_year)),
+        );
+        if !riksmote_range.overlaps(&years) {
+            continue;
+        }
+        urls.push((
+            years,
+            format!("{base_url}&from={from_year}-01-01&tom={to_year}-12-31{base_suffix}"),
+        ))
+    }
+    urls
+}
+
 #[async_trait]
 impl webcrawler::Spider for SfsSpider {
     type Item = Item;
@@ -79,29 +297,10 @@ impl webcrawler::Spider for SfsSpider {
     }
 
     fn start_urls(&self) -> Vec<String> {
-        let base_url = "https://data.riksdagen.se/dokumentlista/?sok=&doktyp=SFS&rm=";
-        // let from_to = "&from=1880-01-01&tom=1900-12-31";
-        let base_suffix = "&ts=&bet=&tempbet=&nr=&org=&iid=&avd=&webbtv=&talare=&exakt=&planering=&facets=&sort=rel&sortorder=desc&rapport=&utformat=xml&a=s;#soktraff";
-        // "https://data.riksdagen.se/dokumentlista/?sok=&doktyp=SFS&rm=&from=1880-01-01&tom=1900-12-31&ts=&bet=&tempbet=&nr=&org=&iid=&avd=&webbtv=&talare=&exakt=&planering=&facets=&sort=rel&sortorder=desc&rapport=&utformat=xml&a=s#soktraff";
-        // let base_url = "https://data.riksdagen.se/dokumentlista/?sok=&doktyp=SFS&rm=&ts=&bet=&tempbet=&nr=&org=&iid=&avd=&webbtv=&talare=&exakt=&planering=&facets=&sort=rel&sortorder=desc&rapport=&utformat=xml&from=1890-01-01&tom=1899-12-31&a=s#soktraff";
-        // let base_url = "https://data.riksdagen.se/dokumentlista/?sok=&doktyp=SFS&rm=&ts=&bet=&tempbet=&nr=&org=&iid=&avd=&webbtv=&talare=&exakt=&planering=&facets=&sort=rel&sortorder=desc&rapport=&utformat=xml";
-        let mut urls = Vec::new();
-
-        for (from_year, to_year) in [
-            (1880, 1900),
-            (1901, 1920),
-            (1921, 1940),
-            (1941, 1960),
-            (1961, 1980),
-            (1981, 2000),
-            (2001, 2020),
-            (2021, 2025),
-        ] {
-            urls.push(format!(
-                "{base_url}&from={from_year}-01-01&tom={to_year}-12-31{base_suffix}"
-            ))
-        }
-        urls
+        start_urls_in(&self.riksmote_range)
+            .into_iter()
+            .map(|(_years, url)| url)
+            .collect()
     }
 
     #[tracing::instrument]
@@ -110,6 +309,7 @@ impl webcrawler::Spider for SfsSpider {
         let mut items = Vec::new();
 
         let dokument_url = "https://data.riksdagen.se/dokument";
+        let dokumentstatus_url = "https://data.riksdagen.se/dokumentstatus/";
         tracing::info!("calling {}", url);
         let response = self.http_client.get(&url).send().await.map_err(|err| {
             tracing_log_error::log_error!(err, url = url, "Failed fetching url");
@@ -132,10 +332,17 @@ impl webcrawler::Spider for SfsSpider {
             err
         })?;
         // println!("{}", text);
+        if url.starts_with(dokumentstatus_url) {
+            let item = Item::dokumentstatus(text).map_err(|err| {
+                tracing_log_error::log_error!(err, url = url, "Failed parsing JSON");
+                err
+            })?;
+            return Ok((vec![item], new_urls));
+        }
         let item: Item = match yaserde::de::from_str(&text) {
             Err(err) if url.contains("dokument/") => {
                 tracing::error!(error.msg = err, text = text, "Failed parsing XML");
-                let new_url = url.replace("dokument", "dokumentstatus");
+                let new_url = format!("{}.json", url.replace("dokument", "dokumentstatus"));
                 tracing::info!("Trying {} instead", new_url);
                 new_urls.push(new_url);
                 if text.starts_with("<div") {
@@ -157,6 +364,18 @@ impl webcrawler::Spider for SfsSpider {
                 new_urls.push(nasta_sida.clone());
             }
             for dokument in &dokumentlista.dokument {
+                if !self.riksmote_range.is_unbounded()
+                    && !dokument
+                        .riksmote()
+                        .is_ok_and(|rm| self.riksmote_range.contains(&rm))
+                {
+                    tracing::debug!(
+                        dok_id = dokument.dok_id,
+                        rm = dokument.rm(),
+                        "skipping document outside riksmöte range"
+                    );
+                    continue;
+                }
                 let dok_id = dokument.dok_id.as_str();
                 let new_url = format!("{dokument_url}/{dok_id}");
                 new_urls.push(new_url);
@@ -178,20 +397,20 @@ impl webcrawler::Spider for SfsSpider {
                 path.push("dokumentlista");
                 file_name = dokumentlista.q.as_str().replace('&', "_");
             }
-            Item::DokumentStatus(dokumentstatus) => {
-                let dokument_typ = dokumentstatus.dokument.typ
-                .as_str()
-                // .unwrap_or("NO_TYP")
-                ;
-                path.push(dokument_typ);
-                let dokument_rm = dokumentstatus.dokument.rm.as_str();
-                path.push(dokument_rm);
-
-                file_name = dokumentstatus
-                    .dokument
-                    .dok_id
-                    .as_str()
-                    .replace([' ', '.'], "_");
+            Item::DokumentStatus(json) => {
+                let page = Item::parse_dokumentstatus(json)?;
+                let dokument = &page.dokumentstatus.dokument;
+                path.push(dokument.typ);
+                path.push(dokument.rm);
+
+                file_name = match dokument.parse_beteckning() {
+                    Ok(beteckning) => beteckning.canonical_id(),
+                    Err(error) => {
+                        let dok_id = dokument.dok_id;
+                        tracing::warn!(%error, dok_id, "could not parse beteckning");
+                        dok_id.replace([' ', '.'], "_")
+                    }
+                };
             }
             _ => {
                 file_name = String::new();
@@ -209,24 +428,17 @@ impl webcrawler::Spider for SfsSpider {
                 format!("unknown-{}", Ulid::new()), // .replace(':', "")
                                                     // .replace([' ', '.', '/'], "_"),
             );
+        } else if matches!(item, Item::DokumentStatus(_)) {
+            path.push(&file_name);
         } else {
             path.push(Ulid::new().to_string());
-            // path.push(&file_name);
         }
         // let file_name = format!("{file_name}.json");
         path.set_extension("json.gz");
         let span = tracing::info_span!("writing output", "{}", path.display());
-        let _enter = span.enter();
-        tracing::info!("creating file");
-        let file = fs::File::create(&path).inspect_err(|err| {
-            tracing_log_error::log_error!(err, url = url, "failed creating file");
-        })?;
-        let compress_writer = flate2::write::GzEncoder::new(file, Compression::default());
-        let writer = std::io::BufWriter::new(compress_writer);
-        tracing::info!("writing JSON");
-        serde_json::to_writer(writer, &item).inspect_err(|err| {
-            tracing_log_error::log_error!(err, url = url, "failed writing JSON");
-        })?;
+        self.write_output(&url, &path, &item)
+            .instrument(span)
+            .await?;
         Ok(path.display().to_string())
     }
 }
@@ -5,13 +5,29 @@ use rstest::rstest;
 
 #[rstest]
 #[case::dokumentlista("assets/dokumentlista.xml")]
-#[case::dokumentstatus("assets/dokumentstatus.xml")]
 fn item_deserialize(#[case] filename: &str) -> anyhow::Result<()> {
     let source = fs::read_to_string(filename)?;
     let item: Item = yaserde::de::from_str(&source).unwrap();
-    assert!(matches!(
-        item,
-        Item::DokumentLista(_) | Item::DokumentStatus(_)
-    ));
+    assert!(matches!(item, Item::DokumentLista(_)));
     Ok(())
 }
+
+#[rstest]
+#[case::bet("assets/bet-2018-2021-h601au1.json")]
+fn item_dokumentstatus_keeps_fetched_json(#[case] filename: &str) -> anyhow::Result<()> {
+    let source = fs::read_to_string(filename)?;
+    let item = Item::dokumentstatus(source.clone())?;
+
+    let Item::DokumentStatus(json) = &item else {
+        panic!("expected a dokumentstatus, got {item:?}");
+    };
+    assert_eq!(json, &source);
+    let page = Item::parse_dokumentstatus(json)?;
+    assert_eq!(page.dokumentstatus.dokument.dok_id, "H601AU1");
+    Ok(())
+}
+
+#[test]
+fn item_dokumentstatus_fails_on_invalid_json() {
+    assert!(Item::dokumentstatus("<dokumentstatus/>".to_string()).is_err());
+}
@@ -1 +1,2 @@
 mod item;
+mod sfs;
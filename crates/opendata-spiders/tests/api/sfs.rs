@@ -0,0 +1,81 @@
+use std::{io::Read, path::Path};
+
+use fs_err as fs;
+
+use opendata_spiders::item::Item;
+use opendata_spiders::sfs::{SfsSpider, SfsSpiderOptions};
+use webcrawler::Spider;
+
+#[test]
+fn versions_dir_mirrors_document_path() -> anyhow::Result<()> {
+    let output_path = tempfile::tempdir()?;
+    let spider = SfsSpider::new(SfsSpiderOptions {
+        output_path: output_path.path().to_path_buf(),
+        keep_versions: true,
+        ..Default::default()
+    });
+    let output_path = fs::canonicalize(output_path.path())?;
+
+    assert_eq!(
+        spider.versions_dir(&output_path.join("SFS/1976/sfs-1976-257.json.gz")),
+        Some(output_path.join("versions/SFS/1976/sfs-1976-257"))
+    );
+    assert_eq!(
+        spider.versions_dir(&output_path.join("dokumentlista/sida.xml")),
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn start_urls_cover_year_spans_of_split_riksmoten() -> anyhow::Result<()> {
+    let output_path = tempfile::tempdir()?;
+    let spider = SfsSpider::new(SfsSpiderOptions {
+        output_path: output_path.path().to_path_buf(),
+        riksmote_range: "2020/21..".parse()?,
+        ..Default::default()
+    });
+
+    let urls = spider.start_urls();
+
+    assert_eq!(urls.len(), 2, "{urls:?}");
+    assert!(
+        urls[0].contains("&from=2001-01-01&tom=2020-12-31"),
+        "{urls:?}"
+    );
+    assert!(
+        urls[1].contains("&from=2021-01-01&tom=2025-12-31"),
+        "{urls:?}"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn process_keeps_earlier_versions_of_changed_documents() -> anyhow::Result<()> {
+    let output_path = tempfile::tempdir()?;
+    let spider = SfsSpider::new(SfsSpiderOptions {
+        output_path: output_path.path().to_path_buf(),
+        ..Default::default()
+    });
+    let url = "https://data.riksdagen.se/dokumentstatus/H601AU1.json".to_string();
+    let source = fs::read_to_string("assets/bet-2018-2021-h601au1.json")?;
+    let changed = source.replacen('{', "{ ", 1);
+
+    let path = spider
+        .process(url.clone(), Item::dokumentstatus(source.clone())?)
+        .await?;
+    spider
+        .process(url.clone(), Item::dokumentstatus(source)?)
+        .await?;
+    let versions_dir = spider.versions_dir(Path::new(&path)).unwrap();
+    assert!(!versions_dir.exists());
+
+    spider
+        .process(url, Item::dokumentstatus(changed.clone())?)
+        .await?;
+    assert_eq!(fs::read_dir(&versions_dir)?.count(), 1);
+    let mut stored = String::new();
+    flate2::read::GzDecoder::new(fs::File::open(&path)?).read_to_string(&mut stored)?;
+    assert_eq!(stored, changed);
+    Ok(())
+}
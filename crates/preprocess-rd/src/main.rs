@@ -2,8 +2,12 @@ use std::path::{Path, PathBuf};
 
 use clap::Parser;
 use preprocess_ui::ui::pretty::prepare_and_run;
-use swegov_opendata_preprocess::preprocess_rd::{
-    preprocess_rd_corpura, PreprocessRdCorpuraOptions,
+use swegov_opendata_preprocess::{
+    preprocess_rd::{preprocess_rd_corpura, PreprocessRdCorpuraOptions, UnknownTagPolicies},
+    shared::{
+        cleaning::CleanStepsPerCorpus,
+        ocr::{OcrRepair, Wordlist},
+    },
 };
 
 use crate::options::Args;
@@ -21,6 +25,16 @@ fn main() -> miette::Result<()> {
     let output = args
         .output
         .unwrap_or_else(|| PathBuf::from("./data/material"));
+    let personlista = args.personlista;
+    let tables = args.tables;
+    let clean_steps = CleanStepsPerCorpus::new(args.clean_steps, args.corpus_clean_steps);
+    let unknown_tags = UnknownTagPolicies::new(args.unknown_tags, args.unknown_tag);
+    let ocr_repair = args
+        .ocr_wordlist
+        .as_deref()
+        .map(Wordlist::from_path)
+        .transpose()?
+        .map(|wordlist| OcrRepair::new(wordlist).before_year(args.ocr_before_year));
     prepare_and_run(
         "preprocess-rd",
         trace,
@@ -37,6 +51,11 @@ fn main() -> miette::Result<()> {
                     corpura: &["rd-bet"],
                     skip_files: &[],
                     processed_json_path: Path::new("processed.json"),
+                    personlista_path: personlista.as_deref(),
+                    tables,
+                    clean_steps: &clean_steps,
+                    unknown_tags: &unknown_tags,
+                    ocr_repair: ocr_repair.as_ref(),
                     verbose,
                 },
             )
@@ -1,5 +1,14 @@
 use std::path::PathBuf;
 
+use swegov_opendata_preprocess::{
+    preprocess_rd::{TagPolicy, UnknownTagPolicy},
+    shared::{
+        cleaning::{CleanSteps, CorpusCleanSteps},
+        ocr::OcrRepair,
+        tables::TableMode,
+    },
+};
+
 #[derive(Debug, clap::Parser)]
 #[clap(author,version,about,long_about=None)]
 pub struct Args {
@@ -15,6 +24,45 @@ pub struct Args {
     #[clap(long, conflicts_with("verbose"))]
     pub no_verbose: bool,
 
+    /// A local copy of the person register (`personlista` as JSON) used to resolve speakers.
+    #[clap(long)]
+    pub personlista: Option<PathBuf>,
+
+    /// How tables are written: `flatten` keeps the text of the cells, `structure` writes
+    /// `<table>`, `<row>` and `<cell>` elements and `exclude` leaves the tables out.
+    #[clap(long, default_value_t = TableMode::Flatten)]
+    pub tables: TableMode,
+
+    /// The steps that clean the texts, as a comma separated list of `control-chars`, `nbsp`
+    /// (no-break spaces), `word-fields` (Word field codes), `nfc`, `ligatures`, `quotes` and
+    /// `dehyphenate`, or `none`.
+    #[clap(long, default_value_t = CleanSteps::RD)]
+    pub clean_steps: CleanSteps,
+
+    /// The cleaning steps for a single corpus, as `<corpus>=<steps>`, e.g.
+    /// `rd-prot=nfc,dehyphenate`. Can be given several times.
+    #[clap(long = "corpus-clean-steps")]
+    pub corpus_clean_steps: Vec<CorpusCleanSteps>,
+
+    /// How elements in the html that aren't known are handled: `unwrap` keeps their content,
+    /// `keep-text` keeps only their text, `skip` leaves them out and `error` stops.
+    #[clap(long, default_value_t = UnknownTagPolicy::Unwrap)]
+    pub unknown_tags: UnknownTagPolicy,
+
+    /// The policy for a single unknown tag, as `<tag>=<policy>`, e.g. `font=keep-text`.
+    /// Can be given several times.
+    #[clap(long = "unknown-tag")]
+    pub unknown_tag: Vec<TagPolicy>,
+
+    /// A Swedish wordlist, with one word per line, that turns on the repair of the OCR'd texts
+    /// of scanned documents. The repaired texts get an `ocr_quality` attribute.
+    #[clap(long)]
+    pub ocr_wordlist: Option<PathBuf>,
+
+    /// The documents from before this year are repaired, if `--ocr-wordlist` is given.
+    #[clap(long, default_value_t = OcrRepair::DEFAULT_BEFORE_YEAR)]
+    pub ocr_before_year: i32,
+
     /// The directory to read input files from.
     pub input: Option<PathBuf>,
     /// The directory to output preprocessed corpus.
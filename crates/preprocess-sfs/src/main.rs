@@ -2,8 +2,12 @@ mod options;
 
 use clap::Parser;
 use preprocess_ui::ui::pretty::prepare_and_run;
-use swegov_opendata_preprocess::preprocess_sfs::{
-    preprocess_sfs_corpus, PreprocessSfsCorpuraOptions,
+use swegov_opendata_preprocess::{
+    preprocess_sfs::{preprocess_sfs_corpus, PreprocessSfsCorpuraOptions},
+    shared::{
+        cleaning::CleanStepsPerCorpus,
+        ocr::{OcrRepair, Wordlist},
+    },
 };
 
 use crate::options::Args;
@@ -14,6 +18,14 @@ pub fn main() -> miette::Result<()> {
     let verbose = args.verbose;
     let input = args.input;
     let output = args.output;
+    let tables = args.tables;
+    let clean_steps = CleanStepsPerCorpus::new(args.clean_steps, args.corpus_clean_steps);
+    let ocr_repair = args
+        .ocr_wordlist
+        .as_deref()
+        .map(Wordlist::from_path)
+        .transpose()?
+        .map(OcrRepair::new);
     prepare_and_run(
         "preprocess-sfs",
         trace,
@@ -29,6 +41,9 @@ pub fn main() -> miette::Result<()> {
                 PreprocessSfsCorpuraOptions {
                     input: &input,
                     output: &output,
+                    tables,
+                    clean_steps: &clean_steps,
+                    ocr_repair: ocr_repair.as_ref(),
                 },
             )
         },
@@ -1,5 +1,10 @@
 use std::path::PathBuf;
 
+use swegov_opendata_preprocess::shared::{
+    cleaning::{CleanSteps, CorpusCleanSteps},
+    tables::TableMode,
+};
+
 #[derive(Debug, clap::Parser)]
 #[clap(author,version,about,long_about=None)]
 pub struct Args {
@@ -15,6 +20,29 @@ pub struct Args {
     #[clap(long, conflicts_with("verbose"))]
     pub no_verbose: bool,
 
+    /// How tables are written: `flatten` keeps the text of the cells but removes the tables of
+    /// scanned documents, `structure` writes `<table>`, `<row>` and `<cell>` elements and
+    /// `exclude` leaves the tables out.
+    #[clap(long, default_value_t = TableMode::Flatten)]
+    pub tables: TableMode,
+
+    /// The steps that clean the texts, as a comma separated list of `control-chars`, `nbsp`
+    /// (no-break spaces), `word-fields` (Word field codes), `nfc`, `ligatures`, `quotes` and
+    /// `dehyphenate`, or `none`.
+    #[clap(long, default_value_t = CleanSteps::SFS)]
+    pub clean_steps: CleanSteps,
+
+    /// The cleaning steps for a single corpus, as `<corpus>=<steps>`, e.g. `sfs=nfc,dehyphenate`.
+    /// Can be given several times.
+    #[clap(long = "corpus-clean-steps")]
+    pub corpus_clean_steps: Vec<CorpusCleanSteps>,
+
+    /// A Swedish wordlist, with one word per line, that turns on the repair of the OCR'd texts
+    /// of scanned documents, the ones split in pages. The repaired texts get an `ocr_quality`
+    /// attribute.
+    #[clap(long)]
+    pub ocr_wordlist: Option<PathBuf>,
+
     /// The directory to read input files from.
     pub input: PathBuf,
     /// The directory to output preprocessed corpus.
@@ -0,0 +1,74 @@
+mod options;
+
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use clap::Parser;
+use fs_err as fs;
+use miette::IntoDiagnostic;
+use swegov_opendata_preprocess::{
+    preprocess_sfs::diff_sfs_versions, schema_drift::SchemaDriftReport, shared::io_ext,
+};
+use tracing_subscriber::EnvFilter;
+
+use crate::options::{Args, Command};
+
+fn main() -> miette::Result<()> {
+    let args = Args::parse();
+
+    init_tracing()?;
+
+    match args.command {
+        Command::SchemaDrift { output, input } => schema_drift(&input, output.as_deref()),
+        Command::SfsDiff { old, new } => sfs_diff(&old, &new),
+    }
+}
+
+fn schema_drift(input: &Path, output: Option<&Path>) -> miette::Result<()> {
+    let report = SchemaDriftReport::scan_dir(input)?;
+    tracing::info!(
+        groups = report.groups.len(),
+        failed = report.failed.len(),
+        "scanned corpus"
+    );
+    match output {
+        Some(output) => {
+            let file = fs::File::create(output).into_diagnostic()?;
+            serde_json::to_writer_pretty(io::BufWriter::new(file), &report).into_diagnostic()?;
+        }
+        None => {
+            let mut stdout = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut stdout, &report).into_diagnostic()?;
+            writeln!(stdout).into_diagnostic()?;
+        }
+    }
+    Ok(())
+}
+
+fn sfs_diff(old: &Path, new: &Path) -> miette::Result<()> {
+    let old = io_ext::read_text(old).into_diagnostic()?;
+    let new = io_ext::read_text(new).into_diagnostic()?;
+    let changes = diff_sfs_versions(&old, &new)?;
+    tracing::info!(changes = changes.len(), "compared versions");
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &changes).into_diagnostic()?;
+    writeln!(stdout).into_diagnostic()?;
+    Ok(())
+}
+
+/// construct a subscriber that prints formatted traces to stderr
+fn init_tracing() -> miette::Result<()> {
+    // Unknown fields are logged for every document, the report collects them instead
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env()
+                .or_else(|_| EnvFilter::try_new("swegov_opendata=error,info"))
+                .expect("telemetry: Creating EnvFilter"),
+        )
+        .with_writer(io::stderr)
+        .finish();
+    tracing::subscriber::set_global_default(subscriber).into_diagnostic()?;
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Parser)]
+#[clap(author,version,about,long_about=None)]
+pub struct Args {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Command {
+    /// Report fields, values and parse failures in a harvested corpus that the models don't know.
+    SchemaDrift {
+        /// Write the report (as JSON) to this path instead of stdout.
+        #[clap(long, short = 'o')]
+        output: Option<PathBuf>,
+
+        /// The directory with `dokumentstatus` JSON, JSON.gz and zipped datasets.
+        input: PathBuf,
+    },
+    /// Report the paragrafer that differ between two fetched versions of an SFS document.
+    SfsDiff {
+        /// The earlier version, as `dokumentstatus` JSON or JSON.gz.
+        old: PathBuf,
+
+        /// The later version, as `dokumentstatus` JSON or JSON.gz.
+        new: PathBuf,
+    },
+}
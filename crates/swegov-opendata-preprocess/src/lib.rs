@@ -3,6 +3,7 @@ mod error;
 pub mod nodeinfo;
 pub mod preprocess_rd;
 pub mod preprocess_sfs;
+pub mod schema_drift;
 pub mod shared;
 
 pub use self::corpusinfo::corpusinfo;
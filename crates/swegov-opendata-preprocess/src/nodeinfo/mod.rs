@@ -1,3 +1,6 @@
 mod rcdom;
 
-pub use self::rcdom::{dbg_rcdom_node, rcdom_collect_texts, rcdom_text_len};
+pub use self::rcdom::{
+    dbg_rcdom_node, rcdom_attr, rcdom_block_text_content, rcdom_collect_texts, rcdom_elem_name,
+    rcdom_path, rcdom_text_content, rcdom_text_len,
+};
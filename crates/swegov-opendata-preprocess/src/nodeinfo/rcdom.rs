@@ -68,3 +68,119 @@ pub fn rcdom_text_len(node: &rcdom::Handle) -> usize {
         _ => 0,
     }
 }
+
+/// The local name of `node` if it is an element.
+pub fn rcdom_elem_name(node: &rcdom::Handle) -> Option<&str> {
+    match &node.data {
+        NodeData::Element { name, .. } => Some(&name.local),
+        _ => None,
+    }
+}
+
+/// The value of the attribute `attr_name` if `node` is an element that has it.
+pub fn rcdom_attr(node: &rcdom::Handle, attr_name: &str) -> Option<String> {
+    match &node.data {
+        NodeData::Element { attrs, .. } => attrs
+            .borrow()
+            .iter()
+            .find(|attr| &*attr.name.local == attr_name)
+            .map(|attr| attr.value.to_string()),
+        _ => None,
+    }
+}
+
+/// All text below `node`, concatenated in document order.
+pub fn rcdom_text_content(node: &rcdom::Handle) -> String {
+    let mut text = String::new();
+    push_text_content(node, &mut text);
+    text
+}
+
+fn push_text_content(node: &rcdom::Handle, text: &mut String) {
+    match &node.data {
+        NodeData::Text { contents } => text.push_str(&contents.borrow()),
+        _ => {
+            for child in node.children.borrow().iter() {
+                push_text_content(child, text);
+            }
+        }
+    }
+}
+
+/// All text below `node` in document order, with a space between the text of block-level
+/// elements so that e.g. `<p>a</p><p>b</p>` becomes "a b".
+pub fn rcdom_block_text_content(node: &rcdom::Handle) -> String {
+    let mut text = String::new();
+    push_block_text_content(node, &mut text, &mut false);
+    text
+}
+
+/// `separate` is set when the next text starts a new block.
+fn push_block_text_content(node: &rcdom::Handle, text: &mut String, separate: &mut bool) {
+    const BLOCK_ELEMENTS: &[&str] = &[
+        "address",
+        "blockquote",
+        "br",
+        "dd",
+        "div",
+        "dl",
+        "dt",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "hr",
+        "li",
+        "ol",
+        "p",
+        "pre",
+        "section",
+        "table",
+        "td",
+        "th",
+        "tr",
+        "ul",
+    ];
+    let is_block = rcdom_elem_name(node).is_some_and(|name| BLOCK_ELEMENTS.contains(&name));
+    *separate |= is_block;
+    match &node.data {
+        NodeData::Text { contents } => {
+            let contents = contents.borrow();
+            if contents.is_empty() {
+                return;
+            }
+            if std::mem::take(separate)
+                && !text.is_empty()
+                && !text.ends_with(char::is_whitespace)
+                && !contents.starts_with(char::is_whitespace)
+            {
+                text.push(' ');
+            }
+            text.push_str(&contents);
+        }
+        _ => {
+            for child in node.children.borrow().iter() {
+                push_block_text_content(child, text, separate);
+            }
+        }
+    }
+    *separate |= is_block;
+}
+
+/// The names of `node` and its ancestor elements, e.g. `html/body/div/p`.
+pub fn rcdom_path(node: &rcdom::Handle) -> String {
+    let mut names = Vec::new();
+    let mut curr = Some(node.clone());
+    while let Some(node) = curr {
+        if let Some(name) = rcdom_elem_name(&node) {
+            names.push(name.to_string());
+        }
+        let parent = node.parent.take();
+        node.parent.set(parent.clone());
+        curr = parent.and_then(|parent| parent.upgrade());
+    }
+    names.reverse();
+    names.join("/")
+}
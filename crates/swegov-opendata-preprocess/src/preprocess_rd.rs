@@ -1,10 +1,24 @@
 mod html;
+mod media;
+mod party;
+mod protocol;
 mod rd_corpura;
 mod rd_json;
 mod shared;
+mod speakers;
+mod unknown_tags;
 mod xml;
 
 pub use self::html::process_html;
+pub use self::media::{MediaManifest, MediaManifestEntry, MediaSegment};
 pub use self::rd_corpura::{preprocess_rd_corpura, PreprocessRdCorpuraOptions};
-pub use self::rd_json::{preprocess_json, PreprocessJsonError};
+pub use self::rd_json::{
+    preprocess_json, preprocess_json_reader, preprocess_json_with_context, PreprocessJsonContext,
+    PreprocessJsonError,
+};
+pub use self::speakers::SpeakerRegister;
+pub use self::unknown_tags::{
+    InvalidTagPolicy, TagPolicy, UnknownTag, UnknownTagExample, UnknownTagPolicies,
+    UnknownTagPolicy, UnknownTagReport, UnknownTagStats,
+};
 pub use self::xml::{preprocess_xml, XmlError};
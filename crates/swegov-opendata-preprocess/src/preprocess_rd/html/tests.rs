@@ -1,20 +1,270 @@
 use minidom_extension::minidom::Element;
 
-use super::{process_html, remove_cdata};
+use rstest::rstest;
+
+use super::{process_html, ProcessHtmlError};
+use crate::{
+    preprocess_rd::unknown_tags::{TagPolicy, UnknownTagPolicies, UnknownTagPolicy},
+    shared::tables::TableMode,
+};
 
 #[test]
 fn test_process_html_from_string() {
     let html = "Riksdagen<br>a) antar lag om sprängämnesprekursorer<br>b) antar lag om ändring i lagen (1996:701) om Tullverkets befogenheter vid Sveriges gräns mot ett annat land inom Europeiska unionen.<br>\r\n";
 
     let mut textelem = Element::bare("text", "");
-    process_html(html, &mut textelem).unwrap();
+    process_html(
+        html,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
+
+    insta::assert_debug_snapshot!(textelem);
+}
+
+#[test]
+fn test_process_html_skips_conditional_comments() {
+    let html = "<TD><!--[if lte IE 7]><P style=\"margin-left:1px;\"><![endif]--><![if ! IE]><P style=\"margin-left:1px;margin-top:12px;margin-right:-25px;margin-bottom:12px;\" class=\"p410 ft90\"><![endif]>No</P></TD>";
+
+    let mut textelem = Element::bare("text", "");
+    process_html(
+        html,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
+
+    assert_eq!(textelem.text(), "");
+    assert_eq!(
+        textelem.children().map(Element::text).collect::<Vec<_>>(),
+        ["No"]
+    );
+}
+
+#[test]
+fn test_process_html_decodes_entities() {
+    let html = "<p>&ndash; &oslash; &euro; &#228; &#xE4; &amp;ouml; &foo;</p>";
+
+    let mut textelem = Element::bare("text", "");
+    process_html(
+        html,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        textelem.children().map(Element::text).collect::<Vec<_>>(),
+        ["– ø € ä ä ö &foo;"]
+    );
+}
+
+#[test]
+fn test_process_html_keeps_last_item_of_rd_lista() {
+    let html = "<span class=\"rd_lista\">Totalt 2 motioner i listan. <br><div><b><a href=\"_H4022\">Motion 2016/17:2</a></b> av A (MP)<br></div><div><b><a href=\"_H4021\">Motion 2016/17:1</a></b> av B (MP)<br></div></span>";
+
+    let mut textelem = Element::bare("text", "");
+    process_html(
+        html,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
+
+    let motions: Vec<_> = textelem
+        .children()
+        .filter_map(|p| p.get_child("b", ""))
+        .map(Element::text)
+        .collect();
+    assert_eq!(motions, ["Motion 2016/17:2", "Motion 2016/17:1"]);
+}
+
+#[test]
+fn test_process_html_joins_inline_tags_in_word_sections() {
+    let html = "<div class=Section1>\r\n<p class=MsoNormal><span style='font-size:10.0pt'>Bevis skall\r\ntecknas<span style='text-transform:uppercase'>/iå </span>det\r\nena exemplaret.</span></p>\r\n</div>";
+
+    let mut textelem = Element::bare("text", "");
+    process_html(
+        html,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        textelem.children().map(Element::text).collect::<Vec<_>>(),
+        ["Bevis skall tecknas/iå det ena exemplaret."]
+    );
+}
+
+const TABLE_HTML: &str = "<P>Före</P><TABLE><TR><TH>Parti</TH><TH>Ja</TH></TR><TR><TD><P>S</P></TD><TD>100</TD></TR></TABLE><P>Efter</P>";
+
+#[test]
+fn test_process_html_drops_loose_text_of_divs() {
+    let html = "<div class=\"sidhuvud_publikation\">Motion till riksdagen</div><div><span>av X (S)</span><p>Text</p></div><div id=\"page_1\"><div><span>Sida</span></div></div>";
+
+    let mut textelem = Element::bare("text", "");
+    process_html(
+        html,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
+
+    let texts: Vec<_> = textelem.children().map(Element::text).collect();
+    assert_eq!(texts, ["Text", ""]);
+    let page = textelem.get_child("page", "").expect("a page");
+    assert_eq!(
+        page.children().map(Element::text).collect::<Vec<_>>(),
+        ["Sida"]
+    );
+}
+
+#[test]
+fn test_process_html_drops_self_closing_breaks_outside_paragraphs() {
+    let html = "a)<BR/>b)<BR/><BR/><br><p>c)<BR/>d)</p>";
+
+    let mut textelem = Element::bare("text", "");
+    process_html(
+        html,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
+
+    let breaks: Vec<_> = textelem
+        .children()
+        .map(|p| p.children().filter(|child| child.name() == "br").count())
+        .collect();
+    assert_eq!(breaks, [1, 1]);
+    assert_eq!(
+        textelem
+            .children()
+            .next()
+            .map(|p| p.texts().collect::<Vec<_>>()),
+        Some(vec!["a)", "b)", "", ""])
+    );
+}
+
+#[test]
+fn test_process_html_flattens_tables() {
+    let mut textelem = Element::bare("text", "");
+    process_html(
+        TABLE_HTML,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        textelem.children().map(Element::text).collect::<Vec<_>>(),
+        ["Före", "Parti", "Ja", "S", "100", "Efter"]
+    );
+}
+
+#[test]
+fn test_process_html_structures_tables() {
+    let mut textelem = Element::bare("text", "");
+    process_html(
+        TABLE_HTML,
+        &mut textelem,
+        TableMode::Structure,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
 
     insta::assert_debug_snapshot!(textelem);
 }
 
 #[test]
-fn test_remove_cdata() {
-    let text = "<P style=\"margin-left:1px;margin-top:12px;margin-right:-25px;margin-bottom:12px;\" class=\"p410 ft90\"><![endif]>No</P></TD>";
+fn test_process_html_excludes_tables() {
+    let mut textelem = Element::bare("text", "");
+    process_html(
+        TABLE_HTML,
+        &mut textelem,
+        TableMode::Exclude,
+        &UnknownTagPolicies::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        textelem.children().map(Element::text).collect::<Vec<_>>(),
+        ["Före", "Efter"]
+    );
+}
+
+/// `button` is an html element that the documents don't use, so it is unknown.
+const UNKNOWN_HTML: &str = "<P>Före <BUTTON>inne</BUTTON> efter <B>fet <BUTTON>x</BUTTON></B></P>\
+    <BUTTON><P>a</P><P>b</P></BUTTON>";
+
+#[rstest]
+#[case(UnknownTagPolicy::Unwrap, &["Före inne efter fet x", "a", "b"])]
+#[case(UnknownTagPolicy::KeepText, &["Före inne efter fet x", "a b"])]
+#[case(UnknownTagPolicy::Skip, &["Före efter fet"])]
+fn test_process_html_applies_unknown_tag_policy(
+    #[case] policy: UnknownTagPolicy,
+    #[case] expected: &[&str],
+) {
+    let mut textelem = Element::bare("text", "");
+    let found = process_html(
+        UNKNOWN_HTML,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::new(policy, []),
+    )
+    .unwrap();
+
+    let texts: Vec<_> = textelem
+        .children()
+        .map(|p| {
+            minidom_extension::minidom_collect_texts(p)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    assert_eq!(texts, expected);
+    let found: Vec<_> = found
+        .iter()
+        .map(|tag| (tag.tag.as_str(), tag.context, tag.policy))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("button", "process_html", policy),
+            ("button", "inline element", policy),
+            ("button", "process_html", policy),
+        ]
+    );
+}
+
+#[test]
+fn test_process_html_uses_policy_of_tag() {
+    let mut textelem = Element::bare("text", "");
+    let result = process_html(
+        UNKNOWN_HTML,
+        &mut textelem,
+        TableMode::Flatten,
+        &UnknownTagPolicies::new(
+            UnknownTagPolicy::Unwrap,
+            [TagPolicy {
+                tag: "button".to_string(),
+                policy: UnknownTagPolicy::Error,
+            }],
+        ),
+    );
 
-    assert_eq!(remove_cdata(text), "<P style=\"margin-left:1px;margin-top:12px;margin-right:-25px;margin-bottom:12px;\" class=\"p410 ft90\">No</P></TD>");
+    assert!(
+        matches!(result, Err(ProcessHtmlError::UnexpectedTag(_))),
+        "{result:?}"
+    );
 }
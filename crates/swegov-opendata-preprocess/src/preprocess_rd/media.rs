@@ -0,0 +1,106 @@
+use std::path::Path;
+
+use swegov_opendata::MediaRef;
+
+use crate::PreprocessError;
+
+use super::shared::write_json;
+
+/// Media records (webb-tv) collected while preprocessing a corpus.
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct MediaManifest {
+    media: Vec<MediaManifestEntry>,
+}
+
+impl MediaManifest {
+    pub fn is_empty(&self) -> bool {
+        self.media.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.media.len()
+    }
+
+    pub fn entries(&self) -> &[MediaManifestEntry] {
+        &self.media
+    }
+
+    pub fn push(&mut self, entry: MediaManifestEntry) {
+        self.media.push(entry);
+    }
+
+    /// Write the manifest as JSON to `path`.
+    pub fn write(&self, path: &Path) -> Result<(), PreprocessError> {
+        write_json(path, self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct MediaManifestEntry {
+    pub dok_id: String,
+    pub debatt_rm: String,
+    pub debatt_typ: String,
+    pub dok_beteckning: String,
+    pub datum: String,
+    pub url: String,
+    pub videofileurl: String,
+    pub audiofileurl: String,
+    pub downloadurl: String,
+    pub thumbnailurl: String,
+    pub debateurl: String,
+    pub debattsekunder: u64,
+    /// The anföranden that are part of this recording.
+    pub anforanden: Vec<MediaSegment>,
+}
+
+impl From<&MediaRef<'_>> for MediaManifestEntry {
+    fn from(media: &MediaRef<'_>) -> Self {
+        Self {
+            dok_id: media.dok_id.to_string(),
+            debatt_rm: media.debatt_rm.to_string(),
+            debatt_typ: media.debatt_typ.to_string(),
+            dok_beteckning: media.dok_beteckning.to_string(),
+            datum: media.datum.to_string(),
+            url: media.url.to_string(),
+            videofileurl: media.videofileurl.to_string(),
+            audiofileurl: media.audiofileurl.to_string(),
+            downloadurl: media.downloadurl.to_string(),
+            thumbnailurl: media.thumbnailurl.to_string(),
+            debateurl: media.debateurl.to_string(),
+            debattsekunder: media.debattsekunder,
+            anforanden: Vec::new(),
+        }
+    }
+}
+
+/// The part of a recording that belongs to one anförande.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct MediaSegment {
+    pub anf_id: Option<String>,
+    pub nummer: String,
+    pub talare: String,
+    /// Offset into the recording in seconds, taken from the fragment of `video_url`.
+    pub start: Option<u64>,
+    pub sekunder: u64,
+}
+
+/// Find the media record that belongs to `dok_id`.
+///
+/// `dok_id` is compared case-insensitively, since webbmedia and dokument
+/// don't always agree on the case (e.g. `H6C120190118zz`).
+pub fn find_media<'m, 'a>(media: &'m [MediaRef<'a>], dok_id: &str) -> Option<&'m MediaRef<'a>> {
+    let dok_id = dok_id.trim();
+    media
+        .iter()
+        .find(|media| media.dok_id.trim().eq_ignore_ascii_case(dok_id))
+}
+
+/// Parse the start offset from a `video_url` of the form `<video_id>#<seconds>`.
+pub fn video_start(video_url: &str) -> Option<u64> {
+    video_url
+        .rsplit_once('#')
+        .and_then(|(_, start)| start.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests;
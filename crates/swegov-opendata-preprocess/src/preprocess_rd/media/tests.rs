@@ -0,0 +1,53 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use swegov_opendata::WebbMediaRef;
+
+#[rstest]
+#[case("49CCCEB5-53E4-4B24-AF32-05C5E25D5676#49", Some(49))]
+#[case("deb29297#602", Some(602))]
+#[case("deb29297#0", Some(0))]
+#[case("deb29297", None)]
+#[case("", None)]
+fn video_start_parses_fragment(#[case] given: &str, #[case] expected: Option<u64>) {
+    assert_eq!(video_start(given), expected);
+}
+
+#[test]
+fn find_media_ignores_case() -> anyhow::Result<()> {
+    let source = r#"{
+        "media": {
+            "url": "VOD1/PAL169/2442210190028478521",
+            "videofileurl": "https://mamstream.riksdagen.se/VOD1/_definst_/smil:PAL169/2442210190028478521.smil/playlist.m3u8",
+            "audiofileurl": "https://mhdownload.riksdagen.se/VOD1/PAL169/2442210190028478521_aud.mp3",
+            "downloadurl": "https://mhdownload.riksdagen.se/VOD1/PAL169/2442210190028478521_480p.mp4",
+            "thumbnailurl": "https://mhdownload.riksdagen.se/posterframe/2442210190028478521.jpg",
+            "debateurl": "/sv/webb-tv/video/debatt",
+            "debattsekunder": "5093",
+            "debatt_rm": "2018/19",
+            "debatt_typ": "Kammaraktivitet",
+            "dok_beteckning": "",
+            "datum": "2019-01-18 09:00:00",
+            "dok_id": "H6C120190118zz",
+            "videostatus": "2",
+            "source": "Rim",
+            "inspelningstyp": "Kammarsystemet",
+            "systemdatum": "2022-10-19 13:03:07"
+        }
+    }"#;
+    let WebbMediaRef { media, .. } = serde_json::from_str(source)?;
+
+    let found = find_media(&media, "H6C120190118ZZ").map(MediaManifestEntry::from);
+
+    assert_eq!(
+        found.map(|entry| (entry.dok_id, entry.debattsekunder, entry.datum)),
+        Some((
+            "H6C120190118zz".to_string(),
+            5093,
+            "2019-01-18 09:00:00".to_string()
+        ))
+    );
+    assert!(find_media(&media, "H6C120190118").is_none());
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+
+/// A party code that maps to a canonical code while the code had this meaning.
+struct PartyRule {
+    code: &'static str,
+    /// First day the code had this meaning, `None` if it always had.
+    from: Option<NaiveDate>,
+    /// First day the code no longer had this meaning, `None` if still in use.
+    until: Option<NaiveDate>,
+    canonical: &'static str,
+}
+
+impl PartyRule {
+    const fn new(code: &'static str, canonical: &'static str) -> Self {
+        Self {
+            code,
+            from: None,
+            until: None,
+            canonical,
+        }
+    }
+
+    fn from(self, year: i32, month: u32, day: u32) -> Self {
+        Self {
+            from: NaiveDate::from_ymd_opt(year, month, day),
+            ..self
+        }
+    }
+
+    fn until(self, year: i32, month: u32, day: u32) -> Self {
+        Self {
+            until: NaiveDate::from_ymd_opt(year, month, day),
+            ..self
+        }
+    }
+
+    fn applies(&self, code: &str, date: NaiveDate) -> bool {
+        self.code.eq_ignore_ascii_case(code)
+            && self.from.is_none_or(|from| from <= date)
+            && self.until.is_none_or(|until| date < until)
+    }
+}
+
+/// Party codes used over the years, mapped to the code of the party today.
+static PARTY_RULES: Lazy<Vec<PartyRule>> = Lazy::new(|| {
+    vec![
+        PartyRule::new("s", "S"),
+        PartyRule::new("m", "M"),
+        // Högerpartiet became Moderata samlingspartiet in 1969.
+        PartyRule::new("h", "M").until(1969, 10, 1),
+        PartyRule::new("c", "C"),
+        // Bondeförbundet became Centerpartiet in 1957.
+        PartyRule::new("bf", "C").until(1958, 1, 1),
+        // Folkpartiet became Liberalerna in November 2015.
+        PartyRule::new("fp", "L").until(2015, 11, 22),
+        PartyRule::new("l", "L").from(2015, 11, 22),
+        // Kristen demokratisk samling and Kristdemokratiska samhällspartiet became
+        // Kristdemokraterna in 1996.
+        PartyRule::new("kds", "KD").until(1996, 6, 1),
+        PartyRule::new("kd", "KD"),
+        // Sveriges kommunistiska parti became Vänsterpartiet kommunisterna in 1967,
+        // which became Vänsterpartiet in 1990.
+        PartyRule::new("k", "V").until(1967, 6, 1),
+        PartyRule::new("vpk", "V")
+            .from(1967, 6, 1)
+            .until(1990, 6, 1),
+        PartyRule::new("v", "V"),
+        PartyRule::new("mp", "MP"),
+        PartyRule::new("sd", "SD"),
+        PartyRule::new("nyd", "NYD").from(1991, 2, 1),
+        PartyRule::new("-", "-"),
+    ]
+});
+
+/// The canonical code for the party `code`, as it was used at `date`.
+///
+/// Codes that aren't known at `date`, e.g. "TALMANNEN" or "k" after 1967, are kept as they are,
+/// since guessing would give a party that the code never meant.
+pub fn canonical_party(code: &str, date: NaiveDate) -> String {
+    let code = code.trim();
+    match PARTY_RULES.iter().find(|rule| rule.applies(code, date)) {
+        Some(rule) => rule.canonical.to_string(),
+        None => {
+            if !code.is_empty() {
+                tracing::debug!(code, %date, "unknown party code");
+            }
+            code.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
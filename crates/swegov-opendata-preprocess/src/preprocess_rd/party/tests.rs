@@ -0,0 +1,29 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+#[case("fp", "1998-03-12", "L")]
+#[case("FP", "2010-10-05", "L")]
+#[case("L", "2018-11-20", "L")]
+#[case("fp", "2019-01-18", "fp")]
+#[case("l", "1998-03-12", "l")]
+#[case("vpk", "1995-03-01", "vpk")]
+#[case("kds", "1992-01-14", "KD")]
+#[case("kds", "2002-05-02", "kds")]
+#[case("KD", "2002-05-02", "KD")]
+#[case("vpk", "1988-02-01", "V")]
+#[case("k", "1950-03-01", "V")]
+#[case("k", "1980-03-01", "k")]
+#[case("h", "1960-01-10", "M")]
+#[case("h", "1990-01-10", "h")]
+#[case("bf", "1950-01-10", "C")]
+#[case("mp", "1990-10-02", "MP")]
+#[case(" s ", "1990-10-02", "S")]
+#[case("-", "2021-06-21", "-")]
+#[case("TALMANNEN", "2019-01-18", "TALMANNEN")]
+fn canonical_party_uses_date(#[case] code: &str, #[case] date: &str, #[case] expected: &str) {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").expect("valid date");
+    assert_eq!(canonical_party(code, date), expected);
+}
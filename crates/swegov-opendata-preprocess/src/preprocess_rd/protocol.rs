@@ -0,0 +1,226 @@
+use chrono::NaiveDate;
+use minidom_extension::minidom::{Element, Node};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use swegov_opendata::DebattAnforandeRef;
+
+use super::{
+    party::canonical_party,
+    speakers::{set_speaker_attrs, SpeakerRegister},
+};
+
+/// A speaker header, e.g. "Anf. 12 ANNA LINDH (s):", that starts a speech in a protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpeakerHeader<'a> {
+    pub nummer: &'a str,
+    pub talare: &'a str,
+    pub parti: Option<&'a str>,
+}
+
+/// Parse a speaker header from the text of a paragraph.
+pub fn parse_speaker_header(text: &str) -> Option<SpeakerHeader<'_>> {
+    static SPEAKER_HEADER: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r"^Anf\.\s*(?P<nummer>\d+)\s+(?P<talare>[^():]+?)\s*(?:\((?P<parti>[^()]*)\))?(?:\s+replik)?\s*:",
+        )
+        .expect("valid regex")
+    });
+    let captures = SPEAKER_HEADER.captures(text.trim_start())?;
+    Some(SpeakerHeader {
+        nummer: captures.name("nummer")?.as_str(),
+        talare: captures.name("talare")?.as_str(),
+        parti: captures
+            .name("parti")
+            .map(|parti| parti.as_str().trim())
+            .filter(|parti| !parti.is_empty()),
+    })
+}
+
+/// Check if the text of a paragraph is an agenda item, e.g. "9 § Meddelande om kammarens arbete".
+pub fn is_section_heading(text: &str) -> bool {
+    static SECTION_HEADING: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^\d+\s*§\s+\S").expect("valid regex"));
+    SECTION_HEADING.is_match(text.trim_start())
+}
+
+/// Check if the text of a paragraph ends the speech before it, e.g. "Överläggningen var härmed
+/// avslutad." at the end of a debate or "Kammaren åtskildes kl. 16.32." at the end of the protocol.
+pub fn is_closing_line(text: &str) -> bool {
+    static CLOSING_LINE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r"^(?:\d+\s*§\s+)?(?:Överläggningen var (?:härmed )?avslutad|Kammaren åtskildes|(?:Kammarens )?[Ss]ammanträdet (?:avslutades|avslutat|avslutas))",
+        )
+        .expect("valid regex")
+    });
+    CLOSING_LINE.is_match(text.trim_start())
+}
+
+/// Wrap each speech in the (cleaned) protocol `textelem` in an `<anforande>` element.
+///
+/// A speech starts at a speaker header and ends at the next speaker header, agenda item or
+/// closing line.
+/// The speeches are the outer elements, so a speech that continues over a page break is one
+/// `<anforande>` with a `<page>` for each of its pages, and the text outside the speeches is
+/// kept in `<page>` elements of its own. A page split in several parts has one `<page>` for
+/// each part, the parts after the first get a `del` attribute with their number and an `id`
+/// with it as a suffix, e.g. `id="3-2"`, so the page ids are unique.
+/// When `anforanden` contains a record with the same number, its `anf_id` and
+/// `intressent_id` are added to the element. Speakers found in `speakers` also get
+/// `kon` and `fodd_ar`. The canonical party code is resolved for `datum`.
+pub fn split_speeches(
+    textelem: &mut Element,
+    anforanden: &[DebattAnforandeRef<'_>],
+    speakers: &SpeakerRegister,
+    datum: NaiveDate,
+) {
+    let mut splitter = SpeechSplitter {
+        anforanden,
+        speakers,
+        datum,
+        speaker: None,
+        open: None,
+        page: None,
+        page_parts: 0,
+        open_page: None,
+    };
+    let nodes = textelem.take_nodes();
+    splitter.split_nodes(textelem, nodes);
+    splitter.close(textelem);
+}
+
+struct SpeechSplitter<'s, 'a> {
+    anforanden: &'s [DebattAnforandeRef<'a>],
+    speakers: &'s SpeakerRegister,
+    datum: NaiveDate,
+    /// Template for the speech currently in progress.
+    speaker: Option<Element>,
+    /// The speech currently in progress.
+    open: Option<Element>,
+    /// Template for the page currently read.
+    page: Option<Element>,
+    /// The number of parts of the page currently read.
+    page_parts: u32,
+    /// The part of the current page in the open speech, or outside the speeches.
+    open_page: Option<Element>,
+}
+
+impl SpeechSplitter<'_, '_> {
+    fn split_nodes(&mut self, textelem: &mut Element, nodes: Vec<Node>) {
+        for node in nodes {
+            match node {
+                Node::Element(mut elem) if elem.name() == "page" => {
+                    self.close_page(textelem);
+                    let mut page = Element::bare("page", elem.ns());
+                    for (name, value) in elem.attrs() {
+                        page.set_attr(name, value);
+                    }
+                    self.page = Some(page);
+                    self.page_parts = 0;
+                    let nodes = elem.take_nodes();
+                    self.split_nodes(textelem, nodes);
+                    self.close_page(textelem);
+                    self.page = None;
+                }
+                Node::Element(elem) if elem.name() == "p" => {
+                    let text = elem.text();
+                    if let Some(header) = parse_speaker_header(&text) {
+                        self.close(textelem);
+                        self.speaker = Some(self.speaker_elem(&header));
+                    } else if is_section_heading(&text) || is_closing_line(&text) {
+                        self.close(textelem);
+                        self.speaker = None;
+                    }
+                    self.push(textelem, Node::Element(elem));
+                }
+                node => self.push(textelem, node),
+            }
+        }
+    }
+
+    fn push(&mut self, textelem: &mut Element, node: Node) {
+        if self.open.is_none() {
+            self.open = self.speaker.clone();
+        }
+        if self.open_page.is_none() {
+            if let Some(page) = &self.page {
+                self.page_parts += 1;
+                self.open_page = Some(page_part(page, self.page_parts));
+            }
+        }
+        match (&mut self.open_page, &mut self.open) {
+            (Some(page), _) => page.append_node(node),
+            (None, Some(open)) => open.append_node(node),
+            (None, None) => textelem.append_node(node),
+        }
+    }
+
+    /// Close the part of the current page, a following node opens a new one.
+    fn close_page(&mut self, textelem: &mut Element) {
+        if let Some(page) = self.open_page.take() {
+            match &mut self.open {
+                Some(open) => open.append_child(page),
+                None => textelem.append_child(page),
+            };
+        }
+    }
+
+    /// Close the open speech.
+    fn close(&mut self, textelem: &mut Element) {
+        self.close_page(textelem);
+        if let Some(open) = self.open.take() {
+            textelem.append_child(open);
+        }
+    }
+
+    fn speaker_elem(&self, header: &SpeakerHeader<'_>) -> Element {
+        let mut elem = Element::builder("anforande", "")
+            .attr("nummer", header.nummer)
+            .attr("talare", header.talare.trim())
+            .build();
+        let anforande = self
+            .anforanden
+            .iter()
+            .find(|anforande| anforande.anf_nummer.trim() == header.nummer);
+        let parti = header
+            .parti
+            .or_else(|| anforande.map(|anforande| anforande.parti.trim()))
+            .unwrap_or("");
+        elem.set_attr("parti", parti);
+        elem.set_attr("parti_kanonisk", canonical_party(parti, self.datum));
+        if let Some(anforande) = anforande {
+            elem.set_attr("anf_id", anforande.anf_id.map(str::trim).unwrap_or(""));
+            elem.set_attr(
+                "intressent_id",
+                anforande.intressent_id.map(str::trim).unwrap_or(""),
+            );
+        } else if !self.anforanden.is_empty() {
+            tracing::debug!(
+                nummer = header.nummer,
+                talare = header.talare,
+                "no debatt record found for speaker header"
+            );
+        }
+        if let Some(person) =
+            self.speakers
+                .resolve(elem.attr("intressent_id"), header.talare, Some(parti))
+        {
+            set_speaker_attrs(&mut elem, person);
+        }
+        elem
+    }
+}
+
+/// Part `del` of the page `page`, counted from 1.
+fn page_part(page: &Element, del: u32) -> Element {
+    let mut part = page.clone();
+    if del > 1 {
+        if let Some(id) = page.attr("id") {
+            part.set_attr("id", format!("{id}-{del}"));
+        }
+        part.set_attr("del", del);
+    }
+    part
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,302 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+#[case("Anf. 1 TALMANNEN:", "1", "TALMANNEN", None)]
+#[case("Anf. 12 LARS WERNER (v):", "12", "LARS WERNER", Some("v"))]
+#[case(
+    "Anf. 5 Statsrådet ANNA LINDH (s) replik:",
+    "5",
+    "Statsrådet ANNA LINDH",
+    Some("s")
+)]
+#[case("Anf. 3 ANDRE VICE TALMANNEN:", "3", "ANDRE VICE TALMANNEN", None)]
+#[case(
+    "Anf. 41 JOHAN PEHRSON (L): Fru talman! Jag yrkar bifall.",
+    "41",
+    "JOHAN PEHRSON",
+    Some("L")
+)]
+fn parse_speaker_header_finds_speaker(
+    #[case] given: &str,
+    #[case] nummer: &str,
+    #[case] talare: &str,
+    #[case] parti: Option<&str>,
+) {
+    assert_eq!(
+        parse_speaker_header(given),
+        Some(SpeakerHeader {
+            nummer,
+            talare,
+            parti
+        })
+    );
+}
+
+#[rstest]
+#[case("1 § Välkomstord")]
+#[case("Ärade ledamöter! Anf. 1 TALMANNEN:")]
+#[case("Anf. TALMANNEN:")]
+fn parse_speaker_header_ignores_other_text(#[case] given: &str) {
+    assert_eq!(parse_speaker_header(given), None);
+}
+
+#[rstest]
+#[case("Överläggningen var härmed avslutad.", true)]
+#[case("Kammaren åtskildes kl. 16.32.", true)]
+#[case("13 § Kammaren åtskildes kl. 11.19.", true)]
+#[case("Sammanträdet avslutades kl. 15.02.", true)]
+#[case("Herr talman! Överläggningen var härmed avslutad.", false)]
+#[case("Jag yrkar bifall.", false)]
+fn is_closing_line_finds_closing_lines(#[case] given: &str, #[case] expected: bool) {
+    assert_eq!(is_closing_line(given), expected);
+}
+
+#[rstest]
+#[case("1 § Välkomstord", true)]
+#[case("10 § Bordläggning", true)]
+#[case("enligt 15 kap. 1 § vallagen.", false)]
+fn is_section_heading_finds_agenda_items(#[case] given: &str, #[case] expected: bool) {
+    assert_eq!(is_section_heading(given), expected);
+}
+
+fn datum() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1990, 10, 2).expect("valid date")
+}
+
+fn elem_p(text: &str) -> Element {
+    Element::builder("p", "").append(text).build()
+}
+
+fn to_string(elem: &Element) -> anyhow::Result<String> {
+    let mut result = Vec::new();
+    elem.write_to(&mut result)?;
+    Ok(String::from_utf8(result)?)
+}
+
+#[test]
+fn split_speeches_wraps_speeches() -> anyhow::Result<()> {
+    let mut textelem = Element::builder("text", "")
+        .append(elem_p("1 § Välkomstord"))
+        .append(elem_p("Anf. 1 TALMANNEN:"))
+        .append(elem_p("Ärade ledamöter!"))
+        .append(elem_p("Anf. 2 LARS WERNER (v):"))
+        .append(elem_p("Herr talman!"))
+        .append(elem_p("2 § Bordläggning"))
+        .append(elem_p("Anmäldes och bordlades"))
+        .build();
+
+    split_speeches(&mut textelem, &[], &SpeakerRegister::default(), datum());
+
+    assert_eq!(
+        to_string(&textelem)?,
+        concat!(
+            r#"<text xmlns=""><p>1 § Välkomstord</p>"#,
+            r#"<anforande nummer="1" parti="" parti_kanonisk="" talare="TALMANNEN"><p>Anf. 1 TALMANNEN:</p><p>Ärade ledamöter!</p></anforande>"#,
+            r#"<anforande nummer="2" parti="v" parti_kanonisk="V" talare="LARS WERNER"><p>Anf. 2 LARS WERNER (v):</p><p>Herr talman!</p></anforande>"#,
+            r#"<p>2 § Bordläggning</p><p>Anmäldes och bordlades</p></text>"#
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn split_speeches_continues_speech_on_next_page() -> anyhow::Result<()> {
+    let mut textelem = Element::builder("text", "")
+        .append(
+            Element::builder("page", "")
+                .attr("number", "1")
+                .append(elem_p("Anf. 7 LARS WERNER (v):"))
+                .append(elem_p("Herr talman!"))
+                .build(),
+        )
+        .append(
+            Element::builder("page", "")
+                .attr("number", "2")
+                .append(elem_p("Jag yrkar bifall."))
+                .build(),
+        )
+        .build();
+
+    split_speeches(&mut textelem, &[], &SpeakerRegister::default(), datum());
+
+    assert_eq!(
+        to_string(&textelem)?,
+        concat!(
+            r#"<text xmlns="">"#,
+            r#"<anforande nummer="7" parti="v" parti_kanonisk="V" talare="LARS WERNER">"#,
+            r#"<page number="1"><p>Anf. 7 LARS WERNER (v):</p><p>Herr talman!</p></page>"#,
+            r#"<page number="2"><p>Jag yrkar bifall.</p></page>"#,
+            r#"</anforande>"#,
+            r#"</text>"#
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn split_speeches_reconciles_with_debatt() -> anyhow::Result<()> {
+    let source = r#"{
+        "dok_id": "H6C120190118zz",
+        "video_url": "FC74DC39-E71F-4688-903D-635B49B45236#0",
+        "debatt_typ": "Övrigt",
+        "debatt_id": "FC74DC39-E71F-4688-903D-635B49B45236",
+        "video_id": "FC74DC39-E71F-4688-903D-635B49B45236",
+        "parent_id": "FC74DC39-E71F-4688-903D-635B49B45236",
+        "debatt_titel": "",
+        "dok_intressent": "",
+        "dok_beteckning": "",
+        "anf_id": "BC3E4DD5-102E-4393-928E-FA5B105EC01D",
+        "anf_video_id": "BC3E4DD5-102E-4393-928E-FA5B105EC01D",
+        "anf_hangar_id": "5017575",
+        "anf_sekunder": "65",
+        "anf_typ": "talmansanförande",
+        "talare_kort": "Andreas Norlén",
+        "kon": "male",
+        "voteringspunkt": "",
+        "anf_datum": "2019-01-18 00:00:00",
+        "datumtid": "2019-01-18 09:00:05",
+        "anf_klockslag": "09:00:05",
+        "startpos": "0",
+        "systemdatum": "2022-05-10 23:32:37",
+        "anf_text": null,
+        "videostatus": "2",
+        "anf_beteckning": "2018/19:39-1",
+        "anf_nummer": "1",
+        "talare": "Talman Andreas Norlén",
+        "intressent_id": "46bb7675-2a79-4efa-8a0d-c513ad32a47d",
+        "parti": "TALMANNEN",
+        "anf_rm": "2018/19"
+    }"#;
+    let anforande: DebattAnforandeRef = serde_json::from_str(source)?;
+    let mut textelem = Element::builder("text", "")
+        .append(elem_p("Anf. 1 TALMANNEN:"))
+        .append(elem_p("Ärade ledamöter!"))
+        .build();
+
+    split_speeches(
+        &mut textelem,
+        &[anforande],
+        &SpeakerRegister::default(),
+        datum(),
+    );
+
+    let speech = textelem
+        .get_child("anforande", "")
+        .expect("a wrapped speech");
+    assert_eq!(
+        speech.attr("anf_id"),
+        Some("BC3E4DD5-102E-4393-928E-FA5B105EC01D")
+    );
+    assert_eq!(
+        speech.attr("intressent_id"),
+        Some("46bb7675-2a79-4efa-8a0d-c513ad32a47d")
+    );
+    assert_eq!(speech.attr("parti"), Some("TALMANNEN"));
+    assert_eq!(speech.attr("parti_kanonisk"), Some("TALMANNEN"));
+    Ok(())
+}
+
+#[test]
+fn split_speeches_keeps_text_around_speeches_in_pages() -> anyhow::Result<()> {
+    let mut textelem = Element::builder("text", "")
+        .append(
+            Element::builder("page", "")
+                .attr("number", "1")
+                .append(elem_p("1 § Välkomstord"))
+                .append(elem_p("Anf. 1 TALMANNEN:"))
+                .append(elem_p("Ärade ledamöter!"))
+                .build(),
+        )
+        .append(
+            Element::builder("page", "")
+                .attr("number", "2")
+                .append(elem_p("Välkomna."))
+                .append(elem_p("2 § Bordläggning"))
+                .build(),
+        )
+        .build();
+
+    split_speeches(&mut textelem, &[], &SpeakerRegister::default(), datum());
+
+    assert_eq!(
+        to_string(&textelem)?,
+        concat!(
+            r#"<text xmlns="">"#,
+            r#"<page number="1"><p>1 § Välkomstord</p></page>"#,
+            r#"<anforande nummer="1" parti="" parti_kanonisk="" talare="TALMANNEN">"#,
+            r#"<page del="2" number="1"><p>Anf. 1 TALMANNEN:</p><p>Ärade ledamöter!</p></page>"#,
+            r#"<page number="2"><p>Välkomna.</p></page>"#,
+            r#"</anforande>"#,
+            r#"<page del="2" number="2"><p>2 § Bordläggning</p></page>"#,
+            r#"</text>"#
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn split_speeches_gives_page_parts_unique_ids() -> anyhow::Result<()> {
+    let mut textelem = Element::builder("text", "")
+        .append(
+            Element::builder("page", "")
+                .attr("id", "1")
+                .append(elem_p("1 § Välkomstord"))
+                .append(elem_p("Anf. 1 TALMANNEN:"))
+                .append(elem_p("Ärade ledamöter!"))
+                .append(elem_p("Anf. 2 LARS WERNER (v):"))
+                .append(elem_p("Herr talman!"))
+                .build(),
+        )
+        .append(
+            Element::builder("page", "")
+                .attr("id", "2")
+                .append(elem_p("Jag yrkar bifall."))
+                .append(elem_p("2 § Bordläggning"))
+                .build(),
+        )
+        .build();
+
+    split_speeches(&mut textelem, &[], &SpeakerRegister::default(), datum());
+
+    let mut ids = Vec::new();
+    for child in textelem.children() {
+        let pages = match child.name() {
+            "anforande" => child.children().collect(),
+            _ => vec![child],
+        };
+        ids.extend(pages.into_iter().filter_map(|page| page.attr("id")));
+    }
+    assert_eq!(ids, ["1", "1-2", "1-3", "2", "2-2"]);
+    Ok(())
+}
+
+#[test]
+fn split_speeches_ends_speech_at_closing_lines() -> anyhow::Result<()> {
+    let mut textelem = Element::builder("text", "")
+        .append(elem_p("Anf. 2 LARS WERNER (v):"))
+        .append(elem_p("Herr talman!"))
+        .append(elem_p("Överläggningen var härmed avslutad."))
+        .append(elem_p("Propositionsordning gavs."))
+        .append(elem_p("Anf. 3 TALMANNEN:"))
+        .append(elem_p("Nästa sammanträde äger rum i morgon."))
+        .append(elem_p("Kammaren åtskildes kl. 16.32."))
+        .append(elem_p("Vid protokollet"))
+        .build();
+
+    split_speeches(&mut textelem, &[], &SpeakerRegister::default(), datum());
+
+    assert_eq!(
+        to_string(&textelem)?,
+        concat!(
+            r#"<text xmlns="">"#,
+            r#"<anforande nummer="2" parti="v" parti_kanonisk="V" talare="LARS WERNER"><p>Anf. 2 LARS WERNER (v):</p><p>Herr talman!</p></anforande>"#,
+            r#"<p>Överläggningen var härmed avslutad.</p><p>Propositionsordning gavs.</p>"#,
+            r#"<anforande nummer="3" parti="" parti_kanonisk="" talare="TALMANNEN"><p>Anf. 3 TALMANNEN:</p><p>Nästa sammanträde äger rum i morgon.</p></anforande>"#,
+            r#"<p>Kammaren åtskildes kl. 16.32.</p><p>Vid protokollet</p></text>"#
+        )
+    );
+    Ok(())
+}
@@ -1,4 +1,10 @@
-use std::{borrow::Cow, collections::HashMap, io::Read, path::Path, sync::atomic::Ordering};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io::{BufReader, Read},
+    path::Path,
+    sync::atomic::Ordering,
+};
 
 use fs_err as fs;
 
@@ -6,17 +12,47 @@ use once_cell::sync::Lazy;
 use preprocess_progress::prodash::{Count, NestedProgress, Progress};
 use regex::Regex;
 use sparv_extension::{make_corpus_config, SparvConfig, SparvMetadata, XmlSourceWriter};
+use swegov_opendata::DataSet;
 use zip::ZipArchive;
 
-use crate::{corpusinfo, preprocess_rd::xml::preprocess_xml, PreprocessError};
+use crate::{
+    corpusinfo,
+    preprocess_rd::xml::preprocess_xml,
+    shared::{
+        cleaning::{CleanSteps, CleanStepsPerCorpus},
+        io_ext::ReplacingReader,
+        ocr::OcrRepair,
+        tables::TableMode,
+    },
+    PreprocessError,
+};
 
-use super::shared::read_json_or_default;
+use super::{
+    media::MediaManifest,
+    rd_json::{preprocess_json_reader, PreprocessJsonContext},
+    shared::read_json_or_default,
+    speakers::SpeakerRegister,
+    unknown_tags::{UnknownTagPolicies, UnknownTagReport},
+};
+
+/// Invalid JSON that some documents contain instead of a value.
+const RESERVATIONSTEXT: &str = "{/* RESERVATIONSTEXT */}";
 
 #[derive(Debug, Clone)]
 pub struct PreprocessRdCorpuraOptions<'a> {
     pub corpura: &'a [&'a str],
     pub skip_files: &'a [&'a str],
     pub processed_json_path: &'a Path,
+    /// A local copy of the person register (`personlista`) used to resolve speakers.
+    pub personlista_path: Option<&'a Path>,
+    /// How the tables in the html are written.
+    pub tables: TableMode,
+    /// How the texts of each corpus are cleaned.
+    pub clean_steps: &'a CleanStepsPerCorpus,
+    /// How the elements in the html that aren't known are handled.
+    pub unknown_tags: &'a UnknownTagPolicies,
+    /// How the texts of scanned documents are repaired, if at all.
+    pub ocr_repair: Option<&'a OcrRepair>,
     pub verbose: bool,
 }
 
@@ -35,6 +71,11 @@ pub fn preprocess_rd_corpura(
         corpura,
         skip_files,
         processed_json_path,
+        personlista_path,
+        tables,
+        clean_steps,
+        unknown_tags,
+        ocr_repair,
         verbose,
     }: PreprocessRdCorpuraOptions<'_>,
 ) -> Result<(), PreprocessError> {
@@ -45,6 +86,19 @@ pub fn preprocess_rd_corpura(
     // Get previously processed data
     let mut processed_json: HashMap<String, HashMap<String, String>> =
         read_json_or_default(processed_json_path)?;
+    let speakers = match personlista_path {
+        Some(path) => {
+            let speakers = SpeakerRegister::from_path(path)?;
+            writeln!(
+                out,
+                "Read {} persons from {} ...",
+                speakers.len(),
+                path.display()
+            )?;
+            speakers
+        }
+        None => SpeakerRegister::default(),
+    };
 
     static CORPUS_RE: Lazy<Regex> =
         Lazy::new(|| Regex::new(r"(\S+)\s?-\d{4}-.+").expect("valid regex"));
@@ -85,6 +139,8 @@ pub fn preprocess_rd_corpura(
     );
     let count = progress.counter();
 
+    let mut media_manifests: HashMap<&str, MediaManifest> = HashMap::new();
+    let mut unknown_tag_report = UnknownTagReport::default();
     for zippath in zippaths {
         let zippath_name = zippath
             .file_name()
@@ -131,6 +187,15 @@ pub fn preprocess_rd_corpura(
         );
         make_corpus_config(&sparv_config, &output.join(corpus.id))?;
         let mut processed_zip_dict = processed_json.remove(zippath_name).unwrap_or_default();
+        let metadata = read_dataset_metadata(
+            &zippath.with_file_name(format!("{corpus_source_base}.metadata.json")),
+        )?;
+        if metadata.is_none() {
+            tracing::warn!(
+                archive = %zippath.display(),
+                "no dataset metadata found, the documents get no upplysning"
+            );
+        }
 
         let child_progress = progress.add_child("Building sparv source");
 
@@ -138,6 +203,14 @@ pub fn preprocess_rd_corpura(
             &mut processed_zip_dict,
             zippath_name,
             &zippath,
+            metadata.as_ref(),
+            media_manifests.entry(corpus.id).or_default(),
+            &speakers,
+            tables,
+            clean_steps.for_corpus(corpus.id),
+            unknown_tags,
+            &mut unknown_tag_report,
+            ocr_repair,
             verbose,
             &mut out,
             child_progress,
@@ -146,15 +219,73 @@ pub fn preprocess_rd_corpura(
         )?;
         count.fetch_add(1, Ordering::Relaxed);
     }
+    for (corpus_id, media_manifest) in media_manifests {
+        if media_manifest.is_empty() {
+            continue;
+        }
+        let media_manifest_path = output.join(corpus_id).join("media.json");
+        writeln!(
+            out,
+            "Writing {} media records to {} ...",
+            media_manifest.len(),
+            media_manifest_path.display()
+        )?;
+        media_manifest.write(&media_manifest_path)?;
+    }
+    if !unknown_tag_report.is_empty() {
+        let unknown_tag_report_path = output.join("unknown_tags.json");
+        writeln!(
+            out,
+            "Found {} unknown tags, writing them to {} ...",
+            unknown_tag_report.len(),
+            unknown_tag_report_path.display()
+        )?;
+        write!(out, "{unknown_tag_report}")?;
+        unknown_tag_report.write(&unknown_tag_report_path)?;
+    }
     Ok(())
 }
 
-#[tracing::instrument(skip(out, progress))]
+/// Read the `DataSet` that `fetch-rd` stores next to each downloaded zip file.
+fn read_dataset_metadata(path: &Path) -> Result<Option<DataSet>, PreprocessError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file_data =
+        fs::read_to_string(path).map_err(|error| PreprocessError::CouldNotReadFile {
+            path: path.to_path_buf(),
+            error,
+        })?;
+    let metadata =
+        serde_json::from_str(&file_data).map_err(|error| PreprocessError::CouldNotReadJson {
+            path: path.to_path_buf(),
+            error,
+        })?;
+    Ok(Some(metadata))
+}
+
+#[tracing::instrument(skip(
+    metadata,
+    media_manifest,
+    speakers,
+    unknown_tags,
+    unknown_tag_report,
+    out,
+    progress
+))]
 #[allow(clippy::too_many_arguments)]
 fn build_sparv_source(
     processed_zip_dict: &mut HashMap<String, String>,
     zippath_name: &str,
     zippath: &std::path::PathBuf,
+    metadata: Option<&DataSet>,
+    media_manifest: &mut MediaManifest,
+    speakers: &SpeakerRegister,
+    tables: TableMode,
+    clean_steps: CleanSteps,
+    unknown_tags: &UnknownTagPolicies,
+    unknown_tag_report: &mut UnknownTagReport,
+    ocr_repair: Option<&OcrRepair>,
     verbose: bool,
     out: &mut impl std::io::Write,
     mut progress: impl preprocess_progress::NestedProgress,
@@ -195,26 +326,52 @@ fn build_sparv_source(
             continue;
         }
 
-        let mut filecontents = String::new();
-        zipobj.read_to_string(&mut filecontents).map_err(|error| {
-            PreprocessError::CouldNotReadZipFile {
-                archive: zippath.to_path_buf(),
-                path: zipobj.name().into(),
+        let name = zipobj.name().to_string();
+        let xmlstring = if name.ends_with(".json") {
+            let reader = ReplacingReader::new(&mut zipobj, RESERVATIONSTEXT, r#""""#);
+            preprocess_json_reader(
+                BufReader::new(reader),
+                metadata,
+                PreprocessJsonContext {
+                    media_manifest: &mut *media_manifest,
+                    speakers,
+                    tables,
+                    clean_steps,
+                    unknown_tags,
+                    unknown_tag_report: &mut *unknown_tag_report,
+                    ocr_repair,
+                },
+            )
+            .map_err(|error| PreprocessError::RdPreprocessJsonError {
+                path: name.clone(),
                 error,
-            }
-        })?;
-
-        let filecontents = filecontents.replace("{/* RESERVATIONSTEXT */}", r#""""#);
-
-        let xmlstring =
-            preprocess_xml(&filecontents, Cow::from(zipobj.name())).map_err(|error| {
-                PreprocessError::XmlError {
-                    path: zipobj.name().into(),
+            })?
+        } else {
+            let mut filecontents = String::new();
+            zipobj.read_to_string(&mut filecontents).map_err(|error| {
+                PreprocessError::CouldNotReadZipFile {
+                    archive: zippath.to_path_buf(),
+                    path: name.clone(),
                     error,
                 }
             })?;
+            let filecontents = filecontents.replace(RESERVATIONSTEXT, r#""""#);
+            preprocess_xml(
+                &filecontents,
+                Cow::from(name.as_str()),
+                tables,
+                clean_steps,
+                unknown_tags,
+                unknown_tag_report,
+                ocr_repair,
+            )
+            .map_err(|error| PreprocessError::XmlError {
+                path: name.clone(),
+                error,
+            })?
+        };
         if xmlstring.is_empty() {
-            tracing::warn!("'{}' generated empty xml", zipobj.name());
+            tracing::warn!("'{}' generated empty xml", name);
             continue;
         }
         source_writer.write(xmlstring)?;
@@ -1,17 +1,107 @@
-use std::{collections::BTreeSet, fmt::Display, iter::Peekable};
+use std::{collections::BTreeSet, fmt::Display, io::BufRead, iter::Peekable};
 
+use chrono::NaiveDate;
 use itertools::Itertools;
-use minidom_extension::minidom::{quick_xml::Writer, Element, Error as MinidomError};
-use swegov_opendata::{DataSet, DokumentStatusPageRef, DokumentStatusRef};
+use minidom_extension::minidom::{quick_xml::Writer, Element, Error as MinidomError, Node};
+use swegov_opendata::{
+    streaming::{read_dokumentstatus, Payload, StreamError, StreamedDokumentStatus},
+    DataSet, DokumentStatusPageRef, DokumentStatusRef, MediaRef,
+};
 
-use crate::shared::{clean_element, io_ext, is_segreg};
+use crate::shared::{
+    clean_element,
+    cleaning::CleanSteps,
+    html::{unknown_entities, warn_unknown_entities},
+    is_segreg,
+    ocr::OcrRepair,
+    rm_start_year, set_beteckning_attrs,
+    tables::TableMode,
+};
 
-use super::html::{process_html, ProcessHtmlError};
+use super::{
+    html::{self, ProcessHtmlError},
+    media::{find_media, video_start, MediaManifest, MediaManifestEntry, MediaSegment},
+    party::canonical_party,
+    protocol::split_speeches,
+    speakers::{kon, set_speaker_attrs, SpeakerRegister},
+    unknown_tags::{UnknownTag, UnknownTagPolicies, UnknownTagReport},
+};
 
-#[tracing::instrument(skip(source, metadata))]
 pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, PreprocessJsonError> {
-    let source = io_ext::without_bom(source);
-    // tracing::trace!("source = {}", source);
+    preprocess_json_with_context(
+        source,
+        Some(metadata),
+        PreprocessJsonContext {
+            media_manifest: &mut MediaManifest::default(),
+            speakers: &SpeakerRegister::default(),
+            tables: TableMode::default(),
+            clean_steps: CleanSteps::default(),
+            unknown_tags: &UnknownTagPolicies::default(),
+            unknown_tag_report: &mut UnknownTagReport::default(),
+            ocr_repair: None,
+        },
+    )
+}
+
+/// State that is shared between the documents of a corpus.
+#[derive(Debug)]
+pub struct PreprocessJsonContext<'c> {
+    /// Collects the media records of the documents.
+    pub media_manifest: &'c mut MediaManifest,
+    /// Used to fill in missing speaker information, may be empty.
+    pub speakers: &'c SpeakerRegister,
+    /// How the tables in the html are written.
+    pub tables: TableMode,
+    /// How the texts are cleaned.
+    pub clean_steps: CleanSteps,
+    /// How the elements in the html that aren't known are handled.
+    pub unknown_tags: &'c UnknownTagPolicies,
+    /// Collects the unknown elements of the documents.
+    pub unknown_tag_report: &'c mut UnknownTagReport,
+    /// How the texts of scanned documents are repaired, if at all.
+    pub ocr_repair: Option<&'c OcrRepair>,
+}
+
+/// Preprocess `source` using the shared state in `context`.
+pub fn preprocess_json_with_context(
+    source: &str,
+    metadata: Option<&DataSet>,
+    context: PreprocessJsonContext<'_>,
+) -> Result<Vec<u8>, PreprocessJsonError> {
+    preprocess_json_reader(source.as_bytes(), metadata, context)
+}
+
+/// Preprocess the document read from `reader` using the shared state in `context`.
+///
+/// The `html` and `anf_text` fields are processed as soon as they are read, so only one
+/// raw text is held in memory at a time. The processed texts are kept until the whole document has
+/// been read, since the metadata they are combined with can come after them.
+///
+/// The `upplysning` of the dataset `metadata` is added to the main text, if there is one.
+#[tracing::instrument(skip(reader, metadata, context))]
+pub fn preprocess_json_reader(
+    reader: impl BufRead,
+    metadata: Option<&DataSet>,
+    context: PreprocessJsonContext<'_>,
+) -> Result<Vec<u8>, PreprocessJsonError> {
+    let PreprocessJsonContext {
+        media_manifest,
+        speakers,
+        tables,
+        clean_steps,
+        unknown_tags,
+        unknown_tag_report,
+        ocr_repair,
+    } = context;
+    let StreamedDokumentStatus {
+        metadata: page,
+        html,
+        mut anf_text,
+    } = read_dokumentstatus(reader, |payload| {
+        process_payload(payload, tables, clean_steps, unknown_tags)
+    })?;
+    let segreg =
+        is_segreg(page.as_str()) || html.iter().chain(anf_text.values()).any(|text| text.segreg);
     let DokumentStatusPageRef {
         dokumentstatus:
             DokumentStatusRef {
@@ -25,9 +115,34 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
                 dokaktivitet: _,
                 dokmotforslag,
                 dokutskottsforslag,
-                webbmedia: _,
+                webbmedia,
             },
-    } = serde_json::from_str(source)?;
+    } = page.page()?;
+    let datum = dokument.datum.date();
+    warn_unknown_entities(
+        dokument.dok_id,
+        html.iter()
+            .chain(anf_text.values())
+            .flat_map(|text| text.unknown_entities.iter().map(String::as_str)),
+    );
+    unknown_tag_report.record(
+        dokument.dok_id,
+        html.iter()
+            .chain(anf_text.values())
+            .flat_map(|text| text.unknown_tags.iter().cloned()),
+    );
+    let mut html_fields = HtmlFields {
+        dok_id: dokument.dok_id,
+        tables,
+        clean_steps,
+        unknown_tags,
+        found: Vec::new(),
+    };
+    let media = webbmedia
+        .map(|webbmedia| webbmedia.media)
+        .unwrap_or_default();
+    let mut media_entries: Vec<MediaManifestEntry> =
+        media.iter().map(MediaManifestEntry::from).collect();
 
     // Build docelem
     let mut docelem = Element::builder("dokument", "")
@@ -48,10 +163,9 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
         .attr("datatyp", "huvuddokument")
         .build();
 
-    textelem.set_attr("segreg", is_segreg(source).to_string());
+    textelem.set_attr("segreg", segreg.to_string());
     // text attributes
     for (name, value) in [
-        ("hangar_id", dokument.hangar_id),
         ("rm", dokument.rm),
         // ("beteckning", &dokument.beteckning),
         ("dokumentnamn", dokument.dokumentnamn),
@@ -60,13 +174,15 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
         // ("organ", &dokument.organ),
         ("nummer", dokument.nummer),
         ("slutnummer", dokument.slutnummer),
-        ("title", dokument.titel.as_str()),
+        ("title", dokument.titel.as_ref()),
         // ("status", &dokument.status),
     ] {
         textelem.set_attr(name, value.replace("\r\n", " "));
     }
+    textelem.set_attr("rm_start_year", rm_start_year(dokument.rm));
+    set_beteckning_attrs(&mut textelem, dokument.parse_beteckning());
     for (name, value_opt) in [
-        // ("hangar_id", &dokument.hangar_id),
+        ("hangar_id", &dokument.hangar_id),
         // ("rm", &dokument.rm),
         ("beteckning", &dokument.beteckning),
         // ("dokumentnamn", &dokument.dokumentnamn),
@@ -103,10 +219,13 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
     ] {
         textelem.set_attr(name, value.to_string());
     }
-    if let Some(upplysning) = &metadata.upplysning {
+    if let Some(upplysning) = metadata.and_then(|metadata| metadata.upplysning.as_ref()) {
         textelem.set_attr("upplysning", upplysning.upplysning());
         // todo!("Handle upplysning={:?}", upplysning);
     }
+    if let Some(media) = find_media(&media, dokument.dok_id) {
+        set_media_attrs(&mut textelem, media);
+    }
     if let Some(dokintressent) = dokintressent {
         let mut intressenter = BTreeSet::new();
         for dok_int in dokintressent.intressent {
@@ -118,8 +237,25 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
         let mut intressent_id = vec![];
         let mut roles = BTreeSet::new();
         let mut name_party_intressent_id_role = vec![];
+        let mut gender = vec![];
+        let mut birth_year = vec![];
 
         for dok_int in intressenter {
+            let mut dok_int_id = dok_int.intressent_id;
+            if !speakers.is_empty() {
+                let person =
+                    speakers.resolve(Some(dok_int.intressent_id), dok_int.namn, dok_int.partibet);
+                if let Some(person) = person.filter(|_| dok_int.intressent_id.trim().is_empty()) {
+                    dok_int_id = person.intressent_id.as_str();
+                }
+                gender.push(person.and_then(kon).unwrap_or(""));
+                birth_year.push(
+                    person
+                        .and_then(|person| person.fodd_ar)
+                        .map(|fodd_ar| fodd_ar.to_string())
+                        .unwrap_or_default(),
+                );
+            }
             name.push(dok_int.namn);
             party.insert(dok_int.partibet.unwrap_or(""));
             name_party.push(format!(
@@ -127,13 +263,13 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
                 dok_int.namn,
                 dok_int.partibet.unwrap_or("")
             ));
-            intressent_id.push(dok_int.intressent_id);
+            intressent_id.push(dok_int_id);
             roles.insert(dok_int.roll);
             name_party_intressent_id_role.push(format!(
                 "{} ({}), {}, {}",
                 dok_int.namn,
                 dok_int.partibet.unwrap_or(""),
-                dok_int.intressent_id,
+                dok_int_id,
                 dok_int.roll
             ));
         }
@@ -150,6 +286,10 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
             "intressent_parti",
             format_multi_value(party.iter().peekable()),
         );
+        textelem.set_attr(
+            "intressent_parti_kanonisk",
+            format_canonical_parties(party.iter().copied(), datum),
+        );
         textelem.set_attr(
             "intressent_id",
             format_multi_value(intressent_id.iter().peekable()),
@@ -158,23 +298,46 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
             "intressent_namn_parti_id_roll",
             format_multi_value(name_party_intressent_id_role.iter().peekable()),
         );
+        if !speakers.is_empty() {
+            textelem.set_attr(
+                "intressent_kon",
+                format_multi_value(gender.iter().peekable()),
+            );
+            textelem.set_attr(
+                "intressent_fodd_ar",
+                format_multi_value(birth_year.iter().peekable()),
+            );
+        }
     }
-    if let Some(html) = dokument.html() {
-        process_html(html, &mut textelem)?;
+    if let Some(html) = html {
+        html.append_to(&mut textelem);
     } else {
         tracing::warn!("The html field is empty");
     }
-    let textelem = clean_element(&textelem); //.expect("Cleaning should work");
+    let mut textelem = clean_element(&textelem, clean_steps); //.expect("Cleaning should work");
+
+    // All texts of a scanned document are repaired, so they all get an `ocr_quality`
+    let ocr_repair = ocr_repair.filter(|repair| repair.applies_to(dokument.rm));
+    if let Some(ocr_repair) = ocr_repair {
+        ocr_repair.repair(&mut textelem);
+    }
     if !(textelem.has_child("p", "") || textelem.has_child("page", "")) {
         tracing::warn!(docelem = ?docelem, textelem = ?textelem, "document contains no text");
     }
+    if dokument.typ == "prot" {
+        let anforanden = debatt
+            .as_ref()
+            .map(|debatt| debatt.anforande.as_slice())
+            .unwrap_or_default();
+        split_speeches(&mut textelem, anforanden, speakers, datum);
+    }
     // Add textelem as child to docelem
     docelem.append_child(textelem);
     // dbg!(&dokintressent);
 
     // Add anforande as separate texts
     if let Some(debatt) = debatt {
-        for anforande in debatt.anforande {
+        for (index, anforande) in debatt.anforande.into_iter().enumerate() {
             let mut textelem = Element::builder("text", "")
                 .attr("datatyp", "anforande")
                 .build();
@@ -193,11 +356,16 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
             ] {
                 textelem.set_attr(name, value.trim());
             }
+            textelem.set_attr("rm_start_year", rm_start_year(anforande.anf_rm));
+            textelem.set_attr(
+                "parti_kanonisk",
+                canonical_party(anforande.parti, anforande.datumtid.date()),
+            );
             for (name, value_opt) in [
                 ("id", anforande.anf_id),
                 ("debatt_id", anforande.debatt_id),
                 ("debatt_typ", anforande.debatt_typ),
-                ("debatt_titel", anforande.debatt_titel),
+                ("debatt_titel", anforande.debatt_titel.as_deref()),
                 ("dok_beteckning", anforande.dok_beteckning),
                 ("dok_id", anforande.dok_id),
                 ("dok_intressent", anforande.dok_intressent),
@@ -220,12 +388,40 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
             ] {
                 textelem.set_attr(name, value.to_string());
             }
-            if let Some(text) = &anforande.anf_text {
-                process_html(text, &mut textelem)?;
+            if let Some(person) = speakers.resolve(
+                anforande.intressent_id,
+                anforande.talare,
+                Some(anforande.parti),
+            ) {
+                set_speaker_attrs(&mut textelem, person);
+            }
+            if let Some(media) = anforande
+                .dok_id
+                .and_then(|dok_id| find_media(&media, dok_id))
+            {
+                set_media_attrs(&mut textelem, media);
+                if let Some(entry) = media_entries
+                    .iter_mut()
+                    .find(|entry| entry.dok_id == media.dok_id)
+                {
+                    entry.anforanden.push(MediaSegment {
+                        anf_id: anforande.anf_id.map(|s| s.trim().to_string()),
+                        nummer: anforande.anf_nummer.trim().to_string(),
+                        talare: anforande.talare.trim().to_string(),
+                        start: video_start(anforande.video_url),
+                        sekunder: anforande.anf_sekunder,
+                    });
+                }
+            }
+            if let Some(text) = anf_text.remove(&index) {
+                text.append_to(&mut textelem);
             } else {
                 tracing::warn!(anforande.anf_id, "The field 'anf_text' is empty");
             }
-            let textelem = clean_element(&textelem);
+            let mut textelem = clean_element(&textelem, clean_steps);
+            if let Some(ocr_repair) = ocr_repair {
+                ocr_repair.repair(&mut textelem);
+            }
             // Add textelem as child to docelem
             docelem.append_child(textelem);
         }
@@ -256,11 +452,14 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
             ] {
                 textelem.set_attr(name, value_opt.map(|s| s.trim()).unwrap_or(""));
             }
-            process_html(forslag.lydelse.as_ref(), &mut textelem)?;
-            if let Some(text) = forslag.lydelse2 {
-                process_html(text, &mut textelem)?;
+            html_fields.process(forslag.lydelse.as_ref(), &mut textelem)?;
+            if let Some(text) = &forslag.lydelse2 {
+                html_fields.process(text, &mut textelem)?;
+            }
+            let mut textelem = clean_element(&textelem, clean_steps);
+            if let Some(ocr_repair) = ocr_repair {
+                ocr_repair.repair(&mut textelem);
             }
-            let textelem = clean_element(&textelem);
             // Add textelem as child to docelem
             docelem.append_child(textelem);
         }
@@ -279,9 +478,12 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
                 textelem.set_attr("systemdatum", systemdatum.to_string());
             }
             if let Some(text) = uppgift.text {
-                process_html(&text, &mut textelem)?;
+                html_fields.process(&text, &mut textelem)?;
+            }
+            let mut textelem = clean_element(&textelem, clean_steps);
+            if let Some(ocr_repair) = ocr_repair {
+                ocr_repair.repair(&mut textelem);
             }
-            let textelem = clean_element(&textelem);
             // Add textelem as child to docelem
             docelem.append_child(textelem);
         }
@@ -306,6 +508,7 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
             ] {
                 textelem.set_attr(name, value.trim());
             }
+            textelem.set_attr("rm_start_year", rm_start_year(utskottsforslag.rm));
             for (name, value_opt) in [
                 ("beteckning", utskottsforslag.beteckning),
                 ("beslut", utskottsforslag.beslut),
@@ -329,21 +532,29 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
                 textelem.set_attr(name, value_opt.map(|s| s.trim()).unwrap_or(""));
             }
             if let Some(motforslag_partier) = utskottsforslag.motforslag_partier {
+                let parties = split_parties(motforslag_partier.as_ref());
                 textelem.set_attr(
                     "motforslag_partier",
-                    split_and_format_parties(motforslag_partier.as_ref()),
+                    format_multi_value(parties.iter().peekable()),
+                );
+                textelem.set_attr(
+                    "motforslag_partier_kanonisk",
+                    format_canonical_parties(parties.iter().map(String::as_str), datum),
                 );
             }
             if let Some(text) = utskottsforslag.forslag {
-                process_html(&text, &mut textelem)?;
+                html_fields.process(&text, &mut textelem)?;
             }
             if let Some(text) = utskottsforslag.forslag_del2 {
-                process_html(&text, &mut textelem)?;
+                html_fields.process(&text, &mut textelem)?;
             }
             if let Some(value) = &utskottsforslag.votering_sammanfattning_html {
-                process_json_value(value, &mut textelem)?;
+                process_json_value(&mut html_fields, value, &mut textelem)?;
+            }
+            let mut textelem = clean_element(&textelem, clean_steps);
+            if let Some(ocr_repair) = ocr_repair {
+                ocr_repair.repair(&mut textelem);
             }
-            let textelem = clean_element(&textelem);
             // Add textelem as child to docelem
             docelem.append_child(textelem);
         }
@@ -368,22 +579,32 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
                 let (name, value_opt) = ("id", motforslag.id);
                 textelem.set_attr(name, value_opt.map(|s| s.trim()).unwrap_or(""));
             }
+            let parties = split_parties(motforslag.partier.as_ref());
+            textelem.set_attr("partier", format_multi_value(parties.iter().peekable()));
             textelem.set_attr(
-                "partier",
-                split_and_format_parties(motforslag.partier.as_ref()),
+                "partier_kanonisk",
+                format_canonical_parties(parties.iter().map(String::as_str), datum),
             );
             if let Some(text) = motforslag.rubrik {
-                process_html(text, &mut textelem)?;
+                html_fields.process(text, &mut textelem)?;
             }
-            if let Some(text) = motforslag.forslag {
-                process_html(text, &mut textelem)?;
+            if let Some(text) = &motforslag.forslag {
+                html_fields.process(text, &mut textelem)?;
+            }
+            let mut textelem = clean_element(&textelem, clean_steps);
+            if let Some(ocr_repair) = ocr_repair {
+                ocr_repair.repair(&mut textelem);
             }
-            let textelem = clean_element(&textelem);
             // Add textelem as child to docelem
             docelem.append_child(textelem);
         }
     }
 
+    for entry in media_entries {
+        media_manifest.push(entry);
+    }
+    unknown_tag_report.record(dokument.dok_id, html_fields.found);
+
     // Serialize dokument
     let mut result = Vec::new();
     let mut writer = Writer::new_with_indent(&mut result, b' ', 2);
@@ -394,6 +615,85 @@ pub fn preprocess_json(source: &str, metadata: &DataSet) -> Result<Vec<u8>, Prep
     Ok(result)
 }
 
+/// The `html` or an `anf_text`, processed while the document is read.
+#[derive(Debug)]
+struct ProcessedText {
+    nodes: Vec<Node>,
+    segreg: bool,
+    /// Logged once the `dok_id` of the document is known.
+    unknown_entities: Vec<String>,
+    /// Recorded once the `dok_id` of the document is known.
+    unknown_tags: Vec<UnknownTag>,
+}
+
+impl ProcessedText {
+    fn append_to(self, textelem: &mut Element) {
+        for node in self.nodes {
+            textelem.append_node(node);
+        }
+    }
+}
+
+/// Processes the html fields of a document that are read after its `dok_id`.
+struct HtmlFields<'a> {
+    dok_id: &'a str,
+    tables: TableMode,
+    clean_steps: CleanSteps,
+    unknown_tags: &'a UnknownTagPolicies,
+    /// The unknown elements found so far.
+    found: Vec<UnknownTag>,
+}
+
+impl HtmlFields<'_> {
+    /// Process `contents` with [`html::process_html`], logging the unknown entities in it.
+    fn process(&mut self, contents: &str, textelem: &mut Element) -> Result<(), ProcessHtmlError> {
+        warn_unknown_entities(self.dok_id, unknown_entities(contents));
+        let found = html::process_html(
+            &self.clean_steps.clean_html(contents),
+            textelem,
+            self.tables,
+            self.unknown_tags,
+        )?;
+        self.found.extend(found);
+        Ok(())
+    }
+}
+
+fn process_payload(
+    payload: Payload<'_>,
+    tables: TableMode,
+    clean_steps: CleanSteps,
+    unknown_tags: &UnknownTagPolicies,
+) -> Result<ProcessedText, ProcessHtmlError> {
+    let mut textelem = Element::bare("text", "");
+    let found = html::process_html(
+        &clean_steps.clean_html(payload.text()),
+        &mut textelem,
+        tables,
+        unknown_tags,
+    )?;
+    Ok(ProcessedText {
+        nodes: textelem.take_nodes(),
+        segreg: is_segreg(payload.text()),
+        unknown_entities: unknown_entities(payload.text())
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        unknown_tags: found,
+    })
+}
+
+fn set_media_attrs(textelem: &mut Element, media: &MediaRef<'_>) {
+    for (name, value) in [
+        ("videofileurl", media.videofileurl),
+        ("audiofileurl", media.audiofileurl),
+        ("thumbnailurl", media.thumbnailurl),
+    ] {
+        textelem.set_attr(name, value.trim());
+    }
+    textelem.set_attr("debattsekunder", media.debattsekunder.to_string());
+}
+
 #[inline]
 fn format_multi_value<I>(mut iter: Peekable<I>) -> String
 where
@@ -407,7 +707,7 @@ where
     format!("|{}|", iter.join("|"))
 }
 
-fn split_and_format_parties(text: &str) -> String {
+fn split_parties(text: &str) -> BTreeSet<String> {
     let mut parties = BTreeSet::new();
     let parts = text.split('"');
     for part in parts {
@@ -418,8 +718,13 @@ fn split_and_format_parties(text: &str) -> String {
             }
         }
     }
+    parties
+}
 
-    format_multi_value(parties.iter().peekable())
+/// Format the canonical codes of `parties` as used at `date`.
+fn format_canonical_parties<'p>(parties: impl Iterator<Item = &'p str>, date: NaiveDate) -> String {
+    let canonical: BTreeSet<String> = parties.map(|party| canonical_party(party, date)).collect();
+    format_multi_value(canonical.iter().peekable())
 }
 
 #[derive(Debug, thiserror::Error, miette::Diagnostic)]
@@ -435,134 +740,104 @@ pub enum PreprocessJsonError {
     HtmlError(#[from] ProcessHtmlError),
 }
 
+impl From<StreamError<ProcessHtmlError>> for PreprocessJsonError {
+    fn from(error: StreamError<ProcessHtmlError>) -> Self {
+        match error {
+            StreamError::Json(error) => Self::JsonError(error),
+            StreamError::Payload(error) => Self::HtmlError(error),
+        }
+    }
+}
+
+/// Process a field that is either html or html converted to JSON, like
+/// `votering_sammanfattning_html`.
+///
+/// The JSON is turned back into html, see [`json_to_html`].
 fn process_json_value(
+    html_fields: &mut HtmlFields<'_>,
     value: &serde_json::Value,
     textelem: &mut Element,
 ) -> Result<(), ProcessHtmlError> {
+    match value {
+        serde_json::Value::String(html) => html_fields.process(html, textelem),
+        value => {
+            let mut html = String::new();
+            json_to_html(None, value, &mut html);
+            html_fields.process(&html, textelem)
+        }
+    }
+}
+
+/// Write `value` as html to `html`, as the element `name` if it has one.
+///
+/// The keys of an object are elements and an array repeats its element, so
+/// `{"tr": [{"td": ["S", "0"]}]}` is `<tr><td>S</td><td>0</td></tr>`. Keys that aren't tag
+/// names, like `@class`, are left out and `#text` is the text of its element.
+///
+/// The keys of a JSON object are sorted, so the parts of tables are put back in their html
+/// order and `#text`, which follows the elements in the documents, comes last.
+fn json_to_html(name: Option<&str>, value: &serde_json::Value, html: &mut String) {
     use serde_json::Value;
-    // if let serde_json::Value::String(html) = value {
-    //     process_html(html, textelem, &Cow::Borrowed(""));
-    //     return;
-    // }
-    // todo!("handle {:?}", value)
+
+    if let Value::Array(items) = value {
+        for item in items {
+            json_to_html(name, item, html);
+        }
+        return;
+    }
+    // The documents write `<tfooter>` for `<tfoot>`
+    let name = name.map(|name| if name == "tfooter" { "tfoot" } else { name });
+    if name == Some("br") {
+        html.push_str("<br>");
+        return;
+    }
+    if let Some(name) = name {
+        html.push_str(&format!("<{name}>"));
+    }
     match value {
-        Value::String(html) => process_html(html, textelem)?,
-        Value::Object(obj) => {
-            if let Some(table) = obj.get("table") {
-                if let Some(table) = table.as_array() {
-                    dbg!(table);
-                    for a in table {
-                        match a {
-                            Value::Object(to) => {
-                                if let Some(tr) = to.get("tr") {
-                                    match tr {
-                                        Value::Array(rows) => {
-                                            for row in rows {
-                                                match row {
-                                                    Value::Object(r) => {
-                                                        if let Some(td) = r.get("td") {
-                                                            match td {
-                                                                Value::Object(td_obj) => {
-                                                                    dbg!(td_obj);
-                                                                    match &td_obj["h4"] {
-                                                                        Value::String(text) => {
-                                                                            textelem.append_child(
-                                                                                elem_p(text),
-                                                                            );
-                                                                        }
-                                                                        Value::Null => (),
-                                                                        x => {
-                                                                            todo!("handle {:?}", x)
-                                                                        }
-                                                                    }
-                                                                    if let Some(td_obj_p) =
-                                                                        td_obj.get("p")
-                                                                    {
-                                                                        match td_obj_p {
-                                                                            Value::String(text) => {
-                                                                                textelem
-                                                                                    .append_child(
-                                                                                        elem_p(
-                                                                                            text,
-                                                                                        ),
-                                                                                    );
-                                                                            }
-                                                                            x => todo!(
-                                                                                "handle {:?}",
-                                                                                x
-                                                                            ),
-                                                                        }
-                                                                    }
-                                                                }
-                                                                Value::Array(td_arr) => {
-                                                                    for td_v in td_arr {
-                                                                        match td_v {
-                                                                            Value::String(text) => {
-                                                                                textelem
-                                                                                    .append_child(
-                                                                                        elem_p(
-                                                                                            text,
-                                                                                        ),
-                                                                                    );
-                                                                            }
-                                                                            x => todo!(
-                                                                                "handle {:?}",
-                                                                                x
-                                                                            ),
-                                                                        }
-                                                                    }
-                                                                }
-                                                                x => todo!("handle {:?}", x),
-                                                            }
-                                                        }
-                                                        if let Some(th) = &r.get("th") {
-                                                            match th {
-                                                                Value::Array(th_arr) => {
-                                                                    for th_v in th_arr {
-                                                                        match th_v {
-                                                                            Value::String(text) => {
-                                                                                textelem
-                                                                                    .append_child(
-                                                                                        elem_p(
-                                                                                            text,
-                                                                                        ),
-                                                                                    );
-                                                                            }
-                                                                            x => todo!(
-                                                                                "handle {:?}",
-                                                                                x
-                                                                            ),
-                                                                        }
-                                                                    }
-                                                                }
-                                                                x => todo!("handle {:?}", x),
-                                                            }
-                                                        }
-                                                    }
-                                                    x => todo!("handle {:?}", x),
-                                                }
-                                            }
-                                        }
-                                        x => todo!("handle {:?}", x),
-                                    }
-                                }
-                            }
-                            x => todo!("handle {:?}", x),
-                        }
-                    }
-                } else if let Some(_table) = table.as_object() {
-                } else {
-                    todo!("handle table={:?}", table);
+        Value::Null | Value::Array(_) => (),
+        Value::Bool(value) => html.push_str(&value.to_string()),
+        Value::Number(value) => html.push_str(&value.to_string()),
+        Value::String(text) => html.push_str(
+            &text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+        ),
+        Value::Object(fields) => {
+            let mut fields: Vec<_> = fields.iter().collect();
+            fields.sort_by_key(|(key, _)| html_order(key));
+            for (key, value) in fields {
+                if key == "#text" {
+                    json_to_html(None, value, html);
+                } else if is_tag_name(key) {
+                    json_to_html(Some(key), value, html);
                 }
             }
         }
-        x => todo!("handle {:?}", x),
     }
-    Ok(())
+    if let Some(name) = name {
+        html.push_str(&format!("</{name}>"));
+    }
+}
+
+/// The position of the element `key` among its siblings in html.
+fn html_order(key: &str) -> u8 {
+    match key {
+        "caption" => 0,
+        "thead" => 1,
+        "th" => 2,
+        "tbody" => 3,
+        "td" => 4,
+        "tfoot" | "tfooter" => 5,
+        "#text" => 7,
+        _ => 6,
+    }
 }
 
-fn elem_p(text: &str) -> Element {
-    let mut p = Element::bare("p", "");
-    p.append_text_node(text);
-    p
+fn is_tag_name(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
 }
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, path::Path};
+
+use fs_err as fs;
+use minidom_extension::minidom::Element;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use swegov_opendata::{Person, PersonListaPage};
+
+use crate::PreprocessError;
+
+/// Resolves speakers and intressenter against a local copy of the person register (`personlista`).
+#[derive(Debug, Default, Clone)]
+pub struct SpeakerRegister {
+    persons: Vec<Person>,
+    by_id: HashMap<String, usize>,
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+impl SpeakerRegister {
+    pub fn new(persons: Vec<Person>) -> Self {
+        let mut by_id = HashMap::new();
+        let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+        for (idx, person) in persons.iter().enumerate() {
+            let intressent_id = person.intressent_id.trim();
+            if !intressent_id.is_empty() {
+                by_id.insert(intressent_id.to_string(), idx);
+            }
+            by_name
+                .entry(normalize_name(&person.namn()))
+                .or_default()
+                .push(idx);
+        }
+        Self {
+            persons,
+            by_id,
+            by_name,
+        }
+    }
+
+    /// Read the register from a `personlista` JSON dump.
+    pub fn from_path(path: &Path) -> Result<Self, PreprocessError> {
+        let file_data =
+            fs::read_to_string(path).map_err(|error| PreprocessError::CouldNotReadFile {
+                path: path.to_path_buf(),
+                error,
+            })?;
+        let PersonListaPage { personlista } =
+            serde_json::from_str(&file_data).map_err(|error| {
+                PreprocessError::CouldNotReadJson {
+                    path: path.to_path_buf(),
+                    error,
+                }
+            })?;
+        Ok(Self::new(personlista.person))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.persons.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.persons.len()
+    }
+
+    /// Find the person behind a speaker.
+    ///
+    /// A non-empty `intressent_id` is looked up directly. Otherwise `talare` is normalised
+    /// and, if several persons share the name, `parti` is used to pick one of them.
+    pub fn resolve(
+        &self,
+        intressent_id: Option<&str>,
+        talare: &str,
+        parti: Option<&str>,
+    ) -> Option<&Person> {
+        if let Some(idx) = intressent_id
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .and_then(|id| self.by_id.get(id))
+        {
+            return Some(&self.persons[*idx]);
+        }
+        let candidates = self.by_name.get(&normalize_name(talare))?;
+        if let [idx] = candidates.as_slice() {
+            return Some(&self.persons[*idx]);
+        }
+        let parti = parti.map(str::trim).filter(|parti| !parti.is_empty())?;
+        let mut matching = candidates
+            .iter()
+            .map(|idx| &self.persons[*idx])
+            .filter(|person| {
+                person
+                    .parti
+                    .as_deref()
+                    .is_some_and(|p| p.trim().eq_ignore_ascii_case(parti))
+            });
+        match (matching.next(), matching.next()) {
+            (Some(person), None) => Some(person),
+            _ => {
+                tracing::debug!(talare, parti, "ambiguous speaker");
+                None
+            }
+        }
+    }
+}
+
+/// Normalise a speaker name for lookup.
+///
+/// Titles ("Statsrådet", "Andre vice talman", "Justitieminister"), party ("(s)")
+/// and "replik" are removed and the name is lowercased,
+/// so "Statsrådet ANNA LINDH (s) replik" becomes "anna lindh".
+pub fn normalize_name(talare: &str) -> String {
+    static PARTY: Lazy<Regex> = Lazy::new(|| Regex::new(r"\([^)]*\)").expect("valid regex"));
+    let talare = PARTY.replace_all(talare, " ");
+    let mut words: Vec<String> = talare.split_whitespace().map(str::to_lowercase).collect();
+    if words.last().is_some_and(|word| word == "replik") {
+        words.pop();
+    }
+    let start = words
+        .iter()
+        .position(|word| !is_title(word))
+        .unwrap_or(words.len());
+    words[start..].join(" ")
+}
+
+fn is_title(word: &str) -> bool {
+    matches!(
+        word,
+        "statsrådet"
+            | "statsråd"
+            | "talman"
+            | "talmannen"
+            | "förste"
+            | "andre"
+            | "tredje"
+            | "vice"
+            | "ålderspresident"
+            | "ålderspresidenten"
+            | "t.f."
+    ) || word.ends_with("minister")
+        || word.ends_with("ministern")
+}
+
+/// Add `intressent_id`, `kon` and `fodd_ar` of `person` to `elem`.
+///
+/// Existing, non-empty `intressent_id` and `kon` are kept.
+pub fn set_speaker_attrs(elem: &mut Element, person: &Person) {
+    if elem.attr("intressent_id").is_none_or(str::is_empty) {
+        elem.set_attr("intressent_id", person.intressent_id.trim());
+    }
+    if elem.attr("kon").is_none_or(str::is_empty) {
+        elem.set_attr("kon", kon(person).unwrap_or(""));
+    }
+    elem.set_attr(
+        "fodd_ar",
+        person
+            .fodd_ar
+            .map(|fodd_ar| fodd_ar.to_string())
+            .unwrap_or_default(),
+    );
+}
+
+/// The gender of `person`, in the form used by `anforande` ("male"/"female").
+pub fn kon(person: &Person) -> Option<&str> {
+    match person.kon.as_deref().map(str::trim) {
+        Some("man") => Some("male"),
+        Some("kvinna") => Some("female"),
+        Some("") | None => None,
+        Some(kon) => Some(kon),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,77 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+
+fn person(intressent_id: &str, tilltalsnamn: &str, efternamn: &str, parti: &str) -> Person {
+    Person {
+        intressent_id: intressent_id.into(),
+        fodd_ar: Some(1957),
+        kon: Some("kvinna".into()),
+        tilltalsnamn: tilltalsnamn.into(),
+        efternamn: efternamn.into(),
+        sorteringsnamn: None,
+        parti: Some(parti.into()),
+        status: None,
+    }
+}
+
+#[fixture]
+fn register() -> SpeakerRegister {
+    SpeakerRegister::new(vec![
+        person("0215263721515", "Anna", "Lindh", "S"),
+        person("0722787826224", "Karin", "Andersson", "C"),
+        person("0564934919729", "Karin", "Andersson", "S"),
+    ])
+}
+
+#[rstest]
+#[case("Statsrådet ANNA LINDH (s) replik", "anna lindh")]
+#[case("Talman Andreas Norlén", "andreas norlén")]
+#[case("Andre vice talman Kerstin Lundgren", "kerstin lundgren")]
+#[case("Utrikesminister Anna Lindh", "anna lindh")]
+#[case("LARS WERNER (v)", "lars werner")]
+#[case("TALMANNEN", "")]
+fn normalize_name_removes_titles_and_party(#[case] given: &str, #[case] expected: &str) {
+    assert_eq!(normalize_name(given), expected);
+}
+
+#[rstest]
+#[case(Some("0215263721515"), "", None, Some("0215263721515"))]
+#[case(None, "Statsrådet ANNA LINDH (s)", None, Some("0215263721515"))]
+#[case(Some(""), "ANNA LINDH", Some("s"), Some("0215263721515"))]
+#[case(None, "KARIN ANDERSSON", None, None)]
+#[case(None, "KARIN ANDERSSON", Some("c"), Some("0722787826224"))]
+#[case(None, "Karin Andersson", Some("S"), Some("0564934919729"))]
+#[case(None, "TALMANNEN", None, None)]
+fn resolve_finds_person(
+    register: SpeakerRegister,
+    #[case] intressent_id: Option<&str>,
+    #[case] talare: &str,
+    #[case] parti: Option<&str>,
+    #[case] expected: Option<&str>,
+) {
+    assert_eq!(
+        register
+            .resolve(intressent_id, talare, parti)
+            .map(|person| person.intressent_id.as_str()),
+        expected
+    );
+}
+
+#[rstest]
+fn set_speaker_attrs_keeps_existing_values(register: SpeakerRegister) {
+    let person = register
+        .resolve(None, "ANNA LINDH", None)
+        .expect("a person");
+    let mut elem = Element::builder("anforande", "")
+        .attr("intressent_id", "")
+        .attr("kon", "female")
+        .build();
+
+    set_speaker_attrs(&mut elem, person);
+
+    assert_eq!(elem.attr("intressent_id"), Some("0215263721515"));
+    assert_eq!(elem.attr("kon"), Some("female"));
+    assert_eq!(elem.attr("fodd_ar"), Some("1957"));
+}
@@ -0,0 +1,207 @@
+//! What to do with the elements in the html that [`process_html`](super::process_html) doesn't
+//! know, and statistics of the ones that were found.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::Path,
+};
+
+use crate::PreprocessError;
+
+use super::shared::write_json;
+
+#[cfg(test)]
+mod tests;
+
+/// The number of places that are kept for each unknown tag in an [`UnknownTagReport`].
+const MAX_EXAMPLES: usize = 10;
+
+/// How an unknown element is handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnknownTagPolicy {
+    /// Replace the element with its content, which is processed as usual.
+    #[default]
+    Unwrap,
+    /// Keep the text of the element, without its markup, in the current paragraph.
+    KeepText,
+    /// Drop the element together with its content.
+    Skip,
+    /// Fail with [`ProcessHtmlError::UnexpectedTag`](super::html::ProcessHtmlError).
+    Error,
+}
+
+impl UnknownTagPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unwrap => "unwrap",
+            Self::KeepText => "keep-text",
+            Self::Skip => "skip",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for UnknownTagPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for UnknownTagPolicy {
+    type Err = InvalidTagPolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unwrap" => Ok(Self::Unwrap),
+            "keep-text" => Ok(Self::KeepText),
+            "skip" => Ok(Self::Skip),
+            "error" => Ok(Self::Error),
+            _ => Err(InvalidTagPolicy(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "invalid tag policy '{0}', expected one of unwrap, keep-text, skip or error, or <tag>=<policy>"
+)]
+pub struct InvalidTagPolicy(pub String);
+
+/// The policy for one tag, written `<tag>=<policy>`, e.g. `font=skip`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagPolicy {
+    pub tag: String,
+    pub policy: UnknownTagPolicy,
+}
+
+impl std::str::FromStr for TagPolicy {
+    type Err = InvalidTagPolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (tag, policy) = s
+            .split_once('=')
+            .ok_or_else(|| InvalidTagPolicy(s.to_string()))?;
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(InvalidTagPolicy(s.to_string()));
+        }
+        Ok(Self {
+            tag: tag.to_lowercase(),
+            policy: policy.trim().parse()?,
+        })
+    }
+}
+
+/// The policy of each unknown tag.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UnknownTagPolicies {
+    /// Used for the tags without a policy of their own.
+    default: UnknownTagPolicy,
+    tags: HashMap<String, UnknownTagPolicy>,
+}
+
+impl UnknownTagPolicies {
+    pub fn new(default: UnknownTagPolicy, tags: impl IntoIterator<Item = TagPolicy>) -> Self {
+        Self {
+            default,
+            tags: tags
+                .into_iter()
+                .map(|TagPolicy { tag, policy }| (tag, policy))
+                .collect(),
+        }
+    }
+
+    /// The policy for the element `tag`, the lowercase name that the html parser gives it.
+    pub fn policy(&self, tag: &str) -> UnknownTagPolicy {
+        self.tags.get(tag).copied().unwrap_or(self.default)
+    }
+}
+
+/// An unknown element that was found while processing html.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownTag {
+    pub tag: String,
+    /// The path of the element in the html, e.g. `html/body/div[2]/foo`.
+    pub path: String,
+    /// Where in the processing the element was found.
+    pub context: &'static str,
+    pub policy: UnknownTagPolicy,
+}
+
+/// The unknown tags found during a run, to tell which of them matter.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct UnknownTagReport {
+    tags: BTreeMap<String, UnknownTagStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct UnknownTagStats {
+    pub policy: UnknownTagPolicy,
+    /// The number of elements found.
+    pub count: usize,
+    /// The first few places where the tag was found.
+    pub examples: Vec<UnknownTagExample>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct UnknownTagExample {
+    pub dok_id: String,
+    pub path: String,
+    pub context: String,
+}
+
+impl UnknownTagReport {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// The number of different unknown tags.
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn get(&self, tag: &str) -> Option<&UnknownTagStats> {
+        self.tags.get(tag)
+    }
+
+    /// Count the unknown tags found in the document `dok_id`.
+    pub fn record(&mut self, dok_id: &str, found: impl IntoIterator<Item = UnknownTag>) {
+        for UnknownTag {
+            tag,
+            path,
+            context,
+            policy,
+        } in found
+        {
+            let stats = self.tags.entry(tag).or_insert_with(|| UnknownTagStats {
+                policy,
+                count: 0,
+                examples: Vec::new(),
+            });
+            stats.count += 1;
+            if stats.examples.len() < MAX_EXAMPLES {
+                stats.examples.push(UnknownTagExample {
+                    dok_id: dok_id.to_string(),
+                    path,
+                    context: context.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Write the report as JSON to `path`.
+    pub fn write(&self, path: &Path) -> Result<(), PreprocessError> {
+        write_json(path, self)
+    }
+}
+
+impl fmt::Display for UnknownTagReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (tag, stats) in &self.tags {
+            writeln!(f, "{tag}: {} ({})", stats.count, stats.policy)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+#[case("unwrap", UnknownTagPolicy::Unwrap)]
+#[case("keep-text", UnknownTagPolicy::KeepText)]
+#[case("skip", UnknownTagPolicy::Skip)]
+#[case("error", UnknownTagPolicy::Error)]
+fn unknown_tag_policy_round_trips(#[case] given: &str, #[case] expected: UnknownTagPolicy) {
+    assert_eq!(given.parse::<UnknownTagPolicy>(), Ok(expected));
+    assert_eq!(expected.to_string(), given);
+}
+
+#[rstest]
+#[case("FONT=skip", Ok(TagPolicy { tag: "font".to_string(), policy: UnknownTagPolicy::Skip }))]
+#[case("o:p = keep-text", Ok(TagPolicy { tag: "o:p".to_string(), policy: UnknownTagPolicy::KeepText }))]
+#[case("font", Err(InvalidTagPolicy("font".to_string())))]
+#[case("=skip", Err(InvalidTagPolicy("=skip".to_string())))]
+#[case("font=drop", Err(InvalidTagPolicy("drop".to_string())))]
+fn tag_policy_parses_tag_and_policy(
+    #[case] given: &str,
+    #[case] expected: Result<TagPolicy, InvalidTagPolicy>,
+) {
+    assert_eq!(given.parse::<TagPolicy>(), expected);
+}
+
+#[test]
+fn policies_fall_back_to_default() {
+    let policies = UnknownTagPolicies::new(
+        UnknownTagPolicy::KeepText,
+        [TagPolicy {
+            tag: "font".to_string(),
+            policy: UnknownTagPolicy::Skip,
+        }],
+    );
+
+    assert_eq!(policies.policy("font"), UnknownTagPolicy::Skip);
+    assert_eq!(policies.policy("blaha"), UnknownTagPolicy::KeepText);
+}
+
+fn found(tag: &str, path: &str) -> UnknownTag {
+    UnknownTag {
+        tag: tag.to_string(),
+        path: path.to_string(),
+        context: "process_html",
+        policy: UnknownTagPolicy::Unwrap,
+    }
+}
+
+#[test]
+fn report_counts_tags_and_keeps_some_examples() {
+    let mut report = UnknownTagReport::default();
+
+    report.record(
+        "H601AU1",
+        (0..12).map(|i| found("blaha", &format!("body/blaha[{i}]"))),
+    );
+    report.record("H601AU6", [found("o", "body/p/o")]);
+
+    assert_eq!(report.len(), 2);
+    let blaha = report.get("blaha").expect("blaha is recorded");
+    assert_eq!(blaha.count, 12);
+    assert_eq!(blaha.examples.len(), MAX_EXAMPLES);
+    assert_eq!(
+        report.get("o").map(|stats| stats.examples.clone()),
+        Some(vec![UnknownTagExample {
+            dok_id: "H601AU6".to_string(),
+            path: "body/p/o".to_string(),
+            context: "process_html".to_string(),
+        }])
+    );
+}
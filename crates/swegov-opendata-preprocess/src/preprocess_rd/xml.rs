@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::collections::BTreeSet;
 
 use minidom::quick_xml::events::Event;
 use minidom::quick_xml::Reader;
@@ -6,14 +7,30 @@ use minidom::quick_xml::Writer;
 use minidom::Element;
 use minidom_extension::{minidom, minidom_collect_texts};
 
-use crate::shared::clean_text;
+use crate::shared::cleaning::CleanSteps;
+use crate::shared::html::{unknown_entities, warn_unknown_entities};
 use crate::shared::is_segreg;
+use crate::shared::ocr::OcrRepair;
+use crate::shared::tables::TableMode;
 
-use super::html::process_html;
+use super::html::{process_html, ProcessHtmlError};
+use super::unknown_tags::{UnknownTagPolicies, UnknownTagReport};
 
-/// Extract meta data and html from f.
-#[tracing::instrument(skip(xml_string))]
-pub fn preprocess_xml(xml_string: &str, filename: Cow<'_, str>) -> Result<Vec<u8>, XmlError> {
+/// Extract meta data and html from f, writing the tables in the html as `tables` says and
+/// cleaning the text with `clean_steps`.
+///
+/// The unknown elements in the html are handled as `unknown_tags` says and recorded in
+/// `unknown_tag_report`. The text of a scanned document is repaired by `ocr_repair`, if given.
+#[tracing::instrument(skip(xml_string, unknown_tags, unknown_tag_report))]
+pub fn preprocess_xml(
+    xml_string: &str,
+    filename: Cow<'_, str>,
+    tables: TableMode,
+    clean_steps: CleanSteps,
+    unknown_tags: &UnknownTagPolicies,
+    unknown_tag_report: &mut UnknownTagReport,
+    ocr_repair: Option<&OcrRepair>,
+) -> Result<Vec<u8>, XmlError> {
     // let tree = Soup::new(xml_string);
 
     // Create new element and build document
@@ -36,6 +53,8 @@ pub fn preprocess_xml(xml_string: &str, filename: Cow<'_, str>) -> Result<Vec<u8
     let mut found_html = false;
     let mut doc_attr = None;
     let mut text_attr = None;
+    let mut entities = BTreeSet::new();
+    let mut found_tags = Vec::new();
     let mut reader = Reader::from_str(xml_string);
     loop {
         match reader.read_event() {
@@ -72,10 +91,20 @@ pub fn preprocess_xml(xml_string: &str, filename: Cow<'_, str>) -> Result<Vec<u8
                 if in_html {
                     let html_string = match e.unescape() {
                         Ok(s) => s,
-                        Err(err) => panic!("unescape failed: {:?}", err),
+                        Err(err) => {
+                            return Err(XmlError::Read {
+                                pos: reader.buffer_position(),
+                                error: err,
+                            })
+                        }
                     };
-                    process_html(&html_string, &mut textelem).expect("valid html");
-                    // tracing::trace!("textelem = {:?}", textelem);
+                    entities.extend(unknown_entities(&html_string).into_iter().map(String::from));
+                    found_tags.extend(process_html(
+                        &clean_steps.clean_html(&html_string),
+                        &mut textelem,
+                        tables,
+                        unknown_tags,
+                    )?);
                 } else if doc_attr.is_some() {
                     let name = doc_attr.take().unwrap();
                     let value = match e.unescape() {
@@ -124,10 +153,18 @@ pub fn preprocess_xml(xml_string: &str, filename: Cow<'_, str>) -> Result<Vec<u8
     if !found_html {
         tracing::warn!("    WARNING: No html found in {filename}");
     }
-    dbg!("BEFORE", &textelem);
-    let textelem = clean_element(&textelem).unwrap();
-    dbg!("AFTER", &textelem);
-    docelem.append_child(textelem);
+    let dok_id = docelem.attr("dok_id").unwrap_or(&filename);
+    warn_unknown_entities(dok_id, entities.iter().map(String::as_str));
+    unknown_tag_report.record(dok_id, found_tags);
+    // Only empty `p` elements are removed by `clean_element`
+    if let Some(mut textelem) = clean_element(&textelem, clean_steps) {
+        let ocr_repair =
+            ocr_repair.filter(|repair| repair.applies_to(textelem.attr("rm").unwrap_or_default()));
+        if let Some(ocr_repair) = ocr_repair {
+            ocr_repair.repair(&mut textelem);
+        }
+        docelem.append_child(textelem);
+    }
     let mut result = Vec::new();
     let mut writer = Writer::new_with_indent(&mut result, b' ', 2);
     docelem.to_writer(&mut writer).map_err(XmlError::Write)?;
@@ -144,17 +181,18 @@ pub enum XmlError {
     },
     #[error("Error writing xml")]
     Write(#[source] minidom::Error),
+    #[error("Error processing the html")]
+    Html(#[from] ProcessHtmlError),
 }
 
-pub fn clean_element(elem: &minidom::Element) -> Option<minidom::Element> {
-    dbg!(&elem);
+pub fn clean_element(elem: &minidom::Element, steps: CleanSteps) -> Option<minidom::Element> {
     let mut elem_builder = Element::builder(elem.name(), elem.ns());
     for (name, value) in elem.attrs() {
         elem_builder = elem_builder.attr(name, value);
     }
     if elem.name() == "p" {
         let mut text = minidom_collect_texts(elem);
-        text = clean_text(&text);
+        text = steps.clean(&text);
         if text.is_empty() {
             return None;
         } else {
@@ -163,7 +201,7 @@ pub fn clean_element(elem: &minidom::Element) -> Option<minidom::Element> {
     } else {
         let mut curr_node: Option<minidom::Node> = None;
         for node in elem.nodes() {
-            let node = clean_node(node);
+            let node = clean_node(node, steps);
             if let Some(node) = node {
                 if !node_is_empty(&node) {
                     match node {
@@ -219,7 +257,7 @@ pub fn clean_element(elem: &minidom::Element) -> Option<minidom::Element> {
     Some(new_elem)
 }
 
-pub fn clean_node(node: &minidom::Node) -> Option<minidom::Node> {
+pub fn clean_node(node: &minidom::Node, steps: CleanSteps) -> Option<minidom::Node> {
     // dbg!(&node);
     match node {
         minidom::Node::Text(contents) => {
@@ -227,7 +265,7 @@ pub fn clean_node(node: &minidom::Node) -> Option<minidom::Node> {
             // clean_text(&mut text);
             Some(minidom::Node::Text(text))
         }
-        minidom::Node::Element(elem) => clean_element(elem).map(minidom::Node::Element),
+        minidom::Node::Element(elem) => clean_element(elem, steps).map(minidom::Node::Element),
     }
 }
 
@@ -32,7 +32,7 @@ mod clean {
         let elem: Element = given.parse().unwrap();
         let expected: Option<Element> = expected.map(|e| e.parse().unwrap());
 
-        let cleaned = clean_element(&elem);
+        let cleaned = clean_element(&elem, CleanSteps::default());
         assert_eq!(
             cleaned.is_some(),
             expected.is_some(),
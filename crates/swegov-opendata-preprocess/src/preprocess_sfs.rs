@@ -1,6 +1,7 @@
 mod error;
 mod sfs_corpura;
 pub mod sfs_json;
+mod sfs_versions;
 mod sparv_source;
 
 pub use self::sfs_corpura::{preprocess_sfs_corpus, PreprocessSfsCorpuraOptions};
@@ -8,3 +9,5 @@ pub use self::sfs_corpura::{preprocess_sfs_corpus, PreprocessSfsCorpuraOptions};
 pub use self::error::SfsPreprocessError;
 
 pub use self::sparv_source::build_sparv_source;
+
+pub use self::sfs_versions::{diff_sfs_versions, ParagrafChange};
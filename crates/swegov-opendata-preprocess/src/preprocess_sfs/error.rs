@@ -1,7 +1,4 @@
 use minidom_extension::minidom;
-use std::string::FromUtf8Error;
-
-use minidom::quick_xml;
 
 #[derive(Debug, thiserror::Error, miette::Diagnostic)]
 pub enum SfsPreprocessError {
@@ -18,22 +15,10 @@ pub enum SfsPreprocessError {
     FailedToWriteXml(#[from] minidom::Error),
     #[error("The 'html' field of the dokument is empty")]
     HtmlFieldIsEmpty,
-    #[error("xml parse error for string at position {pos}: {err:?}")]
-    XmlParsingError {
-        pos: u64,
-        #[source]
-        err: quick_xml::Error,
-    },
-    #[error("xml parse error for string at position {pos}: {err:?}")]
-    XmlParsingAttrError {
-        pos: u64,
-        #[source]
-        err: quick_xml::events::attributes::AttrError,
-    },
-    #[error("[XML] bad UTF8 at pos {pos}: {err:?}")]
-    XmlFromUtf8Error {
-        pos: u64,
+    #[error("The 'upphavd' of the dokuppgift is not a date: '{value}'")]
+    InvalidUpphavd {
+        value: String,
         #[source]
-        err: FromUtf8Error,
+        error: chrono::ParseError,
     },
 }
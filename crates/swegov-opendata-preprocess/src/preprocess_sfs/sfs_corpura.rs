@@ -9,12 +9,19 @@ use sparv_extension::SparvConfig;
 use sparv_extension::SparvMetadata;
 
 use crate::preprocess_sfs;
+use crate::shared::{cleaning::CleanStepsPerCorpus, ocr::OcrRepair, tables::TableMode};
 use crate::PreprocessError;
 
 #[derive(Debug, Clone)]
 pub struct PreprocessSfsCorpuraOptions<'a> {
     pub input: &'a Path,
     pub output: &'a Path,
+    /// How the tables in the html are written.
+    pub tables: TableMode,
+    /// How the texts of each corpus are cleaned.
+    pub clean_steps: &'a CleanStepsPerCorpus,
+    /// How the texts of scanned documents are repaired, if at all.
+    pub ocr_repair: Option<&'a OcrRepair>,
 }
 
 pub fn preprocess_sfs_corpus(
@@ -23,7 +30,7 @@ pub fn preprocess_sfs_corpus(
     _out: impl std::io::Write,
     _err: impl std::io::Write,
     mut progress: impl preprocess_progress::NestedProgress,
-    _options: PreprocessSfsCorpuraOptions<'_>,
+    options: PreprocessSfsCorpuraOptions<'_>,
 ) -> Result<(), PreprocessError> {
     tracing::info!("preprocess SFS corpus from {}", input_path.display());
     let start = std::time::Instant::now();
@@ -56,6 +63,9 @@ pub fn preprocess_sfs_corpus(
                 .join(corpus_id)
                 .join("source")
                 .join(year.file_stem().unwrap()),
+            options.tables,
+            options.clean_steps.for_corpus(corpus_id),
+            options.ocr_repair,
         )?;
         count.fetch_add(1, Ordering::Relaxed);
     }
@@ -1,40 +1,74 @@
-use std::{fs, io::Write};
-
 use chrono::NaiveDate;
-use minidom::{
-    quick_xml::{events::Event, Reader, Writer},
-    Element,
+use minidom::{quick_xml::Writer, Element};
+use minidom_extension::{minidom, minidom_collect_texts};
+use swegov_opendata::{DokumentStatusPageRef, DokumentStatusRef};
+
+use crate::{
+    nodeinfo::{rcdom_attr, rcdom_elem_name},
+    shared::{
+        clean_element,
+        cleaning::CleanSteps,
+        html::{html_body, parse_html, unknown_entities, warn_unknown_entities},
+        is_segreg,
+        ocr::{is_scanned, OcrRepair},
+        rm_start_year, set_beteckning_attrs,
+        tables::TableMode,
+    },
 };
-use minidom_extension::{attrib_query::attrib_equals, minidom};
-use once_cell::sync::Lazy;
-use regex::Regex;
-use swegov_opendata::{DokumentStatus, DokumentStatusPage};
 
-use crate::shared::{clean_element, is_segreg};
+use self::overgang::split_overgangsbestammelser;
 
 use super::SfsPreprocessError;
 
+mod overgang;
+mod provisions;
 mod sfs_div_dok;
 mod sfs_standard;
 
-pub fn preprocess_json(source: &str) -> Result<Vec<u8>, SfsPreprocessError> {
-    let DokumentStatusPage {
+/// Preprocess the SFS document in `source`, writing the tables in its html as `tables` says and
+/// cleaning its text with `clean_steps`.
+///
+/// The texts of scanned documents are repaired by `ocr_repair`, if given.
+pub fn preprocess_json(
+    source: &str,
+    tables: TableMode,
+    clean_steps: CleanSteps,
+    ocr_repair: Option<&OcrRepair>,
+) -> Result<Vec<u8>, SfsPreprocessError> {
+    let docelem = build_dokument(source, tables, clean_steps, ocr_repair)?;
+
+    // Serialize dokument
+    let mut result = Vec::new();
+    let mut writer = Writer::new_with_indent(&mut result, b' ', 2);
+    docelem.to_writer(&mut writer)?;
+    Ok(result)
+}
+
+/// Build the `<dokument>` with the `<text>` of the SFS document in `source`.
+pub(crate) fn build_dokument(
+    source: &str,
+    tables: TableMode,
+    clean_steps: CleanSteps,
+    ocr_repair: Option<&OcrRepair>,
+) -> Result<Element, SfsPreprocessError> {
+    let DokumentStatusPageRef {
         dokumentstatus:
-            DokumentStatus {
+            DokumentStatusRef {
                 dokument,
                 dokuppgift,
                 ..
             },
+        ..
     } = serde_json::from_str(source)?;
 
     // Build dokument
     let mut docelem = Element::builder("dokument", "")
-        .attr("dok_id", &dokument.dok_id)
+        .attr("dok_id", dokument.dok_id)
         .build();
     for (attr, value_opt) in [
-        ("dokument_url_text", &dokument.dokument_url_text),
-        ("dokument_url_html", &dokument.dokument_url_html),
-        ("dokumentstatus_url_xml", &dokument.dokumentstatus_url_xml),
+        ("dokument_url_text", dokument.dokument_url_text),
+        ("dokument_url_html", dokument.dokument_url_html),
+        ("dokumentstatus_url_xml", dokument.dokumentstatus_url_xml),
     ] {
         if let Some(value) = value_opt {
             docelem.set_attr(attr, value);
@@ -49,19 +83,21 @@ pub fn preprocess_json(source: &str) -> Result<Vec<u8>, SfsPreprocessError> {
     // text attributes
     for (name, value) in [
         // ("hangar_id", &dokument.hangar_id),
-        ("rm", &dokument.rm),
+        ("rm", dokument.rm),
         // ("beteckning", &dokument.beteckning),
-        ("dokumentnamn", &dokument.dokumentnamn),
-        ("typ", &dokument.typ),
+        ("dokumentnamn", dokument.dokumentnamn),
+        ("typ", dokument.typ),
         // ("subtyp", &dokument.subtyp),
         // ("organ", &dokument.organ),
-        ("nummer", &dokument.nummer),
-        ("slutnummer", &dokument.slutnummer),
-        ("title", &dokument.titel),
+        ("nummer", dokument.nummer),
+        ("slutnummer", dokument.slutnummer),
+        ("title", dokument.titel.as_ref()),
         // ("status", &dokument.status),
     ] {
         textelem.set_attr(name, value.replace("\r\n", " "));
     }
+    textelem.set_attr("rm_start_year", rm_start_year(dokument.rm));
+    set_beteckning_attrs(&mut textelem, dokument.parse_beteckning());
     for (name, value_opt) in [
         ("hangar_id", &dokument.hangar_id),
         // ("rm", &dokument.rm),
@@ -85,8 +121,8 @@ pub fn preprocess_json(source: &str) -> Result<Vec<u8>, SfsPreprocessError> {
         );
     }
     for (name, value_opt) in [
-        ("subtitle", &dokument.subtitel),
-        ("tempbeteckning", &dokument.tempbeteckning),
+        ("subtitle", dokument.subtitel.as_deref()),
+        ("tempbeteckning", dokument.tempbeteckning),
     ] {
         if let Some(value) = value_opt {
             if !value.is_empty() {
@@ -112,8 +148,11 @@ pub fn preprocess_json(source: &str) -> Result<Vec<u8>, SfsPreprocessError> {
     }
     if let Some(dokuppgift) = &dokuppgift {
         if let Some(upphavd_str) = dokuppgift.get_by_kod("upphavd") {
-            let (upphavd_at, _remaining) =
-                NaiveDate::parse_and_remainder(upphavd_str, "%Y-%m-%d").unwrap();
+            let (upphavd_at, _remaining) = NaiveDate::parse_and_remainder(upphavd_str, "%Y-%m-%d")
+                .map_err(|error| SfsPreprocessError::InvalidUpphavd {
+                    value: upphavd_str.to_string(),
+                    error,
+                })?;
             textelem.set_attr("upphavd", upphavd_at.to_string());
         }
         if let Some(upphnr) = dokuppgift.get_by_kod("upphnr") {
@@ -122,93 +161,58 @@ pub fn preprocess_json(source: &str) -> Result<Vec<u8>, SfsPreprocessError> {
     }
 
     if let Some(html) = dokument.html() {
-        process_html(html, &mut textelem)?;
+        warn_unknown_entities(dokument.dok_id, unknown_entities(html));
+        process_html(&clean_steps.clean_html(html), &mut textelem, tables)?;
     } else {
         return Err(SfsPreprocessError::HtmlFieldIsEmpty);
     }
-    if !(textelem.has_child("p", "") || textelem.has_child("page", "")) {
-        tracing::error!(docelem = ?docelem, textelem = ?textelem, "no p or page");
-        todo!("handle no p/page");
-    }
-    dbg!(&textelem);
-    let textelem = clean_element(&textelem); //.expect("Cleaning should work");
-    if !(textelem.has_child("p", "") || textelem.has_child("page", "")) {
+    let mut textelem = clean_element(&textelem, clean_steps); //.expect("Cleaning should work");
+
+    // Repealed statutes can be left without text
+    if minidom_collect_texts(&textelem).trim().is_empty() {
         tracing::warn!(docelem = ?docelem, textelem = ?textelem, "document contains no text");
     }
+    let scanned = is_scanned(&textelem);
+    let mut overgang =
+        split_overgangsbestammelser(&mut textelem, dokument.beteckning.filter(|b| !b.is_empty()));
+    if let Some(ocr_repair) = ocr_repair.filter(|_| scanned) {
+        for text in std::iter::once(&mut textelem).chain(&mut overgang) {
+            ocr_repair.repair(text);
+        }
+    }
 
     // Add text as child to dokument
     docelem.append_child(textelem);
 
-    // Serialize dokument
-    let mut result = Vec::new();
-    let mut writer = Writer::new_with_indent(&mut result, b' ', 2);
-    docelem.to_writer(&mut writer)?;
-    Ok(result)
+    // Add övergångsbestämmelser as separate texts
+    for overgang_text in overgang {
+        docelem.append_child(overgang_text);
+    }
+    Ok(docelem)
 }
 
-fn process_html(contents: &str, textelem: &mut Element) -> Result<(), SfsPreprocessError> {
-    static DOUBLE_ANGLES: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"<<([\w\s]+)>>").expect("regex failed"));
-    static NON_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(gr|t)?>").expect("regex failed"));
-    static DOUBLE_LEFT_ANGLES: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"<([\w\s]?<[/\w, \(:]+)").expect("regex failed"));
-
-    static LEFT_ANGLE_NON_TAG: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(
-            r#"<([jö:[:^alpha:]--/]|[bijlnrt] |be|ck|er|hl|i[iglt\("]|ln|nc|r[ijlnt]|s[s\d]|ui|[a-z/]?[A-Z»;,\.'-]|if )"#,
-        )
-        .expect("regex failed")
-    });
-    // static LEFT_ANGLE_NON_TAG: Lazy<Regex> = Lazy::new(|| {
-    //     Regex::new(
-    //         r"<([\dö\\: £?=\^’•\*«{_'■\)-]|[btrjil] |be|ck|er|i[gl]|nc|r[lnt]|s[s\d]|[a-z/]?[A-Z»;,\.]|if )",
-    //     )
-    //     .expect("regex failed")
-    // });
-    static ASTERIX_RIGHT_ANGLE: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"\*>").expect("regex failed"));
-    static UNEXPECTED_BANG: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"<!([^-])").expect("regex failed"));
-
-    let contents_processed = contents.replace("\\\"", r#"""#);
-    let contents_processed = contents_processed.replace("\r\n", " ");
-    let contents_processed = contents_processed.replace('&', "&amp;");
-
-    let contents_processed = NON_TAG.replace_all(&contents_processed, "&lt;${1}&gt;");
-    let contents_processed = ASTERIX_RIGHT_ANGLE.replace_all(&contents_processed, "*&gt;");
-    let contents_processed = DOUBLE_ANGLES.replace_all(&contents_processed, "« $1 »");
-    let contents_processed = DOUBLE_LEFT_ANGLES.replace_all(&contents_processed, "&lt;${1}");
-    let contents_processed = LEFT_ANGLE_NON_TAG.replace_all(&contents_processed, "&lt;${1}");
-    let contents_processed = UNEXPECTED_BANG.replace_all(&contents_processed, "&lt;!${1}");
-    // let contents = contents.replace("<<", "«");
-    // let contents = contents.replace(">>", "»");
-    let contents_processed = contents_processed.replace("<t>", " ");
-    // let contents = contents.replace("-<", "-&lt;");
-
-    fs::File::create("assets/contents.html")
-        .unwrap()
-        .write_all(contents_processed.as_bytes())
-        .unwrap();
-    let mut reader = Reader::from_str(&contents_processed);
-    loop {
-        match reader.read_event() {
-            Err(e) => {
-                return Err(SfsPreprocessError::XmlParsingError {
-                    pos: reader.buffer_position(),
-                    err: e,
-                })
-            }
-            Ok(Event::Eof) => break,
-            Ok(Event::Start(e)) => match e.name().as_ref() {
-                b"div" if attrib_equals(&e, b"class", b"dok") => {
-                    sfs_div_dok::process_html_sfs_div_dok(&mut reader, textelem)?;
-                }
-                _ => sfs_standard::process_html_sfs_standard(&mut reader, textelem)?,
-            },
-            Ok(e) => todo!("handle {:?}", e),
-        }
+fn process_html(
+    contents: &str,
+    textelem: &mut Element,
+    tables: TableMode,
+) -> Result<(), SfsPreprocessError> {
+    let dom = parse_html(contents);
+    let Some(body) = html_body(&dom) else {
+        return Ok(());
+    };
+
+    let dok = body
+        .children
+        .borrow()
+        .iter()
+        .find(|child| {
+            rcdom_elem_name(child).is_some() && rcdom_attr(child, "class").as_deref() == Some("dok")
+        })
+        .cloned();
+    match dok {
+        Some(dok) => sfs_div_dok::process_html_sfs_div_dok(&dok, textelem, tables),
+        None => sfs_standard::process_html_sfs_standard(&body, textelem, tables),
     }
-    Ok(())
 }
 
 #[cfg(test)]
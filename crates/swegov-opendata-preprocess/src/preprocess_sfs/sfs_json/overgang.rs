@@ -0,0 +1,146 @@
+//! Splitting the transitional provisions (övergångsbestämmelser) from the statute text.
+//!
+//! The transitional provisions follow the heading "Övergångsbestämmelser" at the end of the
+//! statute, one block for each act that changed it, starting with the SFS number of the act on
+//! a line of its own. The provisions before the first number are those of the statute itself.
+
+use minidom_extension::{
+    minidom::{Element, Node},
+    minidom_collect_texts,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// The SFS number that `p` starts with, if it is alone on the first line, e.g. "2003:257" for
+/// `<p>2003:257<br/> 1. Denna lag träder i kraft…</p>`.
+pub fn parse_overgang_sfs(p: &Element) -> Option<String> {
+    static SFS_NR: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^(?:SFS\s+)?(?P<sfs>\d{4}:\d+)\.?$").expect("valid regex"));
+    if p.name() != "p" {
+        return None;
+    }
+    let mut first_line = String::new();
+    for node in p.nodes() {
+        match node {
+            Node::Element(br) if br.name() == "br" => break,
+            Node::Element(elem) => first_line.push_str(&minidom_collect_texts(elem)),
+            Node::Text(text) => first_line.push_str(text),
+        }
+    }
+    let captures = SFS_NR.captures(first_line.trim())?;
+    Some(captures.name("sfs")?.as_str().to_string())
+}
+
+fn is_overgang_heading(elem: &Element) -> bool {
+    elem.name() == "p" && minidom_collect_texts(elem).trim() == "Övergångsbestämmelser"
+}
+
+fn has_overgang_heading(elem: &Element) -> bool {
+    elem.children().any(|child| {
+        is_overgang_heading(child) || (child.name() == "page" && has_overgang_heading(child))
+    })
+}
+
+/// Move the transitional provisions out of `textelem` into a
+/// `<text datatyp="overgangsbestammelse" sfs=…>` for each act, in the order of the text.
+///
+/// The texts get the attributes of `textelem`, except `datatyp`. The heading is removed and the
+/// provisions of the statute itself get its own SFS number, `grundforfattning_sfs`, or no `sfs`
+/// attribute if it is unknown. In a scanned document the provisions are kept in a `<page>`
+/// for each page they are on, and the pages left empty are removed from `textelem`.
+pub fn split_overgangsbestammelser(
+    textelem: &mut Element,
+    grundforfattning_sfs: Option<&str>,
+) -> Vec<Element> {
+    if !has_overgang_heading(textelem) {
+        return Vec::new();
+    }
+    let mut splitter = OvergangSplitter {
+        textelem: shallow_copy(textelem),
+        grundforfattning_sfs,
+        in_overgang: false,
+        overgang: Vec::new(),
+    };
+    splitter.split(textelem, None);
+    splitter.overgang
+}
+
+struct OvergangSplitter<'a> {
+    /// The main text without content, whose attributes the texts get.
+    textelem: Element,
+    grundforfattning_sfs: Option<&'a str>,
+    /// `true` after the heading.
+    in_overgang: bool,
+    overgang: Vec<Element>,
+}
+
+impl OvergangSplitter<'_> {
+    /// Split the children of `parent`, which is on `page` in a scanned document.
+    fn split(&mut self, parent: &mut Element, page: Option<&Element>) {
+        for node in parent.take_nodes() {
+            match node {
+                Node::Element(mut elem) if elem.name() == "page" => {
+                    let page = shallow_copy(&elem);
+                    self.split(&mut elem, Some(&page));
+                    if !self.in_overgang || elem.nodes().next().is_some() {
+                        parent.append_child(elem);
+                    }
+                }
+                Node::Element(elem) if !self.in_overgang && is_overgang_heading(&elem) => {
+                    self.in_overgang = true;
+                }
+                node if !self.in_overgang => parent.append_node(node),
+                Node::Element(elem) => {
+                    if let Some(sfs) = parse_overgang_sfs(&elem) {
+                        let text = self.overgang_text(Some(&sfs));
+                        self.overgang.push(text);
+                    }
+                    if self.overgang.is_empty() {
+                        let text = self.overgang_text(self.grundforfattning_sfs);
+                        self.overgang.push(text);
+                    }
+                    if let Some(text) = self.overgang.last_mut() {
+                        match page {
+                            Some(page) => page_in(text, page).append_child(elem),
+                            None => text.append_child(elem),
+                        };
+                    }
+                }
+                // Whitespace between the paragraphs
+                Node::Text(_) => (),
+            }
+        }
+    }
+
+    fn overgang_text(&self, sfs: Option<&str>) -> Element {
+        let mut text = self.textelem.clone();
+        text.set_attr("datatyp", "overgangsbestammelse");
+        text.set_attr("sfs", sfs);
+        text
+    }
+}
+
+/// The last child of `text` if it is `page`, otherwise a new copy of `page` appended to `text`.
+fn page_in<'a>(text: &'a mut Element, page: &Element) -> &'a mut Element {
+    let is_last = text
+        .children()
+        .last()
+        .is_some_and(|last| last.name() == "page" && last.attr("id") == page.attr("id"));
+    if !is_last {
+        text.append_child(page.clone());
+    }
+    text.children_mut()
+        .last()
+        .expect("text has a page as last child")
+}
+
+fn shallow_copy(elem: &Element) -> Element {
+    let mut copy = Element::bare(elem.name(), elem.ns());
+    for (name, value) in elem.attrs() {
+        copy.set_attr(name, value);
+    }
+    copy
+}
+
+#[cfg(test)]
+mod tests;
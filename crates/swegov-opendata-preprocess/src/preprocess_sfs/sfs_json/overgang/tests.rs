@@ -0,0 +1,164 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+fn p(text: &str) -> Element {
+    Element::builder("p", "").append(text).build()
+}
+
+fn to_string(elem: &Element) -> String {
+    let mut out = Vec::new();
+    elem.write_to(&mut out).expect("writing to a vec");
+    String::from_utf8(out).expect("valid utf8")
+}
+
+#[rstest]
+#[case(p("1985:458"), Some("1985:458"))]
+#[case(p("SFS 2003:257."), Some("2003:257"))]
+#[case(
+    Element::builder("p", "")
+        .append("2003:257")
+        .append(Element::bare("br", ""))
+        .append(" 1. Denna lag träder i kraft den 1 juli 2003.")
+        .build(),
+    Some("2003:257")
+)]
+#[case(p("Denna lag träder i kraft den 1 juli 1985."), None)]
+#[case(p("Lag (2003:257)."), None)]
+fn parse_overgang_sfs_finds_number_line(#[case] given: Element, #[case] expected: Option<&str>) {
+    assert_eq!(parse_overgang_sfs(&given).as_deref(), expected);
+}
+
+#[test]
+fn split_overgangsbestammelser_groups_by_act() {
+    let mut textelem = Element::builder("text", "")
+        .append(
+            Element::builder("paragraf", "")
+                .append(p("1 § Text."))
+                .build(),
+        )
+        .append(p("Övergångsbestämmelser"))
+        .append(p("Denna förordning träder i kraft den 1 juli 1976."))
+        .append(p("1985:458"))
+        .append(p("Denna förordning träder i kraft den 1 juli 1985."))
+        .append(p("1988:303"))
+        .append(p("Denna förordning träder i kraft den 1 juli 1988."))
+        .append(p("Äldre bestämmelser gäller."))
+        .build();
+
+    let overgang = split_overgangsbestammelser(&mut textelem, Some("1976:257"));
+
+    assert_eq!(
+        to_string(&textelem),
+        r#"<text xmlns=""><paragraf><p>1 § Text.</p></paragraf></text>"#
+    );
+    let overgang: Vec<_> = overgang.iter().map(to_string).collect();
+    assert_eq!(
+        overgang,
+        [
+            concat!(
+                r#"<text xmlns="" datatyp="overgangsbestammelse" sfs="1976:257">"#,
+                r#"<p>Denna förordning träder i kraft den 1 juli 1976.</p></text>"#
+            ),
+            concat!(
+                r#"<text xmlns="" datatyp="overgangsbestammelse" sfs="1985:458">"#,
+                r#"<p>1985:458</p><p>Denna förordning träder i kraft den 1 juli 1985.</p></text>"#
+            ),
+            concat!(
+                r#"<text xmlns="" datatyp="overgangsbestammelse" sfs="1988:303">"#,
+                r#"<p>1988:303</p><p>Denna förordning träder i kraft den 1 juli 1988.</p>"#,
+                r#"<p>Äldre bestämmelser gäller.</p></text>"#
+            ),
+        ]
+    );
+}
+
+#[test]
+fn split_overgangsbestammelser_leaves_text_without_heading() {
+    let mut textelem = Element::builder("text", "").append(p("1985:458")).build();
+
+    assert_eq!(
+        split_overgangsbestammelser(&mut textelem, Some("1976:257")),
+        []
+    );
+    assert_eq!(
+        to_string(&textelem),
+        r#"<text xmlns=""><p>1985:458</p></text>"#
+    );
+}
+
+#[test]
+fn split_overgangsbestammelser_copies_text_attributes() {
+    let mut textelem = Element::builder("text", "")
+        .attr("datatyp", "huvuddokument")
+        .attr("rm", "1976")
+        .attr("beteckning", "")
+        .append(p("1 § Text."))
+        .append(p("Övergångsbestämmelser"))
+        .append(p("Denna förordning träder i kraft den 1 juli 1976."))
+        .append(p("1985:458"))
+        .build();
+
+    let overgang = split_overgangsbestammelser(&mut textelem, None);
+
+    let overgang: Vec<_> = overgang.iter().map(to_string).collect();
+    assert_eq!(
+        overgang,
+        [
+            concat!(
+                r#"<text xmlns="" beteckning="" datatyp="overgangsbestammelse" rm="1976">"#,
+                r#"<p>Denna förordning träder i kraft den 1 juli 1976.</p></text>"#
+            ),
+            concat!(
+                r#"<text xmlns="" beteckning="" datatyp="overgangsbestammelse" rm="1976" "#,
+                r#"sfs="1985:458"><p>1985:458</p></text>"#
+            ),
+        ]
+    );
+    assert_eq!(textelem.attr("datatyp"), Some("huvuddokument"));
+}
+
+#[test]
+fn split_overgangsbestammelser_finds_heading_in_pages() {
+    let page = |id: &str, texts: &[&str]| {
+        let mut page = Element::builder("page", "").attr("id", id).build();
+        for text in texts {
+            page.append_child(p(text));
+        }
+        page
+    };
+    let mut textelem = Element::builder("text", "")
+        .append(page("1", &["1 § Text."]))
+        .append(page(
+            "2",
+            &["2 § Text.", "Övergångsbestämmelser", "1985:458", "Denna"],
+        ))
+        .append(page("3", &["förordning.", "1988:303", "Äldre."]))
+        .build();
+
+    let overgang = split_overgangsbestammelser(&mut textelem, Some("1976:257"));
+
+    assert_eq!(
+        to_string(&textelem),
+        concat!(
+            r#"<text xmlns=""><page id="1"><p>1 § Text.</p></page>"#,
+            r#"<page id="2"><p>2 § Text.</p></page></text>"#
+        )
+    );
+    let overgang: Vec<_> = overgang.iter().map(to_string).collect();
+    assert_eq!(
+        overgang,
+        [
+            concat!(
+                r#"<text xmlns="" datatyp="overgangsbestammelse" sfs="1985:458">"#,
+                r#"<page id="2"><p>1985:458</p><p>Denna</p></page>"#,
+                r#"<page id="3"><p>förordning.</p></page></text>"#
+            ),
+            concat!(
+                r#"<text xmlns="" datatyp="overgangsbestammelse" sfs="1988:303">"#,
+                r#"<page id="3"><p>1988:303</p><p>Äldre.</p></page></text>"#
+            ),
+        ]
+    );
+}
@@ -0,0 +1,533 @@
+//! Nesting of the statute text in `<avdelning>`, `<kapitel>`, `<paragraf>` and `<stycke>`.
+//!
+//! The html of a statute is a flat sequence of paragraphs. Chapters and divisions are found from
+//! their headings, paragrafer from the `<a class="paragraf">` anchors of the html or, in scanned
+//! documents, from their text. Every paragraph that follows a paragraf is a stycke of it.
+//! The ids are built like the anchors of the html, e.g. `K2P3S1` for the first stycke of
+//! 2 kap. 3 §. A provision that continues on the next page is split in one element per page,
+//! the parts after the first have a `del` attribute with their number and an id with it as a
+//! suffix, e.g. `K2P3-2`.
+//!
+//! The amendment notes in the text of a paragraf, e.g. "Lag (2019:123)." or
+//! "/Upphör att gälla U:2025-07-01/", are kept as attributes of the `<paragraf>`, see
+//! [`parse_amendments`]. Every part of a paragraf that continues over pages gets the notes of
+//! the whole paragraf, see [`set_amendment_attrs_of_parts`].
+
+use minidom_extension::{
+    elem_is_empty,
+    minidom::{Element, Node},
+    minidom_collect_texts,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Marks a `<p>` made from a heading of the html, removed when nesting.
+const RUBRIK_ATTR: &str = "sfs-rubrik";
+/// Marks the `<p>` that starts at a paragraf anchor with the name of the anchor, removed when
+/// nesting.
+const PARAGRAF_ATTR: &str = "sfs-paragraf";
+
+/// Mark `p` as a heading.
+pub fn mark_rubrik(p: &mut Element) {
+    p.set_attr(RUBRIK_ATTR, "true");
+}
+
+/// Mark `p` as the start of the paragraf with the anchor `name`, e.g. `K2P3`.
+pub fn mark_paragraf(p: &mut Element, name: &str) {
+    p.set_attr(PARAGRAF_ATTR, name);
+}
+
+/// The chapter and number of the paragraf with the anchor `name`, e.g. `(Some("2"), "3 a")`
+/// for `K2P3a`.
+pub fn parse_paragraf_anchor(name: &str) -> Option<(Option<String>, String)> {
+    static PARAGRAF_ANCHOR: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^(?:K(?P<kapitel>\d+[a-z]?))?P(?P<nr>\d+[a-z]?)$").expect("valid regex")
+    });
+    let captures = PARAGRAF_ANCHOR.captures(name)?;
+    let kapitel = captures
+        .name("kapitel")
+        .map(|kapitel| spaced_nr(kapitel.as_str()));
+    Some((kapitel, spaced_nr(captures.name("nr")?.as_str())))
+}
+
+/// The number of the chapter with the heading `text`, e.g. "2 a" for "2 a kap. Straff".
+pub fn parse_kapitel_heading(text: &str) -> Option<&str> {
+    static KAPITEL_HEADING: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"(?i)^(?P<nr>\d+(?:\s?[a-z])?)\s?kap\.(?:\s|$)").expect("valid regex")
+    });
+    let captures = KAPITEL_HEADING.captures(text.trim_start())?;
+    Some(captures.name("nr")?.as_str())
+}
+
+/// The number of the division with the heading `text`, e.g. "A" for "AVD. A ÖVERGRIPANDE
+/// BESTÄMMELSER" or "Första" for "Första avdelningen".
+pub fn parse_avdelning_heading(text: &str) -> Option<&str> {
+    static AVDELNING_HEADING: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r"(?i)^(?:(?:avd\.\s*|avdelning\s+)(?P<nr>[0-9a-zåäö]+)\b|(?P<ordinal>\w+)\s+avdelningen\b)",
+        )
+        .expect("valid regex")
+    });
+    let captures = AVDELNING_HEADING.captures(text.trim_start())?;
+    Some(captures.name("nr").or(captures.name("ordinal"))?.as_str())
+}
+
+/// The number of the paragraf that `text` starts with, e.g. "1" for "1 § Denna lag…" or
+/// "§ 1. Riksgäldskontoret…", and whether the text is nothing but the number.
+pub fn parse_paragraf_text(text: &str) -> Option<(&str, bool)> {
+    static PARAGRAF_TEXT: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r"^(?:§\s*(?P<old>\d+(?:\s?[a-z])?)\s*(?:[.:-]|$)|(?P<nr>\d+(?:\s?[a-z])?)\s?§)(?:\s|$)",
+        )
+        .expect("valid regex")
+    });
+    let text = text.trim();
+    let captures = PARAGRAF_TEXT.captures(text)?;
+    let nr = captures.name("old").or(captures.name("nr"))?.as_str();
+    let bare = captures.get(0)?.as_str().trim_end() == text;
+    Some((nr, bare))
+}
+
+/// The amendment notes of a paragraf, see [`parse_amendments`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Amendments<'a> {
+    /// The SFS number of the last act that changed the paragraf, e.g. "2019:123" for
+    /// "Lag (2019:123).".
+    pub andrad_genom: Option<&'a str>,
+    /// When this wording enters into force, e.g. "2025-07-01" for
+    /// "/Träder i kraft I:2025-07-01/".
+    pub ikraft: Option<&'a str>,
+    /// When this wording ceases to apply, e.g. "2025-07-01" for
+    /// "/Upphör att gälla U:2025-07-01/".
+    pub upphor: Option<&'a str>,
+}
+
+/// The amendment notes in the `text` of a paragraf.
+///
+/// Only the last note of each kind is used, since a paragraf that was changed again notes the
+/// latest act last.
+pub fn parse_amendments(text: &str) -> Amendments<'_> {
+    static ANDRAD_GENOM: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"\b(?:Lag|Förordning|Kungörelse|Tillkännagivande)\s*\((?P<sfs>\d{4}:\d+)\)")
+            .expect("valid regex")
+    });
+    static IKRAFT: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"/Träder i kraft I:\s*(?P<date>[^/]+?)\s*/").expect("valid regex")
+    });
+    static UPPHOR: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"/Upphör att gälla U:\s*(?P<date>[^/]+?)\s*/").expect("valid regex")
+    });
+    let last = |regex: &Regex, name: &str| {
+        regex
+            .captures_iter(text)
+            .last()
+            .and_then(|captures| captures.name(name))
+            .map(|m| m.as_str())
+    };
+    Amendments {
+        andrad_genom: last(&ANDRAD_GENOM, "sfs"),
+        ikraft: last(&IKRAFT, "date"),
+        upphor: last(&UPPHOR, "date"),
+    }
+}
+
+/// Set the `andrad_genom`, `ikraft` and `upphor` attributes of `paragraf` from its text, for the
+/// notes it has.
+fn set_amendment_attrs(paragraf: &mut Element) {
+    let text = minidom_collect_texts(paragraf);
+    set_amendments(paragraf, &parse_amendments(&text));
+}
+
+fn set_amendments(paragraf: &mut Element, amendments: &Amendments<'_>) {
+    let Amendments {
+        andrad_genom,
+        ikraft,
+        upphor,
+    } = amendments;
+    for (name, value) in [
+        ("andrad_genom", andrad_genom),
+        ("ikraft", ikraft),
+        ("upphor", upphor),
+    ] {
+        paragraf.set_attr(name, *value);
+    }
+}
+
+/// Set the amendment attributes of every part of the paragrafer in `textelem` that continue
+/// over pages from the text of all their parts, since the notes are usually at the end.
+pub fn set_amendment_attrs_of_parts(textelem: &mut Element) {
+    fn collect_parts(elem: &Element, parts: &mut Vec<(bool, String)>) {
+        for child in elem.children() {
+            if child.name() == "paragraf" {
+                parts.push((child.attr("del").is_some(), minidom_collect_texts(child)));
+            } else {
+                collect_parts(child, parts);
+            }
+        }
+    }
+    fn set_attrs(elem: &mut Element, texts: &mut impl Iterator<Item = String>) {
+        for child in elem.children_mut() {
+            if child.name() == "paragraf" {
+                if let Some(text) = texts.next() {
+                    set_amendments(child, &parse_amendments(&text));
+                }
+            } else {
+                set_attrs(child, texts);
+            }
+        }
+    }
+
+    let mut parts = Vec::new();
+    collect_parts(textelem, &mut parts);
+    if !parts.iter().any(|(continued, _)| *continued) {
+        return;
+    }
+    // The text of the whole paragraf for each of its parts
+    let mut texts = Vec::with_capacity(parts.len());
+    let mut start = 0;
+    for end in 1..=parts.len() {
+        if parts.get(end).is_some_and(|(continued, _)| *continued) {
+            continue;
+        }
+        let text = parts[start..end]
+            .iter()
+            .map(|(_, text)| text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        texts.extend(std::iter::repeat_n(text, end - start));
+        start = end;
+    }
+    set_attrs(textelem, &mut texts.into_iter());
+}
+
+/// "3 a" for "3a".
+fn spaced_nr(nr: &str) -> String {
+    match nr.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => format!("{} {}", &nr[..i], &nr[i..]),
+        None => nr.to_string(),
+    }
+}
+
+/// "3a" for "3 a".
+fn compact_nr(nr: &str) -> String {
+    nr.split_whitespace().collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Level {
+    Avdelning,
+    Kapitel,
+    Paragraf,
+    Stycke,
+}
+
+#[derive(Debug, PartialEq)]
+enum Block {
+    Avdelning(String),
+    Kapitel(String),
+    /// The start of a paragraf, `bare` if the paragraph holds nothing but its number.
+    Paragraf {
+        kapitel: Option<String>,
+        nr: String,
+        bare: bool,
+    },
+    /// A heading within a chapter.
+    Rubrik,
+    /// The heading of the transitional provisions, which are outside of all chapters.
+    Overgangsbestammelser,
+    Text,
+}
+
+/// A provision in progress.
+#[derive(Debug)]
+struct Open {
+    level: Level,
+    /// The id of the provision, without the suffix of a continued part.
+    id: String,
+    /// The number of the part, 1 unless continued from an earlier page.
+    del: usize,
+    elem: Element,
+}
+
+impl Open {
+    fn new(level: Level, name: &str, nr: &str, id: String) -> Self {
+        Self {
+            level,
+            elem: provision(name, nr, &id),
+            id,
+            del: 1,
+        }
+    }
+
+    /// An empty copy of this provision for the next page.
+    fn continued(&self) -> Self {
+        let del = self.del + 1;
+        let mut elem = Element::bare(self.elem.name(), self.elem.ns());
+        if let Some(nr) = self.elem.attr("nr") {
+            elem.set_attr("nr", nr);
+        }
+        elem.set_attr("id", format!("{}-{del}", self.id));
+        elem.set_attr("del", del);
+        Self {
+            level: self.level,
+            id: self.id.clone(),
+            del,
+            elem,
+        }
+    }
+}
+
+/// Nests the paragraphs of a statute, see the [module docs](self).
+#[derive(Debug, Default)]
+pub struct ProvisionNester {
+    /// Recognise paragrafer from their text, for scanned documents that have no anchors.
+    from_text: bool,
+    /// The provisions in progress, outermost first.
+    open: Vec<Open>,
+    /// The number of stycken of the current paragraf so far.
+    stycken: usize,
+}
+
+impl ProvisionNester {
+    pub fn scanned() -> Self {
+        Self {
+            from_text: true,
+            ..Default::default()
+        }
+    }
+
+    /// Nest the children of `parent`.
+    ///
+    /// The provisions that are open at the end are continued by the next call, so a paragraf
+    /// that continues on the next page is split in one `<paragraf>` per page, see the
+    /// [module docs](self).
+    pub fn nest(&mut self, parent: &mut Element) {
+        let nodes = parent.take_nodes();
+        for node in nodes {
+            match node {
+                Node::Element(elem) => self.push_elem(parent, elem),
+                node => self.push(parent, node),
+            }
+        }
+        let continued: Vec<_> = self.open.iter().map(Open::continued).collect();
+        self.close(parent, Level::Avdelning);
+        self.open = continued;
+    }
+
+    fn push_elem(&mut self, parent: &mut Element, mut elem: Element) {
+        match self.classify(&mut elem) {
+            Block::Avdelning(nr) => {
+                self.close(parent, Level::Avdelning);
+                let id = format!("AVD{}", compact_nr(&nr));
+                self.open
+                    .push(Open::new(Level::Avdelning, "avdelning", &nr, id));
+                self.push(parent, Node::Element(elem));
+            }
+            Block::Kapitel(nr) => {
+                self.open_kapitel(parent, &nr);
+                self.push(parent, Node::Element(elem));
+            }
+            Block::Paragraf { kapitel, nr, bare } => {
+                if let Some(kapitel) = kapitel {
+                    if self.nr(Level::Kapitel) != Some(kapitel.as_str()) {
+                        self.open_kapitel(parent, &kapitel);
+                    }
+                }
+                self.close(parent, Level::Paragraf);
+                let id = format!("{}P{}", self.id(Level::Kapitel), compact_nr(&nr));
+                self.open
+                    .push(Open::new(Level::Paragraf, "paragraf", &nr, id));
+                self.stycken = 0;
+                if bare {
+                    self.push(parent, Node::Element(elem));
+                } else {
+                    self.push_stycke(parent, elem);
+                }
+            }
+            Block::Rubrik => {
+                self.close(parent, Level::Paragraf);
+                self.push(parent, Node::Element(elem));
+            }
+            Block::Overgangsbestammelser => {
+                self.close(parent, Level::Avdelning);
+                self.push(parent, Node::Element(elem));
+            }
+            Block::Text if elem.name() == "p" && self.nr(Level::Paragraf).is_some() => {
+                self.push_stycke(parent, elem);
+            }
+            Block::Text => self.push(parent, Node::Element(elem)),
+        }
+    }
+
+    fn classify(&self, elem: &mut Element) -> Block {
+        if elem.name() != "p" {
+            return Block::Text;
+        }
+        let rubrik = elem.attr(RUBRIK_ATTR).is_some();
+        let anchor = elem.attr(PARAGRAF_ATTR).map(String::from);
+        elem.set_attr(RUBRIK_ATTR, None::<String>);
+        elem.set_attr(PARAGRAF_ATTR, None::<String>);
+
+        if let Some((kapitel, nr)) = anchor.as_deref().and_then(parse_paragraf_anchor) {
+            return Block::Paragraf {
+                kapitel,
+                nr,
+                bare: false,
+            };
+        }
+        let text = minidom_collect_texts(elem);
+        let text = text.trim();
+        // Without markup, only short lines without references are taken as headings
+        if rubrik || (self.from_text && text.chars().count() <= 100 && !text.contains('§')) {
+            if text == "Övergångsbestämmelser" {
+                return Block::Overgangsbestammelser;
+            }
+            if let Some(nr) = parse_kapitel_heading(text) {
+                return Block::Kapitel(spaced_nr(&compact_nr(nr)));
+            }
+            if let Some(nr) = parse_avdelning_heading(text) {
+                return Block::Avdelning(nr.to_string());
+            }
+        }
+        if rubrik {
+            return Block::Rubrik;
+        }
+        if self.from_text {
+            if let Some((nr, bare)) = parse_paragraf_text(text) {
+                return Block::Paragraf {
+                    kapitel: None,
+                    nr: spaced_nr(&compact_nr(nr)),
+                    bare,
+                };
+            }
+        }
+        Block::Text
+    }
+
+    fn open_kapitel(&mut self, parent: &mut Element, nr: &str) {
+        self.close(parent, Level::Kapitel);
+        let id = format!("K{}", compact_nr(nr));
+        self.open.push(Open::new(Level::Kapitel, "kapitel", nr, id));
+    }
+
+    fn push_stycke(&mut self, parent: &mut Element, mut p: Element) {
+        self.close(parent, Level::Stycke);
+        self.stycken += 1;
+        let nr = self.stycken.to_string();
+        let id = format!("{}S{}", self.id(Level::Paragraf), nr);
+        mark_punkter(&mut p, &id);
+        self.open.push(Open::new(Level::Stycke, "stycke", &nr, id));
+        self.push(parent, Node::Element(p));
+    }
+
+    /// Append `node` to the innermost provision in progress.
+    fn push(&mut self, parent: &mut Element, node: Node) {
+        match self.open.last_mut() {
+            Some(open) => open.elem.append_node(node),
+            None => parent.append_node(node),
+        }
+    }
+
+    /// Close the provisions at `level` and below, dropping those that were continued from the
+    /// previous page but got no content.
+    fn close(&mut self, parent: &mut Element, level: Level) {
+        while self.open.last().is_some_and(|open| open.level >= level) {
+            if let Some(Open {
+                level: open,
+                mut elem,
+                ..
+            }) = self.open.pop()
+            {
+                if open == Level::Paragraf {
+                    set_amendment_attrs(&mut elem);
+                }
+                if !elem_is_empty(&elem) {
+                    self.push(parent, Node::Element(elem));
+                }
+            }
+        }
+    }
+
+    fn nr(&self, level: Level) -> Option<&str> {
+        self.open
+            .iter()
+            .find(|open| open.level == level)
+            .and_then(|open| open.elem.attr("nr"))
+    }
+
+    fn id(&self, level: Level) -> &str {
+        self.open
+            .iter()
+            .find(|open| open.level == level)
+            .map_or("", |open| open.id.as_str())
+    }
+}
+
+fn provision(name: &str, nr: &str, id: &str) -> Element {
+    Element::builder(name, "")
+        .attr("nr", nr)
+        .attr("id", id)
+        .build()
+}
+
+/// The number of the item that a line starts with, e.g. "1" for "1. …" or "2:o) …", or "a" for
+/// "a) …".
+pub fn parse_punkt(line: &str) -> Option<&str> {
+    static PUNKT: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^(?:(?P<nr>\d+)(?:\.|:o\))|(?P<letter>[a-zåäö])\))(?:\s|$)")
+            .expect("valid regex")
+    });
+    let captures = PUNKT.captures(line.trim_start())?;
+    Some(captures.name("nr").or(captures.name("letter"))?.as_str())
+}
+
+/// Wrap the lines of `p` that start an item of a list in `<punkt>`.
+///
+/// The lines are separated by `<br>`, which are kept between the items. Items with a letter get
+/// an id below the last numbered item, e.g. `…S1N3a`.
+fn mark_punkter(p: &mut Element, stycke_id: &str) {
+    let nodes = p.take_nodes();
+    let mut lines: Vec<Vec<Node>> = vec![Vec::new()];
+    for node in nodes {
+        match node {
+            Node::Element(br) if br.name() == "br" => {
+                lines.push(vec![Node::Element(br)]);
+            }
+            node => lines.last_mut().expect("a line").push(node),
+        }
+    }
+    let mut last_nr = String::new();
+    for mut line in lines {
+        let br = match line.first() {
+            Some(Node::Element(elem)) if elem.name() == "br" => Some(line.remove(0)),
+            _ => None,
+        };
+        if let Some(br) = br {
+            p.append_node(br);
+        }
+        let nr = match line.first() {
+            Some(Node::Text(text)) => parse_punkt(text),
+            _ => None,
+        };
+        let Some(nr) = nr.map(String::from) else {
+            for node in line {
+                p.append_node(node);
+            }
+            continue;
+        };
+        let id = if nr.starts_with(|c: char| c.is_ascii_digit()) {
+            last_nr.clone_from(&nr);
+            format!("{stycke_id}N{nr}")
+        } else {
+            format!("{stycke_id}N{last_nr}{nr}")
+        };
+        let mut punkt = provision("punkt", &nr, &id);
+        for node in line {
+            punkt.append_node(node);
+        }
+        p.append_child(punkt);
+    }
+}
+
+#[cfg(test)]
+mod tests;
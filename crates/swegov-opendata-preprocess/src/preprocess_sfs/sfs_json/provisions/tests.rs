@@ -0,0 +1,313 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+#[case("P1", None, "1")]
+#[case("K2P3", Some("2"), "3")]
+#[case("K2aP3b", Some("2 a"), "3 b")]
+fn parse_paragraf_anchor_finds_numbers(
+    #[case] given: &str,
+    #[case] kapitel: Option<&str>,
+    #[case] nr: &str,
+) {
+    assert_eq!(
+        parse_paragraf_anchor(given),
+        Some((kapitel.map(String::from), nr.to_string()))
+    );
+}
+
+#[rstest]
+#[case("S1")]
+#[case("P1S2")]
+#[case("overgang")]
+fn parse_paragraf_anchor_ignores_other_anchors(#[case] given: &str) {
+    assert_eq!(parse_paragraf_anchor(given), None);
+}
+
+#[rstest]
+#[case("1 kap. Inledande bestämmelser", Some("1"))]
+#[case("2 a kap. Straff", Some("2 a"))]
+#[case("3 Kap.", Some("3"))]
+#[case("Av 6 kap. 1 § jordabalken följer", None)]
+#[case("Inledande bestämmelser", None)]
+fn parse_kapitel_heading_finds_chapters(#[case] given: &str, #[case] expected: Option<&str>) {
+    assert_eq!(parse_kapitel_heading(given), expected);
+}
+
+#[rstest]
+#[case("AVD. A ÖVERGRIPANDE BESTÄMMELSER", Some("A"))]
+#[case("Avdelning II Skatt", Some("II"))]
+#[case("Första avdelningen", Some("Första"))]
+#[case("Avdelningen ska", None)]
+fn parse_avdelning_heading_finds_divisions(#[case] given: &str, #[case] expected: Option<&str>) {
+    assert_eq!(parse_avdelning_heading(given), expected);
+}
+
+#[rstest]
+#[case("1 § Denna lag gäller", Some(("1", false)))]
+#[case("3 a § Om", Some(("3 a", false)))]
+#[case("§ 2.", Some(("2", true)))]
+#[case("§4-", Some(("4", true)))]
+#[case("§ 1. Riksgäldskontoret har", Some(("1", false)))]
+#[case("§ 2 mom. 2 af kungörelsen", None)]
+#[case("§§ 5 — 13.", None)]
+#[case("enligt 4 § skall", None)]
+fn parse_paragraf_text_finds_paragrafer(
+    #[case] given: &str,
+    #[case] expected: Option<(&str, bool)>,
+) {
+    assert_eq!(parse_paragraf_text(given), expected);
+}
+
+#[rstest]
+#[case("   1. för registrering", Some("1"))]
+#[case("2:o) att låneunderstödet", Some("2"))]
+#[case("a) innehållet", Some("a"))]
+#[case("1985:458", None)]
+#[case("Lag (2003:257).", None)]
+fn parse_punkt_finds_items(#[case] given: &str, #[case] expected: Option<&str>) {
+    assert_eq!(parse_punkt(given), expected);
+}
+
+#[rstest]
+#[case("Lag (2019:123).", Amendments { andrad_genom: Some("2019:123"), ..Default::default() })]
+#[case(
+    "Förordning\n(1988:303).",
+    Amendments { andrad_genom: Some("1988:303"), ..Default::default() }
+)]
+#[case(
+    "Lag (2005:1). Text. Lag (2010:2).",
+    Amendments { andrad_genom: Some("2010:2"), ..Default::default() }
+)]
+#[case(
+    "/Upphör att gälla U:2025-07-01/ 3 § Text. Lag (2020:5).",
+    Amendments { andrad_genom: Some("2020:5"), upphor: Some("2025-07-01"), ..Default::default() }
+)]
+#[case(
+    "/Träder i kraft I:den dag regeringen bestämmer/ 3 § Text.",
+    Amendments { ikraft: Some("den dag regeringen bestämmer"), ..Default::default() }
+)]
+#[case("enligt lagen (1966:368) om", Amendments::default())]
+fn parse_amendments_finds_notes(#[case] given: &str, #[case] expected: Amendments<'_>) {
+    assert_eq!(parse_amendments(given), expected);
+}
+
+fn p(text: &str) -> Element {
+    Element::builder("p", "").append(text).build()
+}
+
+fn rubrik(text: &str) -> Element {
+    let mut p = p(text);
+    mark_rubrik(&mut p);
+    p
+}
+
+fn paragraf(anchor: &str, text: &str) -> Element {
+    let mut p = p(text);
+    mark_paragraf(&mut p, anchor);
+    p
+}
+
+fn to_string(elem: &Element) -> String {
+    let mut out = Vec::new();
+    elem.write_to(&mut out).expect("writing to a vec");
+    String::from_utf8(out).expect("valid utf8")
+}
+
+#[test]
+fn nest_groups_marked_paragraphs() {
+    let mut textelem = Element::builder("text", "")
+        .append(rubrik("1 kap. Allmänt"))
+        .append(paragraf("K1P1", "1 § Första."))
+        .append(p("Andra stycket."))
+        .append(rubrik("Rubrik"))
+        .append(paragraf("K2P1", "1 § Nytt kapitel."))
+        .append(rubrik("Övergångsbestämmelser"))
+        .append(p("Denna lag träder i kraft."))
+        .build();
+
+    ProvisionNester::default().nest(&mut textelem);
+
+    assert_eq!(
+        to_string(&textelem),
+        concat!(
+            r#"<text xmlns="">"#,
+            r#"<kapitel id="K1" nr="1"><p>1 kap. Allmänt</p>"#,
+            r#"<paragraf id="K1P1" nr="1">"#,
+            r#"<stycke id="K1P1S1" nr="1"><p>1 § Första.</p></stycke>"#,
+            r#"<stycke id="K1P1S2" nr="2"><p>Andra stycket.</p></stycke>"#,
+            r#"</paragraf><p>Rubrik</p></kapitel>"#,
+            r#"<kapitel id="K2" nr="2"><paragraf id="K2P1" nr="1">"#,
+            r#"<stycke id="K2P1S1" nr="1"><p>1 § Nytt kapitel.</p></stycke>"#,
+            r#"</paragraf></kapitel>"#,
+            r#"<p>Övergångsbestämmelser</p><p>Denna lag träder i kraft.</p>"#,
+            r#"</text>"#
+        )
+    );
+}
+
+#[test]
+fn nest_continues_paragraf_on_next_page() {
+    let mut first = Element::builder("page", "")
+        .append(p("Förordning"))
+        .append(p("§ 1."))
+        .append(p("Text."))
+        .build();
+    let mut second = Element::builder("page", "").append(p("Mer text.")).build();
+
+    let mut nester = ProvisionNester::scanned();
+    nester.nest(&mut first);
+    nester.nest(&mut second);
+
+    assert_eq!(
+        to_string(&first),
+        concat!(
+            r#"<page xmlns=""><p>Förordning</p><paragraf id="P1" nr="1"><p>§ 1.</p>"#,
+            r#"<stycke id="P1S1" nr="1"><p>Text.</p></stycke></paragraf></page>"#
+        )
+    );
+    assert_eq!(
+        to_string(&second),
+        concat!(
+            r#"<page xmlns=""><paragraf del="2" id="P1-2" nr="1">"#,
+            r#"<stycke id="P1S2" nr="2"><p>Mer text.</p></stycke></paragraf></page>"#
+        )
+    );
+}
+
+#[test]
+fn nest_gives_parts_on_later_pages_distinct_ids() {
+    let mut pages = [
+        Element::builder("page", "")
+            .append(p("1 kap. Allmänt"))
+            .append(p("1 § Text som"))
+            .build(),
+        Element::builder("page", "").append(p("fortsätter")).build(),
+        Element::builder("page", "")
+            .append(p("och slutar."))
+            .append(p("2 § Ny."))
+            .build(),
+    ];
+
+    let mut nester = ProvisionNester::scanned();
+    for page in &mut pages {
+        nester.nest(page);
+    }
+
+    let ids: Vec<_> = pages
+        .iter()
+        .map(|page| {
+            page.children()
+                .flat_map(|kapitel| kapitel.children())
+                .filter_map(|paragraf| paragraf.attr("id"))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    assert_eq!(ids, [vec!["K1P1"], vec!["K1P1-2"], vec!["K1P1-3", "K1P2"]]);
+    assert_eq!(
+        pages
+            .each_ref()
+            .map(|page| page.children().next().and_then(|k| k.attr("id"))),
+        [Some("K1"), Some("K1-2"), Some("K1-3")]
+    );
+}
+
+#[test]
+fn set_amendment_attrs_of_parts_uses_whole_paragraf() {
+    let mut textelem = Element::bare("text", "");
+    let mut nester = ProvisionNester::scanned();
+    for lines in [
+        ["1 § Text som", "fortsätter"],
+        ["på nästa sida. Lag (2019:123).", "2 § Ny. Lag (2020:1)."],
+    ] {
+        let mut page = Element::bare("page", "");
+        for line in lines {
+            page.append_child(p(line));
+        }
+        nester.nest(&mut page);
+        textelem.append_child(page);
+    }
+
+    set_amendment_attrs_of_parts(&mut textelem);
+
+    let attrs: Vec<_> = textelem
+        .children()
+        .flat_map(Element::children)
+        .map(|paragraf| (paragraf.attr("id"), paragraf.attr("andrad_genom")))
+        .collect();
+    assert_eq!(
+        attrs,
+        [
+            (Some("P1"), Some("2019:123")),
+            (Some("P1-2"), Some("2019:123")),
+            (Some("P2"), Some("2020:1")),
+        ]
+    );
+}
+
+#[test]
+fn nest_marks_punkter() {
+    let mut textelem = Element::builder("text", "")
+        .append(
+            Element::builder("p", "")
+                .append("3 § Registret får användas")
+                .append(Element::bare("br", ""))
+                .append(" 1. för registrering,")
+                .append(Element::bare("br", ""))
+                .append(" a) av inteckningar,")
+                .append(Element::bare("br", ""))
+                .append(Element::builder("i", "").append("Lag (2003:257)").build())
+                .build(),
+        )
+        .build();
+    if let Some(first) = textelem.children_mut().next() {
+        mark_paragraf(first, "P3");
+    }
+
+    ProvisionNester::default().nest(&mut textelem);
+
+    assert_eq!(
+        to_string(&textelem),
+        concat!(
+            r#"<text xmlns=""><paragraf andrad_genom="2003:257" id="P3" nr="3">"#,
+            r#"<stycke id="P3S1" nr="1">"#,
+            r#"<p>3 § Registret får användas<br/>"#,
+            r#"<punkt id="P3S1N1" nr="1"> 1. för registrering,</punkt><br/>"#,
+            r#"<punkt id="P3S1N1a" nr="a"> a) av inteckningar,</punkt><br/>"#,
+            r#"<i>Lag (2003:257)</i></p></stycke></paragraf></text>"#
+        )
+    );
+}
+
+#[test]
+fn nest_sets_amendment_attrs_on_paragraf() {
+    let mut textelem = Element::builder("text", "")
+        .append(paragraf(
+            "P1",
+            "/Upphör att gälla U:2025-07-01/ 1 § Text. Lag (2019:123).",
+        ))
+        .append(paragraf("P2", "2 § Oförändrad."))
+        .build();
+
+    ProvisionNester::default().nest(&mut textelem);
+
+    let attrs: Vec<_> = textelem
+        .children()
+        .map(|paragraf| {
+            ["andrad_genom", "ikraft", "upphor"].map(|name| paragraf.attr(name).map(String::from))
+        })
+        .collect();
+    assert_eq!(
+        attrs,
+        [
+            [
+                Some("2019:123".to_string()),
+                None,
+                Some("2025-07-01".to_string())
+            ],
+            [None, None, None],
+        ]
+    );
+}
@@ -1,250 +1,117 @@
-use std::borrow::Cow;
+use std::convert::Infallible;
 
-use minidom::{
-    quick_xml::{events::Event, Reader},
-    Element,
+use markup5ever_rcdom::{Handle, NodeData};
+use minidom::Element;
+use minidom_extension::{elem_is_empty, minidom};
+
+use crate::{
+    nodeinfo::{rcdom_attr, rcdom_elem_name, rcdom_text_content},
+    shared::{
+        html::Paragraphs,
+        tables::{structured_table, TableMode},
+    },
+};
+
+use super::{
+    provisions::{mark_paragraf, mark_rubrik, ProvisionNester},
+    SfsPreprocessError,
 };
-use minidom_extension::{attrib_query::attrib_equals, elem_is_empty, minidom};
 
-use super::SfsPreprocessError;
+/// Metadata keys whose link is copied to an attribute of the text.
+const METADATA_LINKS: &[&str] = &["Ändringsregister", "Källa"];
 
+/// Process an SFS document that starts with a metadata block followed by the statute in a `div`.
+///
+/// The provisions are recognised from the headings and the paragraf anchors of the html.
 pub fn process_html_sfs_standard(
-    reader: &mut Reader<&[u8]>,
+    body: &Handle,
     textelem: &mut Element,
+    tables: TableMode,
 ) -> Result<(), SfsPreprocessError> {
-    let mut state = ParseHtmlState::Start;
-    loop {
-        match reader.read_event() {
-            Err(e) => {
-                return Err(SfsPreprocessError::XmlParsingError {
-                    pos: reader.buffer_position(),
-                    err: e,
-                })
-            }
-            Ok(Event::Eof) => break,
-            Ok(Event::Start(e)) => {
-                if let ParseHtmlState::Skip { tag: _ } = &state {
-                    continue;
-                }
-                state = match e.name().as_ref() {
-                    b"style" => ParseHtmlState::Skip { tag: b"style" },
-                    b"b" => {
-                        if let ParseHtmlState::Start = state {
-                            ParseHtmlState::ExtractMetadata
-                        } else {
-                            state
-                        }
-                    }
-                    b"a" => match state {
-                        ParseHtmlState::ExtractMetadataFoundKey { key } => {
-                            let mut new_state = None;
-                            for attr in e.attributes() {
-                                let attr =
-                                    attr.map_err(|err| SfsPreprocessError::XmlParsingAttrError {
-                                        pos: reader.buffer_position(),
-                                        err,
-                                    })?;
-                                if attr.key.local_name().as_ref() == b"href" {
-                                    textelem.set_attr(
-                                        key.as_ref().to_lowercase(),
-                                        attr.unescape_value().unwrap().as_ref(),
-                                    );
-                                    new_state = Some(ParseHtmlState::ExtractMetadata);
-                                }
-                            }
-                            new_state.unwrap_or(ParseHtmlState::ExtractMetadataFoundKey { key })
-                        }
-                        ParseHtmlState::Paragraph => {
-                            continue;
-                        }
-                        _ => todo!(
-                            "handle start a e={:?} state={:?} pos={}",
-                            e,
-                            state,
-                            reader.buffer_position()
-                        ),
-                    },
-                    b"div" => match state {
-                        ParseHtmlState::ExtractMetadata | ParseHtmlState::Start => {
-                            if attrib_equals(&e, b"class", b"sfstoc") {
-                                ParseHtmlState::Skip { tag: b"div" }
-                            } else {
-                                let paragraphs = extract_paragraphs(reader)?;
-                                for paragraph in paragraphs {
-                                    textelem.append_child(paragraph);
-                                }
-                                ParseHtmlState::Dokument
-                            }
-                        }
-                        _ => todo!(),
-                    },
-                    b"p" => match state {
-                        ParseHtmlState::Dokument | ParseHtmlState::Start => {
-                            let paragraphs = extract_paragraphs(reader)?;
-                            for paragraph in paragraphs {
-                                textelem.append_child(paragraph);
-                            }
-                            ParseHtmlState::Paragraph
-                        }
-                        _ => todo!("handle {:?} for state={:?}", e, state),
-                    },
-                    _ => todo!("handle {:?}", e),
+    let mut metadata_key = None;
+    for child in body.children.borrow().iter() {
+        match rcdom_elem_name(child) {
+            Some("b") => {
+                let key = rcdom_text_content(child);
+                if METADATA_LINKS.contains(&key.as_str()) {
+                    metadata_key = Some(key);
                 }
             }
-            Ok(Event::Text(content)) => {
-                match state {
-                    ParseHtmlState::Start | ParseHtmlState::Skip { tag: _ } => continue,
-                    _ => (),
-                }
-                let content_text =
-                    content
-                        .unescape()
-                        .map_err(|err| SfsPreprocessError::XmlParsingError {
-                            pos: reader.buffer_position(),
-                            err,
-                        })?;
-                match state {
-                    ParseHtmlState::ExtractMetadata => {
-                        if ["Ändringsregister", "Källa"].contains(&content_text.as_ref()) {
-                            state = ParseHtmlState::ExtractMetadataFoundKey { key: content_text };
-                        }
-                    }
-                    ParseHtmlState::ExtractMetadataFoundKey { key: _ } => continue,
-                    _ => todo!("handle content_text='{}'", content_text),
+            Some("a") => {
+                if let (Some(key), Some(href)) = (&metadata_key, rcdom_attr(child, "href")) {
+                    textelem.set_attr(key.to_lowercase(), href);
+                    metadata_key = None;
                 }
             }
-            Ok(Event::End(e)) => {
-                if let ParseHtmlState::Skip { tag } = state {
-                    if e.name().as_ref() == tag {
-                        state = ParseHtmlState::Start;
-                    }
-                    continue;
-                }
-                match e.name().as_ref() {
-                    b"style" => state = ParseHtmlState::Start,
-                    b"a" => match state {
-                        ParseHtmlState::ExtractMetadata => continue,
-                        ParseHtmlState::Paragraph => continue,
-
-                        _ => todo!(),
-                    },
-                    b"b" => match state {
-                        ParseHtmlState::ExtractMetadata
-                        | ParseHtmlState::ExtractMetadataFoundKey { key: _ } => continue,
-                        _ => todo!(),
-                    },
-                    _ => todo!(),
-                }
+            Some("div") if rcdom_attr(child, "class").as_deref() == Some("sfstoc") => (),
+            Some("div" | "p") => {
+                let mut paragraphs = Paragraphs::new(textelem);
+                extract_paragraphs(child, &mut paragraphs, tables);
             }
-            Ok(Event::Empty(e)) => match e.name().as_ref() {
-                b"p" => {
-                    let paragraphs = extract_paragraphs(reader)?;
-                    for paragraph in paragraphs {
-                        textelem.append_child(paragraph);
-                    }
-                    state = ParseHtmlState::Paragraph
-                }
-                _ => continue,
-            },
-            Ok(Event::Comment(_)) => continue,
-            Ok(e) => todo!("handle {:?}", e),
+            _ => (),
         }
     }
+    ProvisionNester::default().nest(textelem);
     Ok(())
 }
 
-#[derive(Debug, Clone, PartialEq)]
-enum ParseHtmlState<'a> {
-    Start,
-    ExtractMetadata,
-    ExtractMetadataFoundKey { key: Cow<'a, str> },
-    Dokument,
-    Paragraph,
-    Skip { tag: &'static [u8] },
-}
-
-pub fn extract_paragraphs(reader: &mut Reader<&[u8]>) -> Result<Vec<Element>, SfsPreprocessError> {
-    let mut paragraphs = Vec::new();
-    let mut curr = Some(Element::bare("p", ""));
-    loop {
-        match reader.read_event() {
-            Err(err) => {
-                tracing_log_error::log_error!(err, pos = reader.buffer_position(), "handle err ");
-                todo!("handle err {:?}, pos={}", err, reader.buffer_position());
+/// Split the content of `node` into paragraphs at `p` and headings.
+///
+/// A `p` starts a new paragraph that also holds the text following it, since the statute
+/// text is mostly written between empty `<p><a name="…"></a></p>` anchors. A flattened table
+/// gets a paragraph for each cell.
+pub fn extract_paragraphs(node: &Handle, paragraphs: &mut Paragraphs<'_>, tables: TableMode) {
+    for child in node.children.borrow().iter() {
+        match &child.data {
+            NodeData::Text { contents } => {
+                let text = contents.borrow();
+                if paragraphs.current_if_open().is_some() || !text.trim().is_empty() {
+                    paragraphs.current().append_text_node(text.as_ref());
+                }
             }
-            Ok(Event::Start(e)) => match e.name().as_ref() {
-                b"a" => continue,
-                b"p" => {
-                    if let Some(elem) = curr.take() {
-                        paragraphs.push(elem);
-                    }
-                    curr = Some(Element::bare("p", ""));
+            NodeData::Element { name, .. } => match &*name.local {
+                "p" => {
+                    paragraphs.start();
+                    extract_paragraphs(child, paragraphs, tables);
                 }
-                b"pre" => continue,
-                b"b" | b"i" => continue,
-                b"h1" | b"h2" | b"h3" | b"h4" => {
-                    if let Some(elem) = curr.take() {
-                        if !elem_is_empty(&elem) {
-                            paragraphs.push(elem);
-                        }
-                    }
-                    curr = Some(Element::bare("p", ""));
+                "table" if tables == TableMode::Structure => {
+                    let Ok(table) = structured_table(child, |cell, paragraphs| {
+                        extract_paragraphs(cell, paragraphs, tables);
+                        Ok::<_, Infallible>(())
+                    });
+                    paragraphs.append(table);
                 }
-                _ => todo!("handle Start {:?} ", e),
-            },
-            Ok(Event::End(e)) => match e.name().as_ref() {
-                b"a" => continue,
-                b"p" => continue,
-
-                b"pre" => continue,
-                b"div" => break,
-                b"b" | b"i" => continue,
-                b"h1" | b"h2" | b"h3" | b"h4" => {
-                    if let Some(elem) = curr.take() {
-                        paragraphs.push(elem);
-                    }
-                    curr = Some(Element::bare("p", ""));
+                "table" if tables == TableMode::Exclude => paragraphs.flush(),
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                    mark_rubrik(paragraphs.start());
+                    extract_paragraphs(child, paragraphs, tables);
+                    paragraphs.flush();
                 }
-                _ => todo!("handle End {:?} ", e),
-            },
-            Ok(Event::Text(content)) => {
-                let text = match content.unescape() {
-                    Ok(text) => text.to_string(),
-                    Err(err) => {
-                        tracing_log_error::log_error!(err, "making text of error");
-                        let err_content = content.into_inner();
-                        match String::from_utf8(err_content.to_vec()) {
-                            Ok(text) => text,
-                            Err(err) => {
-                                return Err(SfsPreprocessError::XmlFromUtf8Error {
-                                    pos: reader.buffer_position(),
-                                    err,
-                                });
-                            }
-                        }
+                "a" if rcdom_attr(child, "class").as_deref() == Some("paragraf") => {
+                    // The anchor usually follows an empty `<p>`, otherwise it starts a paragraph
+                    if paragraphs
+                        .current_if_open()
+                        .is_some_and(|p| !elem_is_empty(p))
+                    {
+                        paragraphs.start();
                     }
-                };
-                if let Some(elem) = &mut curr {
-                    elem.append_text_node(text);
-                }
-            }
-            Ok(Event::Empty(e)) => match e.name().as_ref() {
-                b"br" => {
-                    if let Some(elem) = &mut curr {
-                        elem.append_child(Element::bare("br", ""));
+                    if let Some(name) = rcdom_attr(child, "name") {
+                        mark_paragraf(paragraphs.current(), &name);
                     }
+                    extract_paragraphs(child, paragraphs, tables);
+                }
+                "div" | "li" | "td" | "th" => {
+                    paragraphs.start();
+                    extract_paragraphs(child, paragraphs, tables);
+                    paragraphs.flush();
                 }
-                b"p" => continue,
-                _ => todo!("handle Empty: {:?}", e),
+                "br" => {
+                    paragraphs.current().append_child(Element::bare("br", ""));
+                }
+                "style" | "script" => (),
+                _ => extract_paragraphs(child, paragraphs, tables),
             },
-            Ok(Event::Eof) => break,
-            Ok(e) => todo!("handle {:?}", e),
-        }
-    }
-    if let Some(elem) = curr.take() {
-        if !elem_is_empty(&elem) {
-            paragraphs.push(elem);
+            _ => (),
         }
     }
-    Ok(paragraphs)
 }
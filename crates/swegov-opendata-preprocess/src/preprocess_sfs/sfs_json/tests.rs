@@ -6,12 +6,122 @@ use pretty_assertions::assert_eq;
 fn test_process_html() -> miette::Result<()> {
     let src = "<div><p><a name=\"S1\"></a></p><a class=\"paragraf\" name=\"P1\"><b>1 §</b></a>   Enligt denna förordning får lån (förvärvslån) lämnas för förvärv från staten av egnahemsfastighet som har<br />\r\n   1. inlösts enligt 56 a § arbetsmarknadskungörelsen (1966:368),<br />\r\n   2. avstyckats från jordbruksfastighet genom åtgärder i samband med jordbrukets rationalisering.<h3 name=\"overgang\"><a name=\"overgang\">Övergångsbestämmelser</a></h3>\r\n1985:458<p><a name=\"P11S2\"></a></p>\r\n\r\nDenna förordning träder i kraft den 1 juli 1985.</div>";
     let mut actual_elem = Element::bare("text", "");
-    process_html(src, &mut actual_elem)?;
-    let actual_elem = clean_element(&actual_elem);
+    process_html(src, &mut actual_elem, TableMode::Flatten)?;
+    let actual_elem = clean_element(&actual_elem, CleanSteps::SFS);
     let mut actual = Vec::new();
     actual_elem.write_to(&mut actual).into_diagnostic()?;
-    let expected = r#"<text xmlns=""><p>1 § Enligt denna förordning får lån (förvärvslån) lämnas för förvärv från staten av egnahemsfastighet som har<br/> 1. inlösts enligt 56 a § arbetsmarknadskungörelsen (1966:368),<br/> 2. avstyckats från jordbruksfastighet genom åtgärder i samband med jordbrukets rationalisering.</p><p>Övergångsbestämmelser</p><p>1985:458</p><p>Denna förordning träder i kraft den 1 juli 1985.</p></text>"#;
+    let expected = r#"<text xmlns=""><paragraf id="P1" nr="1"><stycke id="P1S1" nr="1"><p>1 § Enligt denna förordning får lån (förvärvslån) lämnas för förvärv från staten av egnahemsfastighet som har<br/><punkt id="P1S1N1" nr="1">1. inlösts enligt 56 a § arbetsmarknadskungörelsen (1966:368),</punkt><br/><punkt id="P1S1N2" nr="2">2. avstyckats från jordbruksfastighet genom åtgärder i samband med jordbrukets rationalisering.</punkt></p></stycke></paragraf><p>Övergångsbestämmelser</p><p>1985:458</p><p>Denna förordning träder i kraft den 1 juli 1985.</p></text>"#;
     let actual_str = String::from_utf8_lossy(&actual);
     assert_eq!(actual_str, expected);
     Ok(())
 }
+
+fn process_html_to_string(src: &str, tables: TableMode) -> miette::Result<String> {
+    let mut actual_elem = Element::bare("text", "");
+    process_html(src, &mut actual_elem, tables)?;
+    let actual_elem = clean_element(&actual_elem, CleanSteps::SFS);
+    let mut actual = Vec::new();
+    actual_elem.write_to(&mut actual).into_diagnostic()?;
+    Ok(String::from_utf8_lossy(&actual).into_owned())
+}
+
+const TABLE_SRC: &str = "<div><p>Bilaga</p><table><tr><th>Avgift</th><th>Kronor</th></tr><tr><td>Ansökan</td><td>500</td></tr></table><p>Slut</p></div>";
+
+#[test]
+fn test_process_html_structures_tables() -> miette::Result<()> {
+    let actual = process_html_to_string(TABLE_SRC, TableMode::Structure)?;
+    let expected = r#"<text xmlns=""><p>Bilaga</p><table><row index="1"><cell col="1" header="true" row="1"><p>Avgift</p></cell><cell col="2" header="true" row="1"><p>Kronor</p></cell></row><row index="2"><cell col="1" header="false" row="2"><p>Ansökan</p></cell><cell col="2" header="false" row="2"><p>500</p></cell></row></table><p>Slut</p></text>"#;
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_process_html_excludes_tables() -> miette::Result<()> {
+    let actual = process_html_to_string(TABLE_SRC, TableMode::Exclude)?;
+    let expected = r#"<text xmlns=""><p>Bilaga</p><p>Slut</p></text>"#;
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_process_html_structures_tables_on_scanned_pages() -> miette::Result<()> {
+    let src = r#"<div class="dok"><div class="sida"><p>Sida</p><table class="skannad"><tr><td><p>a</p><p>b</p></td><td><p /></td></tr></table></div></div>"#;
+    let actual = process_html_to_string(src, TableMode::Structure)?;
+    let expected = r#"<text xmlns=""><page id="1"><p>Sida</p><table><row index="1"><cell col="1" header="false" row="1"><p>a</p><p>b</p></cell></row></table></page></text>"#;
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+const SFS_1976_257: &str = "assets/sfs-1976/sfs-1976-257.json";
+
+#[test]
+fn test_preprocess_json_reports_invalid_upphavd() -> miette::Result<()> {
+    let source = fs_err::read_to_string(SFS_1976_257).into_diagnostic()?;
+    let source = source.replace(r#""1992-01-01 00:00:00""#, r#""snart""#);
+
+    let result = preprocess_json(&source, TableMode::Flatten, CleanSteps::SFS, None);
+
+    assert!(
+        matches!(
+            &result,
+            Err(SfsPreprocessError::InvalidUpphavd { value, .. }) if value == "snart"
+        ),
+        "{result:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_preprocess_json_keeps_document_without_text() -> miette::Result<()> {
+    let source = fs_err::read_to_string(SFS_1976_257).into_diagnostic()?;
+    let start = source
+        .find(r#"<div><p><a name=\"S1\">"#)
+        .expect("statute text");
+    let end = source[start..].find(r#"</div>""#).expect("end of html") + start;
+    let source = format!("{}<div></div>{}", &source[..start], &source[end + 6..]);
+
+    let actual = String::from_utf8(preprocess_json(
+        &source,
+        TableMode::Flatten,
+        CleanSteps::SFS,
+        None,
+    )?)
+    .into_diagnostic()?;
+
+    assert!(actual.contains(r#"datatyp="huvuddokument""#), "{actual}");
+    Ok(())
+}
+
+#[test]
+fn test_preprocess_json_repairs_only_scanned_texts() -> miette::Result<()> {
+    let source = fs_err::read_to_string(SFS_1976_257).into_diagnostic()?;
+    let ocr_repair = OcrRepair::new(["lån"].into_iter().collect());
+    let start = source
+        .find(r#"<div><p><a name=\"S1\">"#)
+        .expect("statute text");
+    let end = source[start..].find(r#"</div>""#).expect("end of html") + start;
+    let scanned = format!(
+        r#"{}<div class=\"dok\"><div class=\"sida\"><p>Mats fick lån</p></div></div>{}"#,
+        &source[..start],
+        &source[end + 6..]
+    );
+
+    let born_digital = String::from_utf8(preprocess_json(
+        &source,
+        TableMode::Flatten,
+        CleanSteps::SFS,
+        Some(&ocr_repair),
+    )?)
+    .into_diagnostic()?;
+    let scanned = String::from_utf8(preprocess_json(
+        &scanned,
+        TableMode::Flatten,
+        CleanSteps::SFS,
+        Some(&ocr_repair),
+    )?)
+    .into_diagnostic()?;
+
+    assert!(!born_digital.contains("ocr_quality"), "{born_digital}");
+    assert!(scanned.contains("ocr_quality"), "{scanned}");
+    Ok(())
+}
@@ -0,0 +1,131 @@
+//! Comparing two fetched versions of the same statute, paragraf by paragraf.
+
+use minidom_extension::{minidom::Element, minidom_collect_texts};
+
+use crate::shared::{cleaning::CleanSteps, tables::TableMode};
+
+use super::{sfs_json, SfsPreprocessError};
+
+/// A paragraf that differs between two versions of a statute.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ParagrafChange {
+    /// The paragraf is only in the new version.
+    Added {
+        id: String,
+        andrad_genom: Option<String>,
+        text: String,
+    },
+    /// The paragraf is only in the old version.
+    Removed { id: String, text: String },
+    /// The text of the paragraf differs.
+    Changed {
+        id: String,
+        /// The act that changed the paragraf, as noted in the new version.
+        andrad_genom: Option<String>,
+        old_text: String,
+        new_text: String,
+    },
+}
+
+/// The paragrafer that differ between the `old` and `new` versions of an SFS document.
+///
+/// The paragrafer are matched by their id, e.g. `K2P3`, and compared by their text with the
+/// whitespace normalised. Added and changed paragrafer come in the order of the new version,
+/// followed by the removed ones.
+pub fn diff_sfs_versions(old: &str, new: &str) -> Result<Vec<ParagrafChange>, SfsPreprocessError> {
+    let old = paragrafer(&sfs_json::build_dokument(
+        old,
+        TableMode::Flatten,
+        CleanSteps::SFS,
+        None,
+    )?);
+    let new = paragrafer(&sfs_json::build_dokument(
+        new,
+        TableMode::Flatten,
+        CleanSteps::SFS,
+        None,
+    )?);
+
+    let mut changes = Vec::new();
+    for paragraf in &new {
+        match old.iter().find(|old| old.id == paragraf.id) {
+            None => changes.push(ParagrafChange::Added {
+                id: paragraf.id.clone(),
+                andrad_genom: paragraf.andrad_genom.clone(),
+                text: paragraf.text.clone(),
+            }),
+            Some(old) if old.text != paragraf.text => changes.push(ParagrafChange::Changed {
+                id: paragraf.id.clone(),
+                andrad_genom: paragraf.andrad_genom.clone(),
+                old_text: old.text.clone(),
+                new_text: paragraf.text.clone(),
+            }),
+            Some(_) => (),
+        }
+    }
+    for paragraf in old {
+        if !new.iter().any(|new| new.id == paragraf.id) {
+            changes.push(ParagrafChange::Removed {
+                id: paragraf.id,
+                text: paragraf.text,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+#[derive(Debug)]
+struct Paragraf {
+    id: String,
+    andrad_genom: Option<String>,
+    text: String,
+}
+
+/// The paragrafer of `dokument` in document order, joining the parts of a paragraf that is
+/// split over pages.
+fn paragrafer(dokument: &Element) -> Vec<Paragraf> {
+    fn collect(elem: &Element, paragrafer: &mut Vec<Paragraf>) {
+        for child in elem.children() {
+            if child.name() != "paragraf" {
+                collect(child, paragrafer);
+                continue;
+            }
+            let id = child.attr("id").unwrap_or_default();
+            // The parts on later pages have their number as a suffix to the id, e.g. K2P3-2
+            let id = match child.attr("del") {
+                Some(del) => id.strip_suffix(&format!("-{del}")).unwrap_or(id),
+                None => id,
+            };
+            let text = minidom_collect_texts(child);
+            let andrad_genom = child.attr("andrad_genom").map(String::from);
+            match paragrafer.iter_mut().find(|paragraf| paragraf.id == id) {
+                Some(paragraf) => {
+                    paragraf.text = join_words([paragraf.text.as_str(), text.as_str()]);
+                    if andrad_genom.is_some() {
+                        paragraf.andrad_genom = andrad_genom;
+                    }
+                }
+                None => paragrafer.push(Paragraf {
+                    id: id.to_string(),
+                    andrad_genom,
+                    text: join_words([text.as_str()]),
+                }),
+            }
+        }
+    }
+    let mut paragrafer = Vec::new();
+    collect(dokument, &mut paragrafer);
+    paragrafer
+}
+
+fn join_words<'a>(texts: impl IntoIterator<Item = &'a str>) -> String {
+    texts
+        .into_iter()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests;
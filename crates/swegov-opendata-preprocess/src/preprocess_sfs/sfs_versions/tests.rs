@@ -0,0 +1,87 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+
+const SFS_1976_257: &str = "assets/sfs-1976/sfs-1976-257.json";
+/// Scanned, with paragrafer split over pages.
+const CKS6RIKSG: &str = "assets/cks6riksg/cks6riksg.json";
+
+fn read_asset() -> String {
+    fs_err::read_to_string(SFS_1976_257).expect("asset exists")
+}
+
+#[test]
+fn diff_sfs_versions_finds_nothing_in_same_version() -> Result<(), SfsPreprocessError> {
+    let source = read_asset();
+
+    assert_eq!(diff_sfs_versions(&source, &source)?, []);
+    Ok(())
+}
+
+#[test]
+fn diff_sfs_versions_reports_changed_paragraf() -> Result<(), SfsPreprocessError> {
+    let old = read_asset();
+    let new = old.replace(
+        "meddelas av plan- och bostadsverket. Förordning (1988:303).",
+        "meddelas av Boverket. Förordning (1990:12).",
+    );
+
+    assert_eq!(
+        diff_sfs_versions(&old, &new)?,
+        [ParagrafChange::Changed {
+            id: "P11".to_string(),
+            andrad_genom: Some("1990:12".to_string()),
+            old_text: "11 § Föreskrifter för verkställighet av denna förordning meddelas av \
+                       plan- och bostadsverket. Förordning (1988:303)."
+                .to_string(),
+            new_text: "11 § Föreskrifter för verkställighet av denna förordning meddelas av \
+                       Boverket. Förordning (1990:12)."
+                .to_string(),
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn diff_sfs_versions_reports_added_and_removed_paragrafer() -> Result<(), SfsPreprocessError> {
+    let old = read_asset();
+    let new = old.replace(r#"name=\"P11\"><b>11 §</b>"#, r#"name=\"P12\"><b>12 §</b>"#);
+
+    let changes = diff_sfs_versions(&old, &new)?;
+
+    let ids: Vec<_> = changes
+        .iter()
+        .map(|change| match change {
+            ParagrafChange::Added { id, .. } => format!("+{id}"),
+            ParagrafChange::Removed { id, .. } => format!("-{id}"),
+            ParagrafChange::Changed { id, .. } => format!("~{id}"),
+        })
+        .collect();
+    assert_eq!(ids, ["+P12", "-P11"]);
+    Ok(())
+}
+
+#[test]
+fn diff_sfs_versions_joins_paragraf_split_over_pages() -> Result<(), SfsPreprocessError> {
+    let old = fs_err::read_to_string(CKS6RIKSG).expect("asset exists");
+    let new = old.replace(
+        "G:o) att Kongl. Maj:t skall insätta",
+        "6:o) att Kongl. Maj:t skall insätta",
+    );
+
+    assert_eq!(diff_sfs_versions(&old, &old)?, []);
+    let changes = diff_sfs_versions(&old, &new)?;
+
+    let ids: Vec<_> = changes
+        .iter()
+        .map(|change| match change {
+            ParagrafChange::Changed { id, new_text, .. } => {
+                assert!(new_text.contains("6:o) att Kongl. Maj:t skall insätta"));
+                id.as_str()
+            }
+            change => panic!("unexpected change {change:?}"),
+        })
+        .collect();
+    assert_eq!(ids, ["P8"]);
+    Ok(())
+}
@@ -1,4 +1,7 @@
-use crate::{shared::io_ext, PreprocessError};
+use crate::{
+    shared::{cleaning::CleanSteps, io_ext, ocr::OcrRepair, tables::TableMode},
+    PreprocessError,
+};
 use fs_err as fs;
 use std::path::Path;
 
@@ -6,7 +9,13 @@ use super::sfs_json;
 use sparv_extension::XmlSourceWriter;
 
 #[tracing::instrument()]
-pub fn build_sparv_source(path: &Path, corpus_source_dir: &Path) -> Result<(), PreprocessError> {
+pub fn build_sparv_source(
+    path: &Path,
+    corpus_source_dir: &Path,
+    tables: TableMode,
+    clean_steps: CleanSteps,
+    ocr_repair: Option<&OcrRepair>,
+) -> Result<(), PreprocessError> {
     tracing::info!("creating '{}'", corpus_source_dir.display());
     fs::create_dir_all(corpus_source_dir).map_err(|error| {
         PreprocessError::CouldNotCreateFolder {
@@ -27,11 +36,10 @@ pub fn build_sparv_source(path: &Path, corpus_source_dir: &Path) -> Result<(), P
                 path: file_path.clone(),
                 error,
             })?;
-        let xmlstring = sfs_json::preprocess_json(&filecontents).map_err(|error| {
-            PreprocessError::SfsPreprocessError {
-                path: file_path.clone(),
-                error,
-            }
+        let xmlstring = sfs_json::preprocess_json(&filecontents, tables, clean_steps, ocr_repair)
+            .map_err(|error| PreprocessError::SfsPreprocessError {
+            path: file_path.clone(),
+            error,
         })?;
         source_writer.write(xmlstring)?;
     }
@@ -0,0 +1,374 @@
+//! Report how harvested data drifts from the models in `swegov-opendata`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Read,
+    path::Path,
+};
+
+use fs_err as fs;
+use serde::Deserialize;
+use serde_json::Value;
+use swegov_opendata::{
+    collect_unknown_fields,
+    try_parse::{collect_unparsed_fields, TryParse},
+    with_mode, DataFormat, DataSet, DeserializeMode, DokumentStatusPage, FilFormat, Tracked,
+};
+use zip::ZipArchive;
+
+use crate::{
+    shared::{
+        io_ext::{read_text, without_bom},
+        rm_start_year,
+    },
+    PreprocessError,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Used as document type or year when the document has none.
+pub const UNKNOWN: &str = "unknown";
+
+/// Values of `referenstyp` known when the models were written.
+const KNOWN_REFERENSTYP: &[&str] = &[
+    "Ds",
+    "SOU",
+    "behandlar",
+    "behandlas_i",
+    "föredragningslista",
+    "följdmotion",
+    "ipsvarid",
+    "protokollbeslut",
+    "protokolldebatt",
+    "rskr",
+    "talarlista",
+    "ändringsförfattning",
+];
+
+/// Values of `anf_typ` known when the models were written.
+const KNOWN_ANF_TYP: &[&str] = &[
+    "",
+    "ap",
+    "bu",
+    "ip",
+    "rejoinder",
+    "speech",
+    "talmansanförande",
+];
+
+/// Schema drift of a harvested corpus, grouped by document type and year.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct SchemaDriftReport {
+    pub groups: BTreeMap<String, BTreeMap<String, GroupReport>>,
+    /// Documents that aren't valid JSON or that the models can't deserialize.
+    pub failed: Vec<FailedDocument>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct GroupReport {
+    pub documents: u64,
+    pub datasets: u64,
+    /// Fields that the models don't know, with the number of times they were found.
+    ///
+    /// Fields of a document that fails to deserialize may be missed.
+    pub unknown_fields: BTreeMap<String, u64>,
+    /// Fields with values that the models can't parse, e.g. a date that isn't a date, with the
+    /// number of documents they were found in.
+    pub unparsed_fields: BTreeMap<String, u64>,
+    /// Values of enumerated fields that aren't known, e.g. a new `referenstyp`.
+    pub new_values: BTreeMap<String, BTreeMap<String, u64>>,
+    /// How often each field is present, null or an empty string.
+    pub fields: BTreeMap<String, FieldStats>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct FieldStats {
+    pub present: u64,
+    pub null: u64,
+    pub empty: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FailedDocument {
+    pub source: String,
+    pub error: String,
+}
+
+impl SchemaDriftReport {
+    /// Scan `path` recursively for `dokumentstatus` JSON (optionally gzipped), zipped datasets
+    /// and dataset metadata (`*.metadata.json`).
+    pub fn scan_dir(path: &Path) -> Result<Self, PreprocessError> {
+        let mut report = Self::default();
+        report.add_dir(path)?;
+        Ok(report)
+    }
+
+    fn add_dir(&mut self, path: &Path) -> Result<(), PreprocessError> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(path).map_err(|error| PreprocessError::CouldNotReadFolder {
+            path: path.to_path_buf(),
+            error,
+        })? {
+            let entry = entry.map_err(|error| PreprocessError::CouldNotAccessDirEntry {
+                path: path.to_path_buf(),
+                error,
+            })?;
+            entries.push(entry.path());
+        }
+        entries.sort();
+
+        for path in entries {
+            let name = path.to_string_lossy();
+            if path.is_dir() {
+                self.add_dir(&path)?;
+            } else if name.ends_with(".metadata.json") {
+                if let Some(text) = self.read_file(&path) {
+                    self.add_dataset(&name, &text);
+                }
+            } else if name.ends_with(".json") || name.ends_with(".json.gz") {
+                if let Some(text) = self.read_file(&path) {
+                    self.add_document(&name, &text);
+                }
+            } else if name.ends_with(".zip") {
+                self.add_zip(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a file, a file that can't be read is reported and the scan continues.
+    fn read_file(&mut self, path: &Path) -> Option<String> {
+        match read_text(path) {
+            Ok(text) => Some(text),
+            Err(error) => {
+                self.failed.push(FailedDocument {
+                    source: path.display().to_string(),
+                    error: error.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    fn add_zip(&mut self, path: &Path) -> Result<(), PreprocessError> {
+        let zip_file = fs::File::open(path).map_err(|error| PreprocessError::CouldNotReadFile {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        let mut zipf =
+            ZipArchive::new(zip_file).map_err(|error| PreprocessError::CouldNotReadZipArchive {
+                path: path.to_path_buf(),
+                error,
+            })?;
+        for i in 0..zipf.len() {
+            let mut zipobj = zipf
+                .by_index(i)
+                .map_err(|error| PreprocessError::CouldNotGetZipObjByIndex { index: i, error })?;
+            if !zipobj.name().ends_with(".json") {
+                continue;
+            }
+            let mut filecontents = String::new();
+            zipobj.read_to_string(&mut filecontents).map_err(|error| {
+                PreprocessError::CouldNotReadZipFile {
+                    archive: path.to_path_buf(),
+                    path: zipobj.name().into(),
+                    error,
+                }
+            })?;
+            // Same workaround as when preprocessing, this is known and not drift
+            let filecontents = filecontents.replace("{/* RESERVATIONSTEXT */}", r#""""#);
+            let source = format!("{}/{}", path.display(), zipobj.name());
+            self.add_document(&source, &filecontents);
+        }
+        Ok(())
+    }
+
+    /// Add a `dokumentstatus` document, other documents (e.g. `dokumentlista`) are skipped.
+    pub fn add_document(&mut self, source: &str, text: &str) {
+        let Some(value) = self.parse_json(source, text) else {
+            return;
+        };
+        let Some(dokument) = value
+            .get("dokumentstatus")
+            .map(|status| &status["dokument"])
+        else {
+            tracing::debug!(source, "skipping, not a dokumentstatus");
+            return;
+        };
+        let typ = non_empty(dokument["typ"].as_str());
+        let year = non_empty(dokument["rm"].as_str().map(rm_start_year).as_deref())
+            .or_else(|| non_empty(dokument["datum"].as_str().and_then(|datum| datum.get(..4))));
+
+        let ((result, unknown_fields), unparsed_fields) = collect_unparsed_fields(|| {
+            collect_unknown_fields(|| {
+                with_mode(DeserializeMode::Lenient, || {
+                    DokumentStatusPage::deserialize(Tracked::new(&value))
+                })
+            })
+        });
+        if let Err(error) = result {
+            self.failed.push(FailedDocument {
+                source: source.to_string(),
+                error: error.to_string(),
+            });
+        }
+
+        let group = self.group(typ, year);
+        group.documents += 1;
+        group.add_unparsed(unparsed_fields);
+        group.visit(String::new(), &value, &unknown_fields);
+    }
+
+    /// Add the metadata of a dataset, as stored next to the harvested zip.
+    pub fn add_dataset(&mut self, source: &str, text: &str) {
+        let Some(value) = self.parse_json(source, text) else {
+            return;
+        };
+        let ((result, unknown_fields), unparsed_fields) = collect_unparsed_fields(|| {
+            collect_unknown_fields(|| {
+                with_mode(DeserializeMode::Lenient, || {
+                    DataSet::deserialize(Tracked::new(&value))
+                })
+            })
+        });
+        if let Err(error) = result {
+            self.failed.push(FailedDocument {
+                source: source.to_string(),
+                error: error.to_string(),
+            });
+        }
+        // `rm` is e.g. "1998-2001" or "2019/20", read it from the JSON since the model may have failed
+        let year = value["rm"]
+            .as_str()
+            .and_then(|rm| rm.get(..4))
+            .filter(|year| year.bytes().all(|b| b.is_ascii_digit()))
+            .map(ToString::to_string);
+        let typ = non_empty(value["typ"].as_str());
+
+        // The dataset is the root of the paths from the models
+        let in_dataset = |paths: BTreeSet<String>| {
+            paths
+                .into_iter()
+                .map(|path| format!("dataset.{path}"))
+                .collect::<BTreeSet<_>>()
+        };
+
+        let group = self.group(typ, year);
+        group.datasets += 1;
+        group.add_unparsed(in_dataset(unparsed_fields));
+        group.visit("dataset".to_string(), &value, &in_dataset(unknown_fields));
+    }
+
+    fn parse_json(&mut self, source: &str, text: &str) -> Option<Value> {
+        match serde_json::from_str(without_bom(text)) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.failed.push(FailedDocument {
+                    source: source.to_string(),
+                    error: error.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    fn group(&mut self, typ: Option<String>, year: Option<String>) -> &mut GroupReport {
+        self.groups
+            .entry(typ.unwrap_or_else(|| UNKNOWN.to_string()))
+            .or_default()
+            .entry(year.unwrap_or_else(|| UNKNOWN.to_string()))
+            .or_default()
+    }
+}
+
+fn non_empty(s: Option<&str>) -> Option<String> {
+    s.map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+}
+
+impl GroupReport {
+    fn add_unparsed(&mut self, paths: BTreeSet<String>) {
+        for path in paths {
+            *self.unparsed_fields.entry(path).or_default() += 1;
+        }
+    }
+
+    /// Collect statistics for `value` at `path`, arrays share the path of their elements.
+    ///
+    /// `unknown_fields` are the paths of the fields that the models don't know.
+    fn visit(&mut self, path: String, value: &Value, unknown_fields: &BTreeSet<String>) {
+        match value {
+            Value::Array(values) => {
+                if values.is_empty() {
+                    self.stats(&path).empty += 1;
+                }
+                for value in values {
+                    self.visit(path.clone(), value, unknown_fields);
+                }
+                return;
+            }
+            Value::Object(fields) => {
+                for (name, value) in fields {
+                    let field_path = if path.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{path}.{name}")
+                    };
+                    if unknown_fields.contains(&field_path) {
+                        *self.unknown_fields.entry(field_path.clone()).or_default() += 1;
+                    }
+                    self.visit(field_path, value, unknown_fields);
+                }
+            }
+            _ => {}
+        }
+        if path.is_empty() {
+            return;
+        }
+        match value {
+            Value::Null => self.stats(&path).null += 1,
+            Value::String(s) if s.is_empty() => self.stats(&path).empty += 1,
+            _ => self.stats(&path).present += 1,
+        }
+        if value.is_null() {
+            return;
+        }
+        if is_new_value(&path, value) {
+            let value = value
+                .as_str()
+                .map_or_else(|| value.to_string(), ToString::to_string);
+            *self
+                .new_values
+                .entry(path)
+                .or_default()
+                .entry(value)
+                .or_default() += 1;
+        }
+    }
+
+    fn stats(&mut self, path: &str) -> &mut FieldStats {
+        self.fields.entry(path.to_string()).or_default()
+    }
+}
+
+fn is_new_value(path: &str, value: &Value) -> bool {
+    match path {
+        "dataset.format" => matches!(
+            TryParse::<DataFormat>::deserialize(value),
+            Ok(TryParse::Unparsed(_))
+        ),
+        "dataset.filformat" => matches!(
+            TryParse::<FilFormat>::deserialize(value),
+            Ok(TryParse::Unparsed(_))
+        ),
+        "dokumentstatus.dokreferens.referens.referenstyp" => !value
+            .as_str()
+            .is_some_and(|s| KNOWN_REFERENSTYP.contains(&s)),
+        "dokumentstatus.debatt.anforande.anf_typ" => {
+            !value.as_str().is_some_and(|s| KNOWN_ANF_TYP.contains(&s))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+const BET_PATH: &str = "assets/bet-2018-2021-h601au1.json";
+
+fn read_asset(path: &str) -> Value {
+    let text = read_text(Path::new(path)).unwrap();
+    serde_json::from_str(without_bom(&text)).unwrap()
+}
+
+#[test]
+fn known_document_has_no_drift() {
+    let mut report = SchemaDriftReport::default();
+
+    report.add_document(BET_PATH, &read_text(Path::new(BET_PATH)).unwrap());
+
+    assert_eq!(report.failed, []);
+    let group = &report.groups["bet"]["2018"];
+    assert_eq!(group.documents, 1);
+    assert_eq!(group.unknown_fields, BTreeMap::new());
+    assert_eq!(group.unparsed_fields, BTreeMap::new());
+    assert_eq!(group.new_values, BTreeMap::new());
+    assert_eq!(
+        group.fields["dokumentstatus.dokument.titel"],
+        FieldStats {
+            present: 1,
+            null: 0,
+            empty: 0
+        }
+    );
+    assert_eq!(group.fields["dokumentstatus.dokument.subtitel"].empty, 1);
+}
+
+#[test]
+fn drifted_document_is_reported() {
+    let mut value = read_asset(BET_PATH);
+    let dokumentstatus = &mut value["dokumentstatus"];
+    dokumentstatus["dokument"]["nytt_falt"] = json!("värde");
+    dokumentstatus["dokument"]["subtitel"] = Value::Null;
+    dokumentstatus["dokreferens"]["referens"][0]["referenstyp"] = json!("ny_referens");
+    let mut report = SchemaDriftReport::default();
+
+    report.add_document("drifted.json", &value.to_string());
+
+    let group = &report.groups["bet"]["2018"];
+    assert_eq!(
+        group.unknown_fields,
+        BTreeMap::from([("dokumentstatus.dokument.nytt_falt".to_string(), 1)])
+    );
+    assert_eq!(group.unparsed_fields, BTreeMap::new());
+    assert_eq!(
+        group.new_values,
+        BTreeMap::from([(
+            "dokumentstatus.dokreferens.referens.referenstyp".to_string(),
+            BTreeMap::from([("ny_referens".to_string(), 1)])
+        )])
+    );
+    assert_eq!(group.fields["dokumentstatus.dokument.subtitel"].null, 1);
+    assert_eq!(report.failed, []);
+}
+
+#[test]
+fn unknown_field_is_only_reported_at_its_path() {
+    let mut value = read_asset(BET_PATH);
+    // `datum` is known in `dokument` but not in `referens`
+    value["dokumentstatus"]["dokreferens"]["referens"][0]["datum"] = json!("2019-01-01");
+    let mut report = SchemaDriftReport::default();
+
+    report.add_document("drifted.json", &value.to_string());
+
+    let group = &report.groups["bet"]["2018"];
+    assert_eq!(
+        group.unknown_fields,
+        BTreeMap::from([("dokumentstatus.dokreferens.referens.datum".to_string(), 1)])
+    );
+    assert_eq!(report.failed, []);
+}
+
+#[test]
+fn unparsed_field_is_reported() {
+    let mut value = read_asset(BET_PATH);
+    value["dokumentstatus"]["dokument"]["publicerad"] = json!("igår");
+    let mut report = SchemaDriftReport::default();
+
+    report.add_document("unparsed.json", &value.to_string());
+
+    let group = &report.groups["bet"]["2018"];
+    assert_eq!(
+        group.unparsed_fields,
+        BTreeMap::from([("dokumentstatus.dokument.publicerad".to_string(), 1)])
+    );
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].source, "unparsed.json");
+}
+
+#[test]
+fn dataset_with_new_format_is_reported() {
+    let mut value = read_asset("assets/bet-1998-2001.metadata.json");
+    value["format"] = json!("parquet");
+    let mut report = SchemaDriftReport::default();
+
+    report.add_dataset("bet-1998-2001.metadata.json", &value.to_string());
+
+    let group = &report.groups["bet"]["1998"];
+    assert_eq!(group.datasets, 1);
+    assert_eq!(
+        group.new_values,
+        BTreeMap::from([(
+            "dataset.format".to_string(),
+            BTreeMap::from([("parquet".to_string(), 1)])
+        )])
+    );
+}
+
+#[test]
+fn invalid_json_is_reported_as_failed() {
+    let mut report = SchemaDriftReport::default();
+
+    report.add_document("broken.json", "{\"dokumentstatus\": ");
+
+    assert_eq!(report.groups, BTreeMap::new());
+    assert_eq!(report.failed.len(), 1);
+}
+
+#[test]
+fn dokumentlista_is_skipped() {
+    let mut report = SchemaDriftReport::default();
+
+    report.add_document("dokumentlista.json", r#"{"dokumentlista": {}}"#);
+
+    assert_eq!(report, SchemaDriftReport::default());
+}
@@ -3,17 +3,25 @@ use minidom::{Element, Node};
 use minidom_extension::{elem_is_empty, minidom};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use swegov_opendata::{Beteckning, BeteckningError, Riksmote};
 
+use self::cleaning::{join_line_break, CleanStep, CleanSteps};
+
+pub mod cleaning;
+pub mod html;
 pub mod io_ext;
+pub mod ocr;
+pub mod tables;
 
-pub fn clean_element(elem: &Element) -> Element {
+/// A copy of `elem` with its texts cleaned by `steps` and without empty elements.
+pub fn clean_element(elem: &Element, steps: CleanSteps) -> Element {
     // let new_elem = elem.clone();
     let mut elem_builder = Element::builder(elem.name(), elem.ns());
     for (name, value) in elem.attrs() {
         elem_builder = elem_builder.attr(name, value);
     }
     let mut new_elem = elem_builder.build();
-    clean_nodes(&mut new_elem, elem);
+    clean_nodes(&mut new_elem, elem, steps);
     // if elem.name() == "p" {
     //     // let mut text = minidom_collect_texts(elem);
     //     clean_text(&mut text);
@@ -29,14 +37,20 @@ pub fn clean_element(elem: &Element) -> Element {
 
 pub fn clean_texts(_elem: &mut Element) {}
 
-fn clean_nodes(new_elem: &mut Element, elem: &Element) {
+fn clean_nodes(new_elem: &mut Element, elem: &Element, steps: CleanSteps) {
     for node in elem.nodes() {
         match node {
             Node::Text(contents) => {
-                let mut text = contents.clone();
-                text = clean_text(&text);
+                let mut text = steps.clean(contents);
+                let mut joined = false;
+                if steps.contains(CleanStep::Dehyphenate) {
+                    if let Some(joined_text) = join_after_line_break(new_elem, &text) {
+                        text = joined_text;
+                        joined = true;
+                    }
+                }
                 if !text.is_empty() {
-                    if new_elem.nodes().len() > 0 {
+                    if new_elem.nodes().len() > 0 && !joined {
                         new_elem.append_text_node(" ".to_string());
                     }
                     new_elem.append_text_node(text);
@@ -49,7 +63,7 @@ fn clean_nodes(new_elem: &mut Element, elem: &Element) {
                     elem_builder = elem_builder.attr(name, value);
                 }
                 let mut new_child_elem = elem_builder.build();
-                clean_nodes(&mut new_child_elem, c_elem);
+                clean_nodes(&mut new_child_elem, c_elem, steps);
                 if new_child_elem.name() == "br" || !elem_is_empty(&new_child_elem) {
                     new_elem.append_child(new_child_elem);
                 }
@@ -58,6 +72,24 @@ fn clean_nodes(new_elem: &mut Element, elem: &Element) {
     }
 }
 
+/// Join `text` with the text before the `<br>` that ends `elem`, if that text ends with a word
+/// hyphenated at the line break, removing them from `elem`.
+fn join_after_line_break(elem: &mut Element, text: &str) -> Option<String> {
+    let [.., Node::Text(before), Node::Element(br)] = elem.nodes().as_slice() else {
+        return None;
+    };
+    if br.name() != "br" {
+        return None;
+    }
+    let joined = join_line_break(before, text)?;
+    let mut nodes = elem.take_nodes();
+    nodes.truncate(nodes.len() - 2);
+    for node in nodes {
+        elem.append_node(node);
+    }
+    Some(joined)
+}
+
 pub fn clean_text(text: &str) -> String {
     let text = text.replace('\u{AD}', "");
     Itertools::intersperse(text.split_whitespace(), " ").collect()
@@ -73,5 +105,29 @@ pub fn is_segreg(s: &str) -> bool {
     SEGREG.is_match(s)
 }
 
+/// The start year of the riksmöte `rm`, empty if `rm` isn't a valid riksmöte.
+pub fn rm_start_year(rm: &str) -> String {
+    match rm.parse::<Riksmote>() {
+        Ok(riksmote) => riksmote.start_year().to_string(),
+        Err(error) => {
+            tracing::debug!(%error, "could not parse rm");
+            String::new()
+        }
+    }
+}
+
+/// Set `beteckning_id` and `beteckning_sortkey` on `elem`, empty if `beteckning` couldn't be parsed.
+pub fn set_beteckning_attrs(elem: &mut Element, beteckning: Result<Beteckning, BeteckningError>) {
+    let (canonical_id, sort_key) = match beteckning {
+        Ok(beteckning) => (beteckning.canonical_id(), beteckning.sort_key()),
+        Err(error) => {
+            tracing::debug!(%error, "could not parse beteckning");
+            (String::new(), String::new())
+        }
+    };
+    elem.set_attr("beteckning_id", canonical_id);
+    elem.set_attr("beteckning_sortkey", sort_key);
+}
+
 #[cfg(test)]
 mod tests;
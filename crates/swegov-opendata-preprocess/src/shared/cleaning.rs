@@ -0,0 +1,366 @@
+//! The steps that clean the text of the RD and SFS documents, selected per corpus.
+//!
+//! [`clean_text`](super::clean_text) always removes soft hyphens and collapses whitespace, the
+//! [`CleanStep`]s run before it.
+
+use std::{borrow::Cow, collections::HashMap, fmt};
+
+use icu_normalizer::ComposingNormalizerBorrowed;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use super::clean_text;
+
+#[cfg(test)]
+mod tests;
+
+/// A step of the cleaning, the variants are in the order the steps are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanStep {
+    /// Remove control characters and invisible formatting characters like zero width spaces.
+    ControlChars,
+    /// Remove the no-break spaces written as characters in the html, so e.g. `9\u{a0}315 kr`
+    /// becomes `9315 kr`, as the RD html always had them removed. A `&nbsp;` is kept as a space.
+    ///
+    /// This step is run on the html source by [`CleanSteps::clean_html`].
+    Nbsp,
+    /// Remove Word field codes like `STYLEREF Kantrubrik \* MERGEFORMAT`.
+    WordFields,
+    /// Unicode NFC normalisation, composing e.g. `a` and a combining ring to `å`.
+    Nfc,
+    /// Expand ligatures like `ﬁ` to their letters.
+    Ligatures,
+    /// Write the typographic quotes and apostrophes as `"` and `'`.
+    Quotes,
+    /// Join words hyphenated at a line break, e.g. `rege- ringen` or `rege-<br/>ringen`.
+    Dehyphenate,
+}
+
+impl CleanStep {
+    const ALL: [CleanStep; 7] = [
+        Self::ControlChars,
+        Self::Nbsp,
+        Self::WordFields,
+        Self::Nfc,
+        Self::Ligatures,
+        Self::Quotes,
+        Self::Dehyphenate,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ControlChars => "control-chars",
+            Self::Nbsp => "nbsp",
+            Self::WordFields => "word-fields",
+            Self::Nfc => "nfc",
+            Self::Ligatures => "ligatures",
+            Self::Quotes => "quotes",
+            Self::Dehyphenate => "dehyphenate",
+        }
+    }
+
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::ControlChars => strip_control_chars(text),
+            Self::Nbsp => remove_nbsp(text),
+            Self::WordFields => remove_word_fields(text),
+            Self::Nfc => ComposingNormalizerBorrowed::new_nfc().normalize(text),
+            Self::Ligatures => expand_ligatures(text),
+            Self::Quotes => normalise_quotes(text),
+            Self::Dehyphenate => dehyphenate(text),
+        }
+    }
+
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for CleanStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for CleanStep {
+    type Err = UnknownCleanStep;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|step| step.as_str() == s)
+            .ok_or_else(|| UnknownCleanStep(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "unknown cleaning step '{0}', expected a comma separated list of control-chars, nbsp, \
+     word-fields, nfc, ligatures, quotes and dehyphenate, or none"
+)]
+pub struct UnknownCleanStep(pub String);
+
+/// The cleaning steps used for a corpus, written as a comma separated list like
+/// `nfc,quotes` or `none`.
+///
+/// The default is [`CleanSteps::RD`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CleanSteps(u8);
+
+impl Default for CleanSteps {
+    fn default() -> Self {
+        Self::RD
+    }
+}
+
+impl CleanSteps {
+    /// Only [`clean_text`].
+    pub const NONE: Self = Self(0);
+    /// The default for RD: remove the no-break spaces and the Word field codes, which the RD
+    /// html always had removed.
+    pub const RD: Self = Self(CleanStep::Nbsp.bit() | CleanStep::WordFields.bit());
+    /// The default for SFS: remove the Word field codes, the no-break spaces are kept as spaces.
+    pub const SFS: Self = Self(CleanStep::WordFields.bit());
+
+    pub fn new(steps: impl IntoIterator<Item = CleanStep>) -> Self {
+        Self(steps.into_iter().fold(0, |bits, step| bits | step.bit()))
+    }
+
+    pub fn contains(&self, step: CleanStep) -> bool {
+        self.0 & step.bit() != 0
+    }
+
+    /// The steps in the order they are run.
+    pub fn iter(&self) -> impl Iterator<Item = CleanStep> + '_ {
+        CleanStep::ALL
+            .into_iter()
+            .filter(|step| self.contains(*step))
+    }
+
+    /// Run the steps on the html source in `html`, before it is parsed.
+    pub fn clean_html<'a>(&self, html: &'a str) -> Cow<'a, str> {
+        if self.contains(CleanStep::Nbsp) {
+            CleanStep::Nbsp.apply(html)
+        } else {
+            Cow::Borrowed(html)
+        }
+    }
+
+    /// Run the steps on `text` and then [`clean_text`].
+    ///
+    /// [`CleanStep::Nbsp`] is left to [`CleanSteps::clean_html`], since the no-break spaces in the
+    /// text can come from a `&nbsp;`.
+    pub fn clean(&self, text: &str) -> String {
+        let mut text = Cow::Borrowed(text);
+        for step in self.iter().filter(|step| *step != CleanStep::Nbsp) {
+            if let Cow::Owned(cleaned) = step.apply(&text) {
+                text = Cow::Owned(cleaned);
+            }
+        }
+        clean_text(&text)
+    }
+}
+
+impl fmt::Display for CleanSteps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 {
+            return f.write_str("none");
+        }
+        for (i, step) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            f.write_str(step.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for CleanSteps {
+    type Err = UnknownCleanStep;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "none" {
+            return Ok(Self::NONE);
+        }
+        s.split(',')
+            .map(|step| step.trim().parse())
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::new)
+    }
+}
+
+/// The cleaning steps for one corpus, written `<corpus>=<steps>`, e.g. `rd-prot=nfc,dehyphenate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusCleanSteps {
+    pub corpus: String,
+    pub steps: CleanSteps,
+}
+
+impl std::str::FromStr for CorpusCleanSteps {
+    type Err = UnknownCleanStep;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (corpus, steps) = s
+            .split_once('=')
+            .ok_or_else(|| UnknownCleanStep(s.to_string()))?;
+        let corpus = corpus.trim();
+        if corpus.is_empty() {
+            return Err(UnknownCleanStep(s.to_string()));
+        }
+        Ok(Self {
+            corpus: corpus.to_string(),
+            steps: steps.parse()?,
+        })
+    }
+}
+
+/// The cleaning steps of each corpus.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CleanStepsPerCorpus {
+    /// Used for the corpora without steps of their own.
+    default: CleanSteps,
+    corpora: HashMap<String, CleanSteps>,
+}
+
+impl CleanStepsPerCorpus {
+    pub fn new(default: CleanSteps, corpora: impl IntoIterator<Item = CorpusCleanSteps>) -> Self {
+        Self {
+            default,
+            corpora: corpora
+                .into_iter()
+                .map(|CorpusCleanSteps { corpus, steps }| (corpus, steps))
+                .collect(),
+        }
+    }
+
+    /// The steps for the corpus with the id `corpus_id`, e.g. `rd-bet`.
+    pub fn for_corpus(&self, corpus_id: &str) -> CleanSteps {
+        self.corpora.get(corpus_id).copied().unwrap_or(self.default)
+    }
+}
+
+fn strip_control_chars(text: &str) -> Cow<'_, str> {
+    fn is_stripped(c: char) -> bool {
+        (c.is_control() && !c.is_whitespace())
+            || matches!(
+                c,
+                '\u{200b}' | '\u{200e}' | '\u{200f}' | '\u{2060}' | '\u{feff}'
+            )
+    }
+    if !text.contains(is_stripped) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.chars().filter(|c| !is_stripped(*c)).collect())
+}
+
+fn remove_nbsp(text: &str) -> Cow<'_, str> {
+    if !text.contains('\u{a0}') {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.replace('\u{a0}', ""))
+}
+
+/// Remove the field codes that Word leaves in the text when a field isn't updated.
+///
+/// A field code is the name of a field, its arguments and at least one switch, so `REF` or
+/// `PAGE` in ordinary text is kept.
+fn remove_word_fields(text: &str) -> Cow<'_, str> {
+    static WORD_FIELD: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r#"\b(?:STYLEREF|PAGEREF|NOTEREF|REF|PAGE|NUMPAGES|SEQ|TOC|HYPERLINK|DOCPROPERTY|MERGEFIELD)\b[^\\]{0,80}?(?:\s*\\[*@#]?\s*(?:"[^"]*"|[A-Za-z]+))+"#,
+        )
+        .expect("valid regex")
+    });
+    WORD_FIELD.replace_all(text, "")
+}
+
+fn expand_ligatures(text: &str) -> Cow<'_, str> {
+    fn expansion(c: char) -> Option<&'static str> {
+        match c {
+            'ﬀ' => Some("ff"),
+            'ﬁ' => Some("fi"),
+            'ﬂ' => Some("fl"),
+            'ﬃ' => Some("ffi"),
+            'ﬄ' => Some("ffl"),
+            'ﬅ' | 'ﬆ' => Some("st"),
+            _ => None,
+        }
+    }
+    if !text.chars().any(|c| expansion(c).is_some()) {
+        return Cow::Borrowed(text);
+    }
+    let mut expanded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match expansion(c) {
+            Some(letters) => expanded.push_str(letters),
+            None => expanded.push(c),
+        }
+    }
+    Cow::Owned(expanded)
+}
+
+fn normalise_quotes(text: &str) -> Cow<'_, str> {
+    fn replacement(c: char) -> Option<char> {
+        match c {
+            '“' | '”' | '„' | '‟' | '«' | '»' | '″' => Some('"'),
+            '‘' | '’' | '‚' | '‛' | '′' => Some('\''),
+            _ => None,
+        }
+    }
+    if !text.chars().any(|c| replacement(c).is_some()) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.chars().map(|c| replacement(c).unwrap_or(c)).collect())
+}
+
+/// The words after a hyphen that show that the hyphen isn't a line break, like in
+/// `barn- och ungdomsfrågor`.
+pub(super) const CONJUNCTIONS: &[&str] = &[
+    "och",
+    "eller",
+    "samt",
+    "respektive",
+    "resp",
+    "som",
+    "till",
+    "men",
+    "and",
+    "or",
+];
+
+/// Join `before` and `after`, the texts around a `<br>`, if `before` ends with a word
+/// hyphenated at the line break, e.g. "det är regeringen" for "det är rege-" and "ringen".
+///
+/// The same words are joined as by [`CleanStep::Dehyphenate`].
+pub(super) fn join_line_break(before: &str, after: &str) -> Option<String> {
+    static HEAD: Lazy<Regex> = Lazy::new(|| Regex::new(r"\p{L}-$").expect("valid regex"));
+    static TAIL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\p{Ll}\p{L}*").expect("valid regex"));
+    if !HEAD.is_match(before) {
+        return None;
+    }
+    let tail = TAIL.find(after)?.as_str();
+    if CONJUNCTIONS.contains(&tail) {
+        return None;
+    }
+    Some(format!("{}{after}", &before[..before.len() - 1]))
+}
+
+/// Join the parts of a word that was hyphenated at a line break.
+///
+/// The hyphen must follow a letter and be followed by whitespace and a lowercase word, which
+/// isn't a conjunction, so `barn- och ungdomsfrågor` is kept.
+fn dehyphenate(text: &str) -> Cow<'_, str> {
+    static HYPHENATED: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"(?P<head>\p{L})[-\u{ad}]\s+(?P<tail>\p{Ll}\p{L}*)").expect("valid regex")
+    });
+    HYPHENATED.replace_all(text, |captures: &Captures<'_>| {
+        let head = &captures["head"];
+        let tail = &captures["tail"];
+        if CONJUNCTIONS.contains(&tail) {
+            captures[0].to_string()
+        } else {
+            format!("{head}{tail}")
+        }
+    })
+}
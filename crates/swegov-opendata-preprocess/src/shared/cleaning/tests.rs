@@ -0,0 +1,119 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+#[case(CleanStep::ControlChars, "a\u{7}b\u{200b}c\u{feff}", "abc")]
+#[case(CleanStep::ControlChars, "rad\tett\nrad två", "rad\tett\nrad två")]
+#[case(CleanStep::Nbsp, "9\u{a0}315\u{a0}kr", "9315kr")]
+#[case(
+    CleanStep::WordFields,
+    "STYLEREF Kantrubrik \\* MERGEFORMAT Rubrik",
+    " Rubrik"
+)]
+#[case(CleanStep::WordFields, "se sidan PAGEREF _Toc123 \\h 5", "se sidan  5")]
+#[case(
+    CleanStep::WordFields,
+    "REF och PAGE i texten",
+    "REF och PAGE i texten"
+)]
+#[case(CleanStep::Nfc, "a\u{30a}r", "år")]
+#[case(CleanStep::Ligatures, "ﬁnansiell ﬂygplats", "finansiell flygplats")]
+#[case(
+    CleanStep::Quotes,
+    "”Lagen” och ’ändringen’",
+    "\"Lagen\" och 'ändringen'"
+)]
+#[case(CleanStep::Dehyphenate, "rege- ringen", "regeringen")]
+#[case(CleanStep::Dehyphenate, "rege\u{ad} ringen", "regeringen")]
+#[case(
+    CleanStep::Dehyphenate,
+    "barn- och ungdomsfrågor",
+    "barn- och ungdomsfrågor"
+)]
+#[case(CleanStep::Dehyphenate, "EU- Kommissionen", "EU- Kommissionen")]
+fn clean_step_applies(#[case] step: CleanStep, #[case] given: &str, #[case] expected: &str) {
+    assert_eq!(step.apply(given), expected);
+}
+
+#[rstest]
+#[case("none", CleanSteps::NONE)]
+#[case("nfc", CleanSteps::new([CleanStep::Nfc]))]
+#[case(
+    "dehyphenate, quotes",
+    CleanSteps::new([CleanStep::Quotes, CleanStep::Dehyphenate])
+)]
+fn clean_steps_parses(#[case] given: &str, #[case] expected: CleanSteps) {
+    assert_eq!(given.parse::<CleanSteps>(), Ok(expected));
+}
+
+#[test]
+fn clean_steps_displays_steps_in_order() {
+    let steps = CleanSteps::new([CleanStep::Dehyphenate, CleanStep::Nfc]);
+
+    assert_eq!(steps.to_string(), "nfc,dehyphenate");
+    assert_eq!(CleanSteps::NONE.to_string(), "none");
+    assert_eq!(CleanSteps::default().to_string(), "nbsp,word-fields");
+    assert_eq!(CleanSteps::SFS.to_string(), "word-fields");
+}
+
+#[test]
+fn clean_steps_rejects_unknown_step() {
+    assert_eq!(
+        "nfc,stemming".parse::<CleanSteps>(),
+        Err(UnknownCleanStep("stemming".to_string()))
+    );
+}
+
+#[test]
+fn clean_steps_cleans_text() {
+    let steps = CleanSteps::new([CleanStep::Ligatures, CleanStep::Dehyphenate]);
+
+    assert_eq!(
+        steps.clean("  de ﬁnansiella   rege- ringarna\u{ad} "),
+        "de finansiella regeringarna"
+    );
+    assert_eq!(CleanSteps::NONE.clean("rege- ringen"), "rege- ringen");
+}
+
+#[test]
+fn clean_steps_per_corpus_falls_back_to_default() {
+    let per_corpus = CleanStepsPerCorpus::new(
+        CleanSteps::new([CleanStep::WordFields]),
+        ["rd-prot=nfc,dehyphenate"
+            .parse::<CorpusCleanSteps>()
+            .unwrap()],
+    );
+
+    assert_eq!(
+        per_corpus.for_corpus("rd-prot"),
+        CleanSteps::new([CleanStep::Nfc, CleanStep::Dehyphenate])
+    );
+    assert_eq!(
+        per_corpus.for_corpus("rd-bet"),
+        CleanSteps::new([CleanStep::WordFields])
+    );
+}
+
+#[rstest]
+#[case("nfc")]
+#[case("=nfc")]
+#[case("rd-bet=nfd")]
+fn corpus_clean_steps_rejects_invalid(#[case] given: &str) {
+    assert!(given.parse::<CorpusCleanSteps>().is_err());
+}
+
+#[rstest]
+#[case("det är rege-", "ringens förslag", Some("det är regeringens förslag"))]
+#[case("barn-", "och ungdomsfrågor", None)]
+#[case("EU-", "Kommissionen", None)]
+#[case("2019-", "talet", None)]
+#[case("rege", "ringen", None)]
+fn join_line_break_joins_hyphenated_word(
+    #[case] before: &str,
+    #[case] after: &str,
+    #[case] expected: Option<&str>,
+) {
+    assert_eq!(join_line_break(before, after).as_deref(), expected);
+}
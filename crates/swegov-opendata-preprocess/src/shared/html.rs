@@ -0,0 +1,563 @@
+//! Parsing of the `html` fields of Riksdagen documents.
+//!
+//! The fields are HTML fragments of varying quality: uppercase and unclosed tags, Word
+//! conditional comments and OCR'd text with stray `<`. They are parsed with html5ever into a
+//! DOM, which the RD and SFS preprocessors then walk to build their `<p>`/`<page>` elements.
+//! The parser decodes all HTML5 named and numeric character references.
+
+use std::{borrow::Cow, cell::Cell, collections::BTreeSet};
+
+use html5ever::{
+    data::NAMED_ENTITIES,
+    ns,
+    tendril::StrTendril,
+    tokenizer::{BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer},
+    tree_builder::{Attribute, TreeBuilder},
+    LocalName, ParseOpts, QualName, TokenizerResult,
+};
+use markup5ever_rcdom::{Handle, RcDom};
+use minidom_extension::{
+    elem_is_empty,
+    minidom::{Element, Node},
+};
+
+use crate::nodeinfo::{rcdom_attr, rcdom_elem_name};
+
+#[cfg(test)]
+mod tests;
+
+/// Parse `contents` as an HTML document.
+///
+/// A `<` that doesn't start a known tag, a comment or a doctype is treated as text, see
+/// [`MarkupSink`], and character references that were escaped twice are decoded, see
+/// [`undo_double_escaping`].
+pub fn parse_html(contents: &str) -> RcDom {
+    let contents = undo_double_escaping(contents);
+    let opts = ParseOpts::default();
+    let input = BufferQueue::default();
+    input.push_back(StrTendril::from_slice(&contents));
+    let tree_builder = TreeBuilder::new(RcDom::default(), opts.tree_builder);
+    let tokenizer = Tokenizer::new(
+        MarkupSink::new(tree_builder, &contents, &input),
+        opts.tokenizer,
+    );
+    while let TokenizerResult::Script(_) = tokenizer.feed(&input) {}
+    tokenizer.sink.at_end.set(true);
+    tokenizer.end();
+    tokenizer.sink.inner.sink
+}
+
+/// The `body` element of `dom`.
+///
+/// The parser always creates one, wrapping the content of a fragment in `html` and `body`.
+pub fn html_body(dom: &RcDom) -> Option<Handle> {
+    let html = find_child(&dom.document, "html")?;
+    find_child(&html, "body")
+}
+
+fn find_child(node: &Handle, name: &str) -> Option<Handle> {
+    node.children
+        .borrow()
+        .iter()
+        .find(|child| rcdom_elem_name(child) == Some(name))
+        .cloned()
+}
+
+/// Turn character references that were escaped twice, like `&amp;ouml;` or `&amp;#228;`,
+/// back into `&ouml;` and `&#228;`.
+///
+/// Only references to known entities are changed, so a literal `&amp;` stays as it is.
+pub fn undo_double_escaping(contents: &str) -> Cow<'_, str> {
+    let mut unescaped = String::new();
+    let mut copied = 0;
+    for (pos, amp) in contents.match_indices("&amp;") {
+        let rest = &contents[pos + amp.len()..];
+        let is_char_ref = match char_ref_name(rest) {
+            Some(name) => is_known_entity(name),
+            None => is_numeric_char_ref(rest),
+        };
+        if !is_char_ref {
+            continue;
+        }
+        unescaped.push_str(&contents[copied..=pos]);
+        copied = pos + amp.len();
+    }
+    if copied == 0 {
+        return Cow::Borrowed(contents);
+    }
+    unescaped.push_str(&contents[copied..]);
+    Cow::Owned(unescaped)
+}
+
+/// The named character references in `contents` that aren't HTML5 entities.
+///
+/// The parser keeps them as text, so `&foo;` ends up in the output as it is.
+pub fn unknown_entities(contents: &str) -> BTreeSet<&str> {
+    contents
+        .match_indices('&')
+        .filter_map(|(pos, _)| char_ref_name(&contents[pos + 1..]))
+        .filter(|name| !is_known_entity(name))
+        .collect()
+}
+
+/// Log each of `entities` as an unknown entity in the document `dok_id`.
+pub fn warn_unknown_entities<'a>(dok_id: &str, entities: impl IntoIterator<Item = &'a str>) {
+    for entity in entities {
+        tracing::warn!(dok_id, entity, "unknown html entity");
+    }
+}
+
+/// The name of the named character reference at the start of `rest`, the text after a `&`.
+fn char_ref_name(rest: &str) -> Option<&str> {
+    let len = rest
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(rest.len());
+    let name = &rest[..len];
+    let is_name = name.starts_with(|c: char| c.is_ascii_alphabetic());
+    (is_name && rest[len..].starts_with(';')).then_some(name)
+}
+
+fn is_known_entity(name: &str) -> bool {
+    // The table also maps every prefix of an entity name to (0, 0)
+    NAMED_ENTITIES
+        .get(format!("{name};").as_str())
+        .is_some_and(|&(code_point, _)| code_point != 0)
+}
+
+fn is_numeric_char_ref(rest: &str) -> bool {
+    let Some(number) = rest.strip_prefix('#') else {
+        return false;
+    };
+    let (digits, is_digit): (&str, fn(&char) -> bool) = match number.strip_prefix(['x', 'X']) {
+        Some(hex) => (hex, char::is_ascii_hexdigit),
+        None => (number, char::is_ascii_digit),
+    };
+    let len = digits.chars().take_while(is_digit).count();
+    len > 0 && digits[len..].starts_with(';')
+}
+
+/// Wraps the tree builder and turns tags that aren't markup back into text.
+///
+/// A tokenizer reads `<en kommun` as the start of an `en` element and drops the text up to the
+/// next `>`. Tags in the documents are either HTML elements, Word namespaced elements
+/// (`o:p`, `v:shape`…) or a few Riksdagen specific elements, so any other tag is text: the sink
+/// passes on its `<` as text and puts the rest of it back in the input. The same goes for bogus
+/// comments like `</ p>` and `<!x`, and for known tags that are OCR'd text, see
+/// [`MarkupSink::is_markup`].
+///
+/// The sink also unescapes `\"` in text and marks a `<br/>` that is written as self-closing,
+/// see [`is_self_closing`]. A `<pre>` inside a `<p>`, which motions use for the lines of their
+/// signatures, would end the paragraph, so its tags are dropped.
+struct MarkupSink<'a, Sink> {
+    inner: Sink,
+    /// The html being parsed.
+    source: &'a str,
+    /// The tokenizer's input, read from `source`.
+    input: &'a BufferQueue,
+    /// The offset in `source` where the last token ended.
+    token_end: Cell<usize>,
+    /// Set when the tokenizer has read all of `input` and is emitting the last tokens.
+    at_end: Cell<bool>,
+    /// Set between a `<p>` and its end tag.
+    in_paragraph: Cell<bool>,
+}
+
+impl<'a, Sink: TokenSink> MarkupSink<'a, Sink> {
+    fn new(inner: Sink, source: &'a str, input: &'a BufferQueue) -> Self {
+        Self {
+            inner,
+            source,
+            input,
+            token_end: Cell::new(0),
+            at_end: Cell::new(false),
+            in_paragraph: Cell::new(false),
+        }
+    }
+
+    /// The offset in `source` up to which the tokenizer has read the input.
+    fn offset(&self) -> usize {
+        let rest = BufferQueue::default();
+        self.input.swap_with(&rest);
+        let mut rest_len = 0;
+        while let Some(buf) = rest.pop_front() {
+            rest_len += buf.len();
+            self.input.push_back(buf);
+        }
+        self.source.len() - rest_len
+    }
+
+    /// Where the markup that ends at `end` starts.
+    ///
+    /// The tokenizer may already have read the character before the markup when it emitted the
+    /// previous token, so the markup starts at the first `<` from there.
+    fn markup_start(&self, end: usize) -> Option<usize> {
+        let token_end = self.token_end.get();
+        let from = self.source[..token_end]
+            .chars()
+            .next_back()
+            .map_or(token_end, |c| token_end - c.len_utf8());
+        self.source[from..end].find('<').map(|pos| from + pos)
+    }
+
+    /// Whether the tag `tag` that ends at `end` is markup.
+    ///
+    /// In OCR'd text `a <b c` is a `b` tag with the attributes `c` and the words up to the next
+    /// `>`, so a tag with a one letter name, attributes without values and no `>` on its line is
+    /// text. The content of a raw text element like `<title` is text up to its end tag, so such
+    /// a tag is only markup when the end tag follows.
+    fn is_markup(&self, tag: &Tag, end: usize) -> bool {
+        let name = tag.name.to_ascii_lowercase();
+        let name = &*name;
+        if RAW_TEXT_TAGS.contains(&name) {
+            return tag.kind == TagKind::EndTag
+                || self.source[end..]
+                    .to_ascii_lowercase()
+                    .contains(&format!("</{name}"));
+        }
+        if !is_known_tag(name) {
+            return false;
+        }
+        let is_ocr_text = name.len() == 1
+            && !tag.attrs.is_empty()
+            && tag.attrs.iter().all(|attr| attr.value.is_empty())
+            && self
+                .markup_start(end)
+                .is_some_and(|start| self.source[start..end].contains('\n'));
+        !is_ocr_text
+    }
+
+    /// Whether the comment that ends at `end` is a comment, a conditional comment like
+    /// `<![if ! IE]>` or a processing instruction rather than a bogus comment.
+    fn is_comment(&self, end: usize) -> bool {
+        self.markup_start(end).is_some_and(|start| {
+            let rest = &self.source[start + 1..end];
+            rest.starts_with("!--") || rest.starts_with("![") || rest.starts_with('?')
+        })
+    }
+
+    /// Pass on the markup that ends at `end` as text.
+    fn reread_as_text(&self, end: usize, line_number: u64) -> TokenSinkResult<Sink::Handle> {
+        let Some(start) = self.markup_start(end) else {
+            return TokenSinkResult::Continue;
+        };
+        if self.at_end.get() {
+            self.token_end.set(end);
+            return self.process_text(&self.source[start..end], line_number);
+        }
+        self.token_end.set(start + 1);
+        self.input
+            .push_front(StrTendril::from_slice(&self.source[start + 1..end]));
+        self.process_text("<", line_number)
+    }
+
+    fn process_text(&self, text: &str, line_number: u64) -> TokenSinkResult<Sink::Handle> {
+        self.inner
+            .process_token(Token::CharacterTokens(text.into()), line_number)
+    }
+}
+
+impl<Sink: TokenSink> TokenSink for MarkupSink<'_, Sink> {
+    type Handle = Sink::Handle;
+
+    fn process_token(&self, token: Token, line_number: u64) -> TokenSinkResult<Self::Handle> {
+        if let Token::ParseError(_) = token {
+            return self.inner.process_token(token, line_number);
+        }
+        let end = self.offset();
+        let token = match token {
+            Token::TagToken(tag) if !self.is_markup(&tag, end) => {
+                return self.reread_as_text(end, line_number);
+            }
+            Token::CommentToken(_) if !self.is_comment(end) => {
+                return self.reread_as_text(end, line_number);
+            }
+            Token::TagToken(tag) if &*tag.name == "pre" && self.in_paragraph.get() => {
+                self.token_end.set(end);
+                return TokenSinkResult::Continue;
+            }
+            Token::TagToken(tag) if &*tag.name == "p" => {
+                self.in_paragraph.set(tag.kind == TagKind::StartTag);
+                Token::TagToken(tag)
+            }
+            Token::TagToken(mut tag) if tag.self_closing && &*tag.name == "br" => {
+                tag.attrs.push(Attribute {
+                    name: QualName::new(None, ns!(), LocalName::from(SELF_CLOSING)),
+                    value: StrTendril::new(),
+                });
+                Token::TagToken(tag)
+            }
+            Token::CharacterTokens(text) if text.contains(r#"\""#) => {
+                Token::CharacterTokens(text.replace(r#"\""#, r#"""#).into())
+            }
+            // A tag that isn't closed at the end of the input is dropped by the tokenizer. The
+            // tree builder always continues after text
+            Token::EOFToken if self.source[self.token_end.get()..].contains('<') => {
+                let _ = self.reread_as_text(end, line_number);
+                Token::EOFToken
+            }
+            token => token,
+        };
+        self.token_end.set(end);
+        self.inner.process_token(token, line_number)
+    }
+
+    fn end(&self) {
+        self.inner.end();
+    }
+
+    fn adjusted_current_node_present_but_not_in_html_namespace(&self) -> bool {
+        self.inner
+            .adjusted_current_node_present_but_not_in_html_namespace()
+    }
+}
+
+/// The attribute that marks a self-closing `<br/>`.
+const SELF_CLOSING: &str = "self-closing";
+
+/// Whether `node` was written as a self-closing tag like `<br/>`.
+///
+/// Only line breaks are marked.
+pub fn is_self_closing(node: &Handle) -> bool {
+    rcdom_attr(node, SELF_CLOSING).is_some()
+}
+
+/// Whether the lowercase `name` is the name of an element in the documents.
+fn is_known_tag(name: &str) -> bool {
+    match name.split_once(':') {
+        Some((prefix, local)) => !local.is_empty() && WORD_PREFIXES.contains(&prefix),
+        None => HTML_TAGS.contains(&name) || RIKSDAGEN_TAGS.contains(&name),
+    }
+}
+
+const WORD_PREFIXES: &[&str] = &["o", "v", "w", "m", "st1", "x"];
+
+const RIKSDAGEN_TAGS: &[&str] = &[
+    "hanvisning",
+    "ingenbild",
+    "kant",
+    "noter",
+    "notreferens",
+    "rubriksarskiltyttrande",
+    "textovervagande",
+    "yttrandebilaga",
+];
+
+/// Elements whose content the tokenizer reads as text up to their end tag.
+const RAW_TEXT_TAGS: &[&str] = &[
+    "iframe",
+    "noembed",
+    "noframes",
+    "noscript",
+    "plaintext",
+    "script",
+    "style",
+    "textarea",
+    "title",
+    "xmp",
+];
+
+const HTML_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "acronym",
+    "address",
+    "applet",
+    "area",
+    "article",
+    "aside",
+    "audio",
+    "b",
+    "base",
+    "basefont",
+    "bdi",
+    "bdo",
+    "bgsound",
+    "big",
+    "blink",
+    "blockquote",
+    "body",
+    "br",
+    "button",
+    "canvas",
+    "caption",
+    "center",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "data",
+    "datalist",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "dialog",
+    "dir",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "embed",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "font",
+    "footer",
+    "form",
+    "frame",
+    "frameset",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "i",
+    "img",
+    "input",
+    "ins",
+    "kbd",
+    "label",
+    "legend",
+    "li",
+    "link",
+    "listing",
+    "main",
+    "map",
+    "mark",
+    "marquee",
+    "math",
+    "menu",
+    "meta",
+    "meter",
+    "nav",
+    "nobr",
+    "object",
+    "ol",
+    "optgroup",
+    "option",
+    "output",
+    "p",
+    "param",
+    "picture",
+    "pre",
+    "progress",
+    "q",
+    "rp",
+    "rt",
+    "ruby",
+    "s",
+    "samp",
+    "section",
+    "select",
+    "small",
+    "source",
+    "span",
+    "strike",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "svg",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "tr",
+    "track",
+    "tt",
+    "u",
+    "ul",
+    "var",
+    "video",
+    "wbr",
+    "xml",
+];
+
+/// Append `text` to `elem`, extending its last text node if it has one.
+pub fn push_text(elem: &mut Element, text: &str) {
+    if let Some(Node::Text(last)) = elem.nodes_mut().next_back() {
+        last.push_str(text);
+    } else {
+        elem.append_text_node(text);
+    }
+}
+
+/// Collects `<p>` elements into a parent element.
+///
+/// Inline content goes into the current paragraph, which is opened on demand and appended to
+/// the parent when the next paragraph starts or when it is flushed.
+pub struct Paragraphs<'a> {
+    parent: &'a mut Element,
+    curr: Option<Element>,
+}
+
+impl<'a> Paragraphs<'a> {
+    pub fn new(parent: &'a mut Element) -> Self {
+        Self { parent, curr: None }
+    }
+
+    /// The current paragraph, opening one if needed.
+    pub fn current(&mut self) -> &mut Element {
+        self.curr.get_or_insert_with(|| Element::bare("p", ""))
+    }
+
+    /// The current paragraph, if one is open.
+    pub fn current_if_open(&mut self) -> Option<&mut Element> {
+        self.curr.as_mut()
+    }
+
+    /// The current paragraph, or else the last paragraph appended to the parent.
+    pub fn current_or_last(&mut self) -> Option<&mut Element> {
+        if self.curr.is_some() {
+            return self.curr.as_mut();
+        }
+        self.parent
+            .children_mut()
+            .last()
+            .filter(|elem| elem.name() == "p")
+    }
+
+    /// Flush the current paragraph and open a new one.
+    pub fn start(&mut self) -> &mut Element {
+        self.flush();
+        self.current()
+    }
+
+    /// Append the current paragraph to the parent, unless it is empty.
+    pub fn flush(&mut self) {
+        if let Some(p) = self.curr.take() {
+            if !elem_is_empty(&p) {
+                self.parent.append_child(p);
+            }
+        }
+    }
+
+    /// Append the current paragraph to the parent, also if it is empty.
+    pub fn flush_all(&mut self) {
+        if let Some(p) = self.curr.take() {
+            self.parent.append_child(p);
+        }
+    }
+
+    /// Flush the current paragraph and append `elem` to the parent.
+    pub fn append(&mut self, elem: Element) {
+        self.flush();
+        self.parent.append_child(elem);
+    }
+}
+
+impl Drop for Paragraphs<'_> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
@@ -0,0 +1,161 @@
+use super::*;
+
+use crate::nodeinfo::rcdom_text_content;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+fn element_names(node: &Handle, names: &mut Vec<String>) {
+    for child in node.children.borrow().iter() {
+        if let Some(name) = rcdom_elem_name(child) {
+            names.push(name.to_string());
+        }
+        element_names(child, names);
+    }
+}
+
+#[rstest]
+#[case("<p>text</p>", &["p"])]
+#[case("<P CLASS=x>text</P>", &["p"])]
+#[case("<br/><br />", &["br", "br"])]
+#[case("<o:p></o:p>", &["o:p"])]
+#[case("<!-- comment --><![if ! IE]>text<![endif]>", &[])]
+#[case("<kant>text</kant>", &["kant"])]
+#[case("no tags", &[])]
+fn parse_html_keeps_markup(#[case] given: &str, #[case] expected: &[&str]) {
+    let dom = parse_html(given);
+    let mut names = Vec::new();
+    element_names(&html_body(&dom).expect("a body"), &mut names);
+
+    assert_eq!(names, expected);
+}
+
+#[rstest]
+#[case("a <5 b", "a <5 b")]
+#[case("<en kommun", "<en kommun")]
+#[case("<en kommun> och <b>fet</b>", "<en kommun> och fet")]
+#[case("<<text>>", "<<text>>")]
+#[case("åt <ö<ä <en>", "åt <ö<ä <en>")]
+#[case("Portfolio Size <=", "Portfolio Size <=")]
+#[case("<i-text</i>", "<i-text")]
+#[case("<C, e.g. AAA (1</span> text", "<C, e.g. AAA (1 text")]
+#[case("<!x", "<!x")]
+#[case("a </ p> b", "a </ p> b")]
+#[case(r#"och <span>'i\"</span>"#, r#"och 'i""#)]
+fn parse_html_reads_stray_tags_as_text(#[case] given: &str, #[case] expected: &str) {
+    let dom = parse_html(given);
+    let body = html_body(&dom).expect("a body");
+
+    assert_eq!(rcdom_text_content(&body), expected);
+}
+
+/// Scanned, the OCR'd text is in `<p>` elements.
+const MOT_1979_1833: &str = "assets/mot-1971-1979-g3021833.json";
+
+fn paragraph_texts(node: &Handle, texts: &mut Vec<String>) {
+    for child in node.children.borrow().iter() {
+        if rcdom_elem_name(child) == Some("p") {
+            texts.push(rcdom_text_content(child));
+        } else {
+            paragraph_texts(child, texts);
+        }
+    }
+}
+
+#[rstest]
+#[case::bold("av Olof Palme <b m. fl.", "av Olof Palme <b m. fl.")]
+#[case::italic("<i n 1979/80 av Olof Palme", "<i n 1979/80 av Olof Palme")]
+#[case::strike("<s. 12 av Olof Palme", "<s. 12 av Olof Palme")]
+#[case::title("a <title av Olof Palme", "a <title av Olof Palme")]
+#[case::plaintext("<plaintext av Olof Palme", "<plaintext av Olof Palme")]
+#[case::xmp("<xmp av Olof Palme", "<xmp av Olof Palme")]
+#[case::markup("av Olof <b>Palme</b>", "av Olof Palme")]
+fn parse_html_reads_ocr_tags_as_text(#[case] given: &str, #[case] expected: &str) {
+    let source = fs_err::read_to_string(MOT_1979_1833).expect("asset exists");
+    let value: serde_json::Value =
+        serde_json::from_str(source.trim_start_matches('\u{feff}')).expect("valid json");
+    let html = value["dokumentstatus"]["dokument"]["html"]
+        .as_str()
+        .expect("a html field");
+    let html = html.replacen(
+        "<p>av Olof Palme m. fl.</p>",
+        &format!("<p>{given}\n</p>"),
+        1,
+    );
+
+    let dom = parse_html(&html);
+    let mut texts = Vec::new();
+    paragraph_texts(&html_body(&dom).expect("a body"), &mut texts);
+
+    assert_eq!(
+        texts[..5],
+        [
+            "Mot. 1979/80:1833",
+            "Motion \n1979/80:1833",
+            &format!("{expected}\n"),
+            "om den sociala bostadspolitiken",
+            "Innehåll",
+        ]
+    );
+    assert_eq!(texts.len(), 346);
+}
+
+#[test]
+fn parse_html_marks_self_closing_line_breaks() {
+    let dom = parse_html("a<br/>b<br>c");
+    let body = html_body(&dom).expect("a body");
+    let breaks: Vec<_> = body
+        .children
+        .borrow()
+        .iter()
+        .filter(|child| rcdom_elem_name(child) == Some("br"))
+        .map(is_self_closing)
+        .collect();
+
+    assert_eq!(breaks, [true, false]);
+}
+
+#[rstest]
+#[case("&amp;ouml;", "&ouml;")]
+#[case("&amp;#228; &amp;#xE4;", "&#228; &#xE4;")]
+#[case("&amp;amp;", "&amp;")]
+#[case("A &amp; B", "A &amp; B")]
+#[case("&amp;foo;", "&amp;foo;")]
+#[case("&amp;#;", "&amp;#;")]
+fn undo_double_escaping_decodes_known_refs(#[case] given: &str, #[case] expected: &str) {
+    assert_eq!(undo_double_escaping(given), expected);
+}
+
+#[test]
+fn unknown_entities_skips_known_and_numeric_refs() {
+    let contents = "&ndash; &foo; &oslash;&euro; &#228; &bar;x &foo; AT&T &1a;";
+
+    assert_eq!(unknown_entities(contents), BTreeSet::from(["bar", "foo"]));
+}
+
+#[test]
+fn push_text_extends_last_text_node() {
+    let mut elem = Element::bare("p", "");
+    push_text(&mut elem, "a");
+    push_text(&mut elem, "b");
+    elem.append_child(Element::bare("br", ""));
+    push_text(&mut elem, "c");
+
+    assert_eq!(elem.texts().collect::<Vec<_>>(), ["ab", "c"]);
+}
+
+#[test]
+fn paragraphs_skip_empty_paragraphs() {
+    let mut textelem = Element::bare("text", "");
+    {
+        let mut paragraphs = Paragraphs::new(&mut textelem);
+        paragraphs.current().append_text_node("first");
+        paragraphs.start();
+        paragraphs.start().append_text_node("second");
+        paragraphs.append(Element::bare("page", ""));
+        paragraphs.current_if_open();
+    }
+
+    let names: Vec<_> = textelem.children().map(Element::name).collect();
+    assert_eq!(names, ["p", "p", "page"]);
+}
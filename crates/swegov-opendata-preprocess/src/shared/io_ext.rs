@@ -20,9 +20,84 @@ pub fn read_text(path: &Path) -> io::Result<String> {
 }
 
 pub fn without_bom(s: &str) -> &str {
-    if &s[0..3] == "\u{feff}" {
-        &s[3..]
-    } else {
-        s
+    s.strip_prefix('\u{feff}').unwrap_or(s)
+}
+
+/// A reader that replaces every `pattern` read from `inner` with `replacement`.
+///
+/// Works like `str::replace` on the whole input, but only keeps a chunk of it in memory.
+pub struct ReplacingReader<R> {
+    inner: R,
+    pattern: &'static [u8],
+    replacement: &'static [u8],
+    /// Read from `inner` but not yet scanned for `pattern`.
+    pending: Vec<u8>,
+    /// Scanned and ready to be returned.
+    output: Vec<u8>,
+    pos: usize,
+    eof: bool,
+}
+
+impl<R: Read> ReplacingReader<R> {
+    const CHUNK_SIZE: usize = 8 * 1024;
+
+    pub fn new(inner: R, pattern: &'static str, replacement: &'static str) -> Self {
+        assert!(!pattern.is_empty(), "pattern must not be empty");
+        Self {
+            inner,
+            pattern: pattern.as_bytes(),
+            replacement: replacement.as_bytes(),
+            pending: Vec::new(),
+            output: Vec::new(),
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    fn fill_output(&mut self) -> io::Result<()> {
+        self.output.clear();
+        self.pos = 0;
+        while self.output.is_empty() && !(self.eof && self.pending.is_empty()) {
+            let len = self.pending.len();
+            self.pending.resize(len + Self::CHUNK_SIZE, 0);
+            let read = self.inner.read(&mut self.pending[len..]);
+            let read = read.inspect_err(|_| self.pending.truncate(len))?;
+            self.pending.truncate(len + read);
+            self.eof = read == 0;
+
+            // The end of `pending` may be the start of a pattern that continues in the next read
+            let limit = if self.eof {
+                self.pending.len()
+            } else {
+                self.pending.len().saturating_sub(self.pattern.len() - 1)
+            };
+            let mut i = 0;
+            while i < limit {
+                if self.pending[i..].starts_with(self.pattern) {
+                    self.output.extend_from_slice(self.replacement);
+                    i += self.pattern.len();
+                } else {
+                    self.output.push(self.pending[i]);
+                    i += 1;
+                }
+            }
+            self.pending.drain(..i);
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for ReplacingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.output.len() {
+            self.fill_output()?;
+        }
+        let len = buf.len().min(self.output.len() - self.pos);
+        buf[..len].copy_from_slice(&self.output[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
     }
 }
+
+#[cfg(test)]
+mod tests;
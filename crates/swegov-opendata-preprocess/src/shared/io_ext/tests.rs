@@ -0,0 +1,37 @@
+use std::io::Read;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::*;
+
+const PATTERN: &str = "{/* RESERVATIONSTEXT */}";
+
+#[rstest]
+#[case::no_pattern(r#"{"a": "b"}"#, 1)]
+#[case::pattern(r#"{"a": {/* RESERVATIONSTEXT */}, "b": {/* RESERVATIONSTEXT */}}"#, 1)]
+#[case::pattern_over_reads(r#"{"a": {/* RESERVATIONSTEXT */}, "b": {/* RESERVATIONSTEXT */}}"#, 3)]
+#[case::partial_pattern_at_end(r#"{"a": "b"}{/* RESERVATIONS"#, 2)]
+fn replacing_reader_works_like_replace(
+    #[case] given: &str,
+    #[case] read_size: usize,
+) -> io::Result<()> {
+    let inner = Chunked(given.as_bytes(), read_size);
+    let mut reader = ReplacingReader::new(inner, PATTERN, r#""""#);
+
+    let mut actual = String::new();
+    reader.read_to_string(&mut actual)?;
+
+    assert_eq!(actual, given.replace(PATTERN, r#""""#));
+    Ok(())
+}
+
+/// Returns at most `.1` bytes per read.
+struct Chunked<'a>(&'a [u8], usize);
+
+impl Read for Chunked<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.1);
+        self.0.read(&mut buf[..len])
+    }
+}
@@ -0,0 +1,417 @@
+//! Repair of the text of scanned documents, like the motions of the 1970s and the oldest SFS.
+//!
+//! The RD documents are taken for scanned by their riksmöte, see [`OcrRepair::applies_to`], and
+//! the SFS texts by their pages, see [`is_scanned`], since most of the old SFS are consolidated
+//! text that was never scanned.
+//!
+//! The repair drops the running headers and footers of the pages, joins words hyphenated at
+//! line breaks and fixes words with characters that the OCR confused, e.g. `sorn` for `som`,
+//! using a wordlist. Each repaired text gets an `ocr_quality`, the share of its words that are
+//! in the wordlist.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::Path,
+};
+
+use fs_err as fs;
+use minidom::{Element, Node};
+use minidom_extension::minidom;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use crate::PreprocessError;
+
+use super::{cleaning::CONJUNCTIONS, rm_start_year};
+
+#[cfg(test)]
+mod tests;
+
+/// Characters that the OCR confuses, as the misread characters and the right ones.
+const CONFUSIONS: &[(&str, &str)] = &[
+    ("rn", "m"),
+    ("m", "rn"),
+    ("cl", "d"),
+    ("li", "h"),
+    ("h", "li"),
+    ("<", "c"),
+    ("<", "e"),
+    ("c", "e"),
+    ("e", "c"),
+    ("1", "l"),
+    ("1", "i"),
+    ("l", "i"),
+    ("i", "l"),
+    ("0", "o"),
+    ("f", "t"),
+    ("t", "f"),
+    ("a", "ä"),
+    ("a", "å"),
+    ("ä", "å"),
+    ("å", "ä"),
+    ("o", "ö"),
+    ("ö", "o"),
+];
+
+/// The number of pages a text needs before repeated first and last lines are dropped.
+const MIN_PAGES: usize = 3;
+
+/// The longest line, in words, that is taken for a header or footer.
+const MAX_HEADER_WORDS: usize = 12;
+
+/// The words of a language, e.g. Swedish.
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct Wordlist {
+    words: HashSet<String>,
+}
+
+impl Wordlist {
+    /// Read a wordlist with one word per line.
+    pub fn from_path(path: &Path) -> Result<Self, PreprocessError> {
+        let contents =
+            fs::read_to_string(path).map_err(|error| PreprocessError::CouldNotReadFile {
+                path: path.to_path_buf(),
+                error,
+            })?;
+        Ok(contents.lines().collect())
+    }
+
+    /// Whether `word` is in the list, regardless of case.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+// The words would flood the logs of the instrumented functions
+impl fmt::Debug for Wordlist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Wordlist")
+            .field("len", &self.words.len())
+            .finish()
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Wordlist {
+    fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
+        Self {
+            words: iter
+                .into_iter()
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase)
+                .collect(),
+        }
+    }
+}
+
+/// Whether `textelem` is the text of a scanned document, i.e. is split in `<page>` elements like
+/// the `div.dok` html of the oldest SFS.
+pub fn is_scanned(textelem: &Element) -> bool {
+    textelem.has_child("page", "")
+}
+
+/// The OCR repair of the texts of scanned documents.
+#[derive(Debug, Clone)]
+pub struct OcrRepair {
+    wordlist: Wordlist,
+    /// The RD documents from before this year are scanned.
+    before_year: i32,
+}
+
+impl OcrRepair {
+    /// The year before which the RD documents are scanned, unless set with
+    /// [`OcrRepair::before_year`].
+    pub const DEFAULT_BEFORE_YEAR: i32 = 1980;
+
+    pub fn new(wordlist: Wordlist) -> Self {
+        Self {
+            wordlist,
+            before_year: Self::DEFAULT_BEFORE_YEAR,
+        }
+    }
+
+    /// Take the RD documents from before `year` for scanned.
+    pub fn before_year(mut self, year: i32) -> Self {
+        self.before_year = year;
+        self
+    }
+
+    /// Whether the RD documents of the riksmöte `rm` are scanned and should be repaired.
+    pub fn applies_to(&self, rm: &str) -> bool {
+        rm_start_year(rm)
+            .parse::<i32>()
+            .is_ok_and(|year| year < self.before_year)
+    }
+
+    /// Repair the text in `textelem` and set its `ocr_quality`, empty if it has no words.
+    pub fn repair(&self, textelem: &mut Element) {
+        drop_page_headers(textelem);
+        self.repair_element(textelem);
+        let quality = self
+            .quality(textelem)
+            .map(|quality| format!("{quality:.2}"))
+            .unwrap_or_default();
+        textelem.set_attr("ocr_quality", quality);
+    }
+
+    /// The share of the words in `elem` that are in the wordlist.
+    pub fn quality(&self, elem: &Element) -> Option<f64> {
+        let mut words = 0;
+        let mut known = 0;
+        visit_texts(elem, &mut |text| {
+            for word in WORD.find_iter(text).map(|word| word.as_str()) {
+                if word.chars().any(|c| c.is_ascii_digit()) || !has_letters(word) {
+                    continue;
+                }
+                words += 1;
+                if self.wordlist.contains(word) {
+                    known += 1;
+                }
+            }
+        });
+        (words > 0).then(|| f64::from(known) / f64::from(words))
+    }
+
+    fn repair_element(&self, elem: &mut Element) {
+        let mut nodes: Vec<Node> = Vec::new();
+        for node in elem.take_nodes() {
+            match node {
+                Node::Text(text) => match self.join_line_break(&mut nodes, &text) {
+                    Some(joined) => nodes.push(Node::Text(joined)),
+                    None => nodes.push(Node::Text(text)),
+                },
+                Node::Element(mut child) => {
+                    self.repair_element(&mut child);
+                    nodes.push(Node::Element(child));
+                }
+            }
+        }
+        for node in nodes {
+            match node {
+                Node::Text(text) => {
+                    let text = self.join_hyphenated(&text);
+                    elem.append_text_node(self.fix_words(&text));
+                }
+                node => elem.append_node(node),
+            }
+        }
+    }
+
+    /// Join `text` with the text before the `<br>` that ends `nodes`, if that text ends with a
+    /// word hyphenated at the line break, removing them from `nodes`.
+    fn join_line_break(&self, nodes: &mut Vec<Node>, text: &str) -> Option<String> {
+        static HEAD: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\p{L}+)-$").expect("valid regex"));
+        static TAIL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\p{L}+").expect("valid regex"));
+        let [.., Node::Text(before), Node::Element(br)] = nodes.as_slice() else {
+            return None;
+        };
+        if br.name() != "br" {
+            return None;
+        }
+        let head = HEAD.captures(before)?.get(1)?.as_str();
+        let tail = TAIL.find(text)?.as_str();
+        if !self.joins(head, tail) {
+            return None;
+        }
+        let joined = format!("{}{text}", &before[..before.len() - 1]);
+        nodes.truncate(nodes.len() - 2);
+        Some(joined)
+    }
+
+    /// Join the words in `text` that are hyphenated at a line break, e.g. `rege- ringen`.
+    fn join_hyphenated(&self, text: &str) -> String {
+        static HYPHENATED: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"(?P<head>\p{L}+)-\s+(?P<tail>\p{L}+)").expect("valid regex"));
+        HYPHENATED
+            .replace_all(text, |captures: &Captures<'_>| {
+                let head = &captures["head"];
+                let tail = &captures["tail"];
+                if self.joins(head, tail) {
+                    format!("{head}{tail}")
+                } else {
+                    captures[0].to_string()
+                }
+            })
+            .into_owned()
+    }
+
+    /// Whether `head-` and `tail` are the parts of one word: the tail is lowercase and not a
+    /// conjunction and either the joined word is known or the head isn't a word of its own.
+    fn joins(&self, head: &str, tail: &str) -> bool {
+        tail.starts_with(char::is_lowercase)
+            && !CONJUNCTIONS.contains(&tail)
+            && (self.wordlist.contains(&format!("{head}{tail}")) || !self.wordlist.contains(head))
+    }
+
+    /// Replace the unknown words in `text` that are known after undoing a confusion.
+    fn fix_words(&self, text: &str) -> String {
+        WORD.replace_all(text, |captures: &Captures<'_>| {
+            let word = &captures[0];
+            self.fix_word(word).unwrap_or_else(|| word.to_string())
+        })
+        .into_owned()
+    }
+
+    fn fix_word(&self, word: &str) -> Option<String> {
+        if !has_letters(word) || self.wordlist.contains(word) {
+            return None;
+        }
+        let lower = word.to_lowercase();
+        for (misread, right) in CONFUSIONS {
+            for (pos, _) in lower.match_indices(misread) {
+                let candidate =
+                    format!("{}{right}{}", &lower[..pos], &lower[pos + misread.len()..]);
+                if self.wordlist.contains(&candidate) {
+                    return Some(with_case_of(word, &candidate));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A word, possibly with characters that the OCR confused for letters.
+static WORD: Lazy<Regex> = Lazy::new(|| Regex::new(r"[\p{L}\d<]+").expect("valid regex"));
+
+/// Whether `word` has at least two letters, the shorter ones aren't repaired or counted.
+fn has_letters(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).count() >= 2
+}
+
+/// `word` written in the case of `original`, all uppercase or with an initial capital.
+fn with_case_of(original: &str, word: &str) -> String {
+    let mut letters = original.chars().filter(|c| c.is_alphabetic());
+    if letters.clone().count() > 1 && letters.clone().all(char::is_uppercase) {
+        return word.to_uppercase();
+    }
+    match letters.next() {
+        Some(first) if first.is_uppercase() => {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        _ => word.to_string(),
+    }
+}
+
+fn visit_texts(elem: &Element, f: &mut impl FnMut(&str)) {
+    for node in elem.nodes() {
+        match node {
+            Node::Text(text) => f(text),
+            Node::Element(child) => visit_texts(child, f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Edge {
+    First,
+    Last,
+}
+
+/// Drop the first and last lines of the pages in `textelem` that are repeated on many of its
+/// pages, the running headers and footers like `Mot. 1979/80:1833` and page numbers.
+///
+/// Numbers are ignored when comparing the lines.
+fn drop_page_headers(textelem: &mut Element) {
+    let pages = textelem
+        .children()
+        .filter(|child| child.name() == "page")
+        .count();
+    if pages < MIN_PAGES {
+        return;
+    }
+    let mut counts: HashMap<(Edge, String), usize> = HashMap::new();
+    for page in textelem.children().filter(|child| child.name() == "page") {
+        for edge in [Edge::First, Edge::Last] {
+            if let Some(key) = edge_p(page, edge).and_then(line_key) {
+                *counts.entry((edge, key)).or_default() += 1;
+            }
+        }
+    }
+    let repeated = |edge: Edge, p: Option<&Element>| {
+        p.and_then(line_key)
+            .and_then(|key| counts.get(&(edge, key)))
+            .is_some_and(|count| *count >= MIN_PAGES && count * 3 >= pages)
+    };
+    for page in textelem
+        .children_mut()
+        .filter(|child| child.name() == "page")
+    {
+        let drop_last = repeated(Edge::Last, edge_p(page, Edge::Last));
+        let last_key = edge_p(page, Edge::Last).and_then(line_key);
+        if repeated(Edge::First, edge_p(page, Edge::First)) {
+            remove_edge_p(page, Edge::First);
+        }
+        // Unless it was the first line, too
+        if drop_last && edge_p(page, Edge::Last).and_then(line_key) == last_key {
+            remove_edge_p(page, Edge::Last);
+        }
+    }
+}
+
+/// The text of the line `p`, lowercase and with its numbers as `#`, if it is short enough to
+/// be a header or footer.
+fn line_key(p: &Element) -> Option<String> {
+    static NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").expect("valid regex"));
+    let mut text = String::new();
+    visit_texts(p, &mut |part| {
+        text.push(' ');
+        text.push_str(part);
+    });
+    let words: Vec<_> = text.split_whitespace().collect();
+    if words.is_empty() || words.len() > MAX_HEADER_WORDS {
+        return None;
+    }
+    Some(
+        NUMBER
+            .replace_all(&words.join(" ").to_lowercase(), "#")
+            .into_owned(),
+    )
+}
+
+/// The first or last `p` of `elem`, looking into the first or last child.
+fn edge_p(elem: &Element, edge: Edge) -> Option<&Element> {
+    let child = match edge {
+        Edge::First => elem.children().next(),
+        Edge::Last => elem.children().last(),
+    }?;
+    if child.name() == "p" {
+        Some(child)
+    } else {
+        edge_p(child, edge)
+    }
+}
+
+/// Remove the `p` that [`edge_p`] finds.
+fn remove_edge_p(elem: &mut Element, edge: Edge) {
+    let mut nodes = elem.take_nodes();
+    let is_element = |node: &Node| matches!(node, Node::Element(_));
+    let index = match edge {
+        Edge::First => nodes.iter().position(is_element),
+        Edge::Last => nodes.iter().rposition(is_element),
+    };
+    if let Some(index) = index {
+        if let Node::Element(child) = &mut nodes[index] {
+            if child.name() == "p" {
+                nodes.remove(index);
+            } else {
+                remove_edge_p(child, edge);
+            }
+        }
+    }
+    for node in nodes {
+        elem.append_node(node);
+    }
+}
@@ -0,0 +1,150 @@
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+fn wordlist() -> Wordlist {
+    [
+        "det",
+        "är",
+        "som",
+        "att",
+        "regeringen",
+        "förslag",
+        "riksdagen",
+        "hemställs",
+        "motion",
+        "och",
+        "ungdomsfrågor",
+        "barn",
+        "dom",
+        "mot",
+        "på",
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn p(text: &str) -> Element {
+    Element::builder("p", "").append(text).build()
+}
+
+fn to_string(elem: &Element) -> String {
+    let mut out = Vec::new();
+    elem.write_to(&mut out).expect("writing to a vec");
+    String::from_utf8(out).expect("valid utf8")
+}
+
+#[rstest]
+#[case("sorn", Some("som"))]
+#[case("Sorn", Some("Som"))]
+#[case("REGERINGEN", None)]
+#[case("regeringcn", Some("regeringen"))]
+#[case("f0rslag", None)]
+#[case("förslag", None)]
+#[case("1979", None)]
+#[case("Palme", None)]
+fn fix_word_undoes_confusions(#[case] given: &str, #[case] expected: Option<&str>) {
+    let repair = OcrRepair::new(wordlist());
+
+    assert_eq!(repair.fix_word(given).as_deref(), expected);
+}
+
+#[rstest]
+#[case("rege- ringen", "regeringen")]
+#[case("barn- och ungdomsfrågor", "barn- och ungdomsfrågor")]
+#[case("riks- dagen", "riksdagen")]
+#[case("mot- Riksdagen", "mot- Riksdagen")]
+fn join_hyphenated_joins_words(#[case] given: &str, #[case] expected: &str) {
+    let repair = OcrRepair::new(wordlist());
+
+    assert_eq!(repair.join_hyphenated(given), expected);
+}
+
+#[test]
+fn repair_joins_words_across_line_breaks() {
+    let repair = OcrRepair::new(wordlist());
+    let mut textelem = Element::builder("text", "")
+        .append(
+            Element::builder("p", "")
+                .append("det är rege-")
+                .append(Element::bare("br", ""))
+                .append("ringens förslag")
+                .build(),
+        )
+        .build();
+
+    repair.repair(&mut textelem);
+
+    assert_eq!(
+        to_string(&textelem),
+        r#"<text xmlns="" ocr_quality="0.75"><p>det är regeringens förslag</p></text>"#
+    );
+}
+
+#[test]
+fn repair_drops_repeated_headers_and_footers() {
+    let repair = OcrRepair::new(wordlist());
+    let mut textelem = Element::bare("text", "");
+    for page in 1..=3 {
+        textelem.append_child(
+            Element::builder("page", "")
+                .append(p(&format!("Mot. 1971:{page}")))
+                .append(p("det är sorn regeringen"))
+                .append(p(&page.to_string()))
+                .build(),
+        );
+    }
+
+    repair.repair(&mut textelem);
+
+    assert_eq!(
+        to_string(&textelem),
+        concat!(
+            r#"<text xmlns="" ocr_quality="1.00">"#,
+            "<page><p>det är som regeringen</p></page>",
+            "<page><p>det är som regeringen</p></page>",
+            "<page><p>det är som regeringen</p></page>",
+            "</text>"
+        )
+    );
+}
+
+#[test]
+fn repair_keeps_lines_of_few_pages() {
+    let repair = OcrRepair::new(wordlist());
+    let mut textelem = Element::bare("text", "");
+    for _ in 0..2 {
+        textelem.append_child(
+            Element::builder("page", "")
+                .append(p("Motion"))
+                .append(p("det är förslag"))
+                .build(),
+        );
+    }
+
+    repair.repair(&mut textelem);
+
+    assert_eq!(textelem.children().flat_map(Element::children).count(), 4);
+}
+
+#[test]
+fn repair_sets_empty_quality_without_words() {
+    let repair = OcrRepair::new(wordlist());
+    let mut textelem = Element::builder("text", "").append(p("1 §")).build();
+
+    repair.repair(&mut textelem);
+
+    assert_eq!(textelem.attr("ocr_quality"), Some(""));
+}
+
+#[rstest]
+#[case("1971", true)]
+#[case("1979/80", true)]
+#[case("1980/81", false)]
+#[case("2003", false)]
+fn applies_to_documents_before_year(#[case] rm: &str, #[case] expected: bool) {
+    let repair = OcrRepair::new(wordlist()).before_year(1980);
+
+    assert_eq!(repair.applies_to(rm), expected);
+}
@@ -0,0 +1,175 @@
+//! Writing html tables as `<table>`, `<row>` and `<cell>` elements.
+
+use std::fmt;
+
+use markup5ever_rcdom::Handle;
+use minidom_extension::minidom::Element;
+
+use crate::nodeinfo::{rcdom_attr, rcdom_elem_name};
+
+use super::html::Paragraphs;
+
+#[cfg(test)]
+mod tests;
+
+/// How the tables in the html are written to the Sparv source.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TableMode {
+    /// Keep the text of the cells as paragraphs, the tables on scanned SFS pages are removed.
+    #[default]
+    Flatten,
+    /// Write the tables as `<table><row><cell>` elements, see [`structured_table`].
+    Structure,
+    /// Leave out the tables and their text.
+    Exclude,
+}
+
+impl TableMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Flatten => "flatten",
+            Self::Structure => "structure",
+            Self::Exclude => "exclude",
+        }
+    }
+}
+
+impl fmt::Display for TableMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TableMode {
+    type Err = UnknownTableMode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flatten" => Ok(Self::Flatten),
+            "structure" => Ok(Self::Structure),
+            "exclude" => Ok(Self::Exclude),
+            _ => Err(UnknownTableMode(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown table mode '{0}', expected one of flatten, structure or exclude")]
+pub struct UnknownTableMode(pub String);
+
+/// Build a `<table>` from the html `table`, using `fill` to write the content of each cell and
+/// of the caption as paragraphs.
+///
+/// Each `tr` becomes a `<row index=…>` and each `td` or `th` a
+/// `<cell row=… col=… header=…>`, with `colspan` and `rowspan` when the cell spans more than one
+/// column or row. Rows and columns are numbered from 1, counting the columns that are covered
+/// by cells spanning from earlier rows. A cell is a header if it is a `th` or in a `thead`.
+pub fn structured_table<E>(
+    table: &Handle,
+    mut fill: impl FnMut(&Handle, &mut Paragraphs<'_>) -> Result<(), E>,
+) -> Result<Element, E> {
+    let mut elem = Element::bare("table", "");
+    let mut grid = TableGrid::default();
+    add_rows(table, &mut elem, &mut grid, false, &mut fill)?;
+    Ok(elem)
+}
+
+fn add_rows<E>(
+    node: &Handle,
+    table: &mut Element,
+    grid: &mut TableGrid,
+    in_head: bool,
+    fill: &mut impl FnMut(&Handle, &mut Paragraphs<'_>) -> Result<(), E>,
+) -> Result<(), E> {
+    for child in node.children.borrow().iter() {
+        match rcdom_elem_name(child) {
+            Some("caption") => {
+                let mut caption = Element::bare("caption", "");
+                fill(child, &mut Paragraphs::new(&mut caption))?;
+                table.append_child(caption);
+            }
+            Some("thead") => add_rows(child, table, grid, true, fill)?,
+            Some("tbody" | "tfoot") => add_rows(child, table, grid, false, fill)?,
+            Some("tr") => {
+                let mut row = Element::bare("row", "");
+                row.set_attr("index", grid.start_row());
+                for cell_node in child.children.borrow().iter() {
+                    let name = rcdom_elem_name(cell_node);
+                    if !matches!(name, Some("td" | "th")) {
+                        continue;
+                    }
+                    let colspan = span_attr(cell_node, "colspan", 1000);
+                    let rowspan = span_attr(cell_node, "rowspan", 65534);
+                    let mut cell = Element::bare("cell", "");
+                    cell.set_attr("row", grid.rows);
+                    cell.set_attr("col", grid.place(colspan, rowspan));
+                    cell.set_attr("header", (in_head || name == Some("th")).to_string());
+                    if colspan > 1 {
+                        cell.set_attr("colspan", colspan);
+                    }
+                    if rowspan > 1 {
+                        cell.set_attr("rowspan", rowspan);
+                    }
+                    fill(cell_node, &mut Paragraphs::new(&mut cell))?;
+                    row.append_child(cell);
+                }
+                table.append_child(row);
+            }
+            // Whitespace, `colgroup` and the odd `script` or `form`
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// The `colspan` or `rowspan` of a cell, clamped to `1..=max` like browsers do.
+fn span_attr(cell: &Handle, name: &str, max: usize) -> usize {
+    rcdom_attr(cell, name)
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(1)
+        .clamp(1, max)
+}
+
+/// Tracks the position of the cells of a table.
+#[derive(Debug, Default)]
+struct TableGrid {
+    /// The number of rows started so far, the index of the current row.
+    rows: usize,
+    /// The 0-based column where the search for a free slot for the next cell starts.
+    col: usize,
+    /// The number of rows, counting the current one, that are covered by a cell from an earlier
+    /// row, per column.
+    covered: Vec<usize>,
+}
+
+impl TableGrid {
+    /// Start a new row, returning its index.
+    fn start_row(&mut self) -> usize {
+        for covered in &mut self.covered {
+            *covered = covered.saturating_sub(1);
+        }
+        self.rows += 1;
+        self.col = 0;
+        self.rows
+    }
+
+    /// Place a cell at the first free column of the current row, returning its column index.
+    fn place(&mut self, colspan: usize, rowspan: usize) -> usize {
+        while self
+            .covered
+            .get(self.col)
+            .is_some_and(|covered| *covered > 0)
+        {
+            self.col += 1;
+        }
+        let col = self.col;
+        if self.covered.len() < col + colspan {
+            self.covered.resize(col + colspan, 0);
+        }
+        for covered in &mut self.covered[col..col + colspan] {
+            *covered = rowspan;
+        }
+        self.col += colspan;
+        col + 1
+    }
+}
@@ -0,0 +1,131 @@
+use std::convert::Infallible;
+
+use super::*;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use crate::{
+    nodeinfo::rcdom_text_content,
+    shared::html::{html_body, parse_html},
+};
+
+fn build_table(html: &str) -> Element {
+    let dom = parse_html(html);
+    let body = html_body(&dom).expect("a body");
+    let table = body.children.borrow()[0].clone();
+    let Ok(elem) = structured_table(&table, |node, paragraphs| {
+        paragraphs
+            .current()
+            .append_text_node(rcdom_text_content(node));
+        Ok::<_, Infallible>(())
+    });
+    elem
+}
+
+/// `(row, col, header, text)` of each cell.
+fn cells(table: &Element) -> Vec<(String, String, String, String)> {
+    table
+        .children()
+        .filter(|row| row.name() == "row")
+        .flat_map(Element::children)
+        .map(|cell| {
+            (
+                cell.attr("row").unwrap_or_default().to_string(),
+                cell.attr("col").unwrap_or_default().to_string(),
+                cell.attr("header").unwrap_or_default().to_string(),
+                paragraph_texts(cell),
+            )
+        })
+        .collect()
+}
+
+fn paragraph_texts(elem: &Element) -> String {
+    elem.children().map(Element::text).collect()
+}
+
+fn cell(row: &str, col: &str, header: &str, text: &str) -> (String, String, String, String) {
+    (row.into(), col.into(), header.into(), text.into())
+}
+
+#[test]
+fn structured_table_numbers_rows_and_cells() {
+    let table = build_table(
+        "<table><thead><tr><td>År</td><td>Belopp</td></tr></thead>\
+         <tr><th>2020</th><td>10</td></tr></table>",
+    );
+
+    let rows: Vec<_> = table
+        .children()
+        .map(|row| (row.name().to_string(), row.attr("index").map(String::from)))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("row".to_string(), Some("1".to_string())),
+            ("row".to_string(), Some("2".to_string())),
+        ]
+    );
+    assert_eq!(
+        cells(&table),
+        [
+            cell("1", "1", "true", "År"),
+            cell("1", "2", "true", "Belopp"),
+            cell("2", "1", "true", "2020"),
+            cell("2", "2", "false", "10"),
+        ]
+    );
+}
+
+#[test]
+fn structured_table_skips_columns_covered_by_spans() {
+    let table = build_table(
+        "<table><tr><td rowspan=2>a</td><td colspan=2>b</td><td>c</td></tr>\
+         <tr><td>d</td><td>e</td></tr></table>",
+    );
+
+    assert_eq!(
+        cells(&table),
+        [
+            cell("1", "1", "false", "a"),
+            cell("1", "2", "false", "b"),
+            cell("1", "4", "false", "c"),
+            cell("2", "2", "false", "d"),
+            cell("2", "3", "false", "e"),
+        ]
+    );
+    let first = table
+        .children()
+        .next()
+        .and_then(|row| row.children().next());
+    assert_eq!(first.and_then(|cell| cell.attr("rowspan")), Some("2"));
+}
+
+#[test]
+fn structured_table_keeps_caption() {
+    let table = build_table("<table><caption>Tabell 1</caption><tr><td>a</td></tr></table>");
+
+    let names: Vec<_> = table.children().map(Element::name).collect();
+    assert_eq!(names, ["caption", "row"]);
+    assert_eq!(
+        table.children().next().map(paragraph_texts).as_deref(),
+        Some("Tabell 1")
+    );
+}
+
+#[rstest]
+#[case("flatten", TableMode::Flatten)]
+#[case("structure", TableMode::Structure)]
+#[case("exclude", TableMode::Exclude)]
+fn table_mode_round_trips(#[case] given: &str, #[case] expected: TableMode) {
+    assert_eq!(given.parse::<TableMode>(), Ok(expected));
+    assert_eq!(expected.to_string(), given);
+}
+
+#[test]
+fn table_mode_rejects_unknown() {
+    assert_eq!(
+        "cells".parse::<TableMode>(),
+        Err(UnknownTableMode("cells".to_string()))
+    );
+}
@@ -40,3 +40,33 @@ fn clean_text_doesnt_touch_cleaned(#[case] given: &str) {
 fn clean_text_cleans_text(#[case] given: &str, #[case] expected: &str) {
     assert_eq!(clean_text(given), expected);
 }
+
+#[rstest]
+#[case("2019/20", "2019")]
+#[case("1999/2000", "1999")]
+#[case("1880", "1880")]
+#[case("2007/08:1", "2007")]
+#[case("", "")]
+#[case("201213", "")]
+fn rm_start_year_parses_riksmote(#[case] given: &str, #[case] expected: &str) {
+    assert_eq!(rm_start_year(given), expected);
+}
+
+#[rstest]
+#[case("bet", "2019/20", "FiU11", "bet-2019-20-fiu11", "bet-2019-fiu-000011")]
+#[case("sfs", "1994", "1994:448", "sfs-1994-448", "sfs-1994-0-000448-000")]
+#[case("fr", "1997/98", "103", "", "")]
+fn set_beteckning_attrs_sets_id_and_sort_key(
+    #[case] typ: &str,
+    #[case] rm: &str,
+    #[case] beteckning: &str,
+    #[case] canonical_id: &str,
+    #[case] sort_key: &str,
+) {
+    let mut elem = Element::builder("text", "").build();
+
+    set_beteckning_attrs(&mut elem, Beteckning::from_parts(typ, rm, beteckning));
+
+    assert_eq!(elem.attr("beteckning_id"), Some(canonical_id));
+    assert_eq!(elem.attr("beteckning_sortkey"), Some(sort_key));
+}
@@ -3,7 +3,13 @@ use fs_err as fs;
 use minidom_extension::minidom::Element;
 use rstest::rstest;
 use swegov_opendata::{DataSet, DokumentStatusPageRef, DokumentStatusRef};
-use swegov_opendata_preprocess::{preprocess_rd, shared::io_ext};
+use swegov_opendata_preprocess::{
+    preprocess_rd::{
+        self, MediaManifest, PreprocessJsonContext, SpeakerRegister, UnknownTagPolicies,
+        UnknownTagReport,
+    },
+    shared::{cleaning::CleanSteps, io_ext, ocr::OcrRepair, tables::TableMode},
+};
 
 #[rstest]
 #[case("assets/bet-1998-2001-gp01bou1.json")]
@@ -98,11 +104,80 @@ fn preprocess_rd_html(#[case] filename: &str) -> anyhow::Result<()> {
     let source = io_ext::without_bom(&source);
     let DokumentStatusPageRef {
         dokumentstatus: DokumentStatusRef { dokument, .. },
+        ..
     } = serde_json::from_str(source)?;
 
     let mut textelem = Element::bare("text", "");
-    preprocess_rd::process_html(dokument.html().expect("valid html"), &mut textelem)?;
+    preprocess_rd::process_html(
+        dokument.html().expect("valid html"),
+        &mut textelem,
+        TableMode::Flatten,
+        &preprocess_rd::UnknownTagPolicies::default(),
+    )?;
 
     insta::assert_debug_snapshot!(format!("html-{}", filename), textelem);
     Ok(())
 }
+
+#[test]
+fn preprocess_rd_json_repairs_all_texts_of_scanned_documents() -> anyhow::Result<()> {
+    let file_data = fs::read_to_string("assets/mot-1998-2001-gp02fi39.json")?;
+    let metadata: DataSet =
+        serde_json::from_str(&fs::read_to_string("assets/mot-1998-2001.metadata.json")?)?;
+    let ocr_repair = OcrRepair::new(["motion"].into_iter().collect()).before_year(2010);
+
+    let xmlstring = preprocess_rd::preprocess_json_with_context(
+        &file_data,
+        Some(&metadata),
+        PreprocessJsonContext {
+            media_manifest: &mut MediaManifest::default(),
+            speakers: &SpeakerRegister::default(),
+            tables: TableMode::default(),
+            clean_steps: CleanSteps::default(),
+            unknown_tags: &UnknownTagPolicies::default(),
+            unknown_tag_report: &mut UnknownTagReport::default(),
+            ocr_repair: Some(&ocr_repair),
+        },
+    )?;
+    let docelem: Element = String::from_utf8(xmlstring)?.parse()?;
+
+    let texts: Vec<_> = docelem
+        .children()
+        .filter(|child| child.is("text", ""))
+        .collect();
+    assert!(texts
+        .iter()
+        .any(|text| text.attr("datatyp") == Some("forslag")));
+    for text in texts {
+        assert!(text.attr("ocr_quality").is_some(), "{text:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn preprocess_rd_json_without_metadata() -> anyhow::Result<()> {
+    let file_data = fs::read_to_string("assets/mot-1998-2001-gp02fi39.json")?;
+    let metadata: DataSet =
+        serde_json::from_str(&fs::read_to_string("assets/mot-1998-2001.metadata.json")?)?;
+
+    let without_metadata = preprocess_rd::preprocess_json_with_context(
+        &file_data,
+        None,
+        PreprocessJsonContext {
+            media_manifest: &mut MediaManifest::default(),
+            speakers: &SpeakerRegister::default(),
+            tables: TableMode::default(),
+            clean_steps: CleanSteps::default(),
+            unknown_tags: &UnknownTagPolicies::default(),
+            unknown_tag_report: &mut UnknownTagReport::default(),
+            ocr_repair: None,
+        },
+    )?;
+
+    // The metadata of the dataset has no upplysning
+    assert_eq!(
+        String::from_utf8(without_metadata)?,
+        String::from_utf8(preprocess_rd::preprocess_json(&file_data, &metadata)?)?
+    );
+    Ok(())
+}
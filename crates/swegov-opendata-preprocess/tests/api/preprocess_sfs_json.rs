@@ -5,6 +5,7 @@ use std::path::PathBuf;
 
 use minidom_extension::minidom::{quick_xml::reader::Reader, Element};
 use swegov_opendata_preprocess::preprocess_sfs::{build_sparv_source, sfs_json};
+use swegov_opendata_preprocess::shared::{cleaning::CleanSteps, tables::TableMode};
 use swegov_opendata_preprocess::{PreprocessError, PreprocessResult};
 
 #[test]
@@ -28,12 +29,12 @@ fn test_preprocess_sfs_json() -> PreprocessResult<()> {
     example1_source_file.read_to_string(&mut example1_source)?;
 
     // Act
-    let actual = sfs_json::preprocess_json(&example1_source).map_err(|error| {
-        PreprocessError::SfsPreprocessError {
-            path: example1_source_path.clone(),
-            error,
-        }
-    })?;
+    let actual =
+        sfs_json::preprocess_json(&example1_source, TableMode::Flatten, CleanSteps::SFS, None)
+            .map_err(|error| PreprocessError::SfsPreprocessError {
+                path: example1_source_path.clone(),
+                error,
+            })?;
 
     // Assert
     let mut reader = Reader::from_reader(actual.as_slice());
@@ -66,7 +67,13 @@ fn test_build_sparv_source_sfs_1976() -> anyhow::Result<()> {
     let corpus_source_dir = assets_path.join("gen").join("sfs-1976");
 
     // Act
-    build_sparv_source(&example1_source_path, &corpus_source_dir)?;
+    build_sparv_source(
+        &example1_source_path,
+        &corpus_source_dir,
+        TableMode::Flatten,
+        CleanSteps::SFS,
+        None,
+    )?;
 
     // Assert
     let actual_path = "assets/gen/sfs-1976/sfs-1976-1.xml";
@@ -86,7 +93,13 @@ fn test_build_sparv_source_sfs_1994() -> anyhow::Result<()> {
     let corpus_source_dir = assets_path.join("gen").join("sfs-1994");
 
     // Act
-    build_sparv_source(&example1_source_path, &corpus_source_dir)?;
+    build_sparv_source(
+        &example1_source_path,
+        &corpus_source_dir,
+        TableMode::Flatten,
+        CleanSteps::SFS,
+        None,
+    )?;
 
     // Assert
     let actual_path = "assets/gen/sfs-1994/sfs-1994-1.xml";
@@ -107,7 +120,13 @@ fn test_build_sparv_source_cks6riksg() -> anyhow::Result<()> {
     let corpus_source_dir = assets_path.join("gen").join("cks6riksg");
 
     // Act
-    build_sparv_source(&example1_source_path, &corpus_source_dir)?;
+    build_sparv_source(
+        &example1_source_path,
+        &corpus_source_dir,
+        TableMode::Flatten,
+        CleanSteps::SFS,
+        None,
+    )?;
 
     // Assert
     let actual_path = "assets/gen/cks6riksg/cks6riksg-1.xml";
@@ -117,3 +136,66 @@ fn test_build_sparv_source_cks6riksg() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// The SFS documents in `assets/sfs-*`, both in this crate and in the workspace.
+fn sfs_assets() -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for assets_path in [
+        [env!("CARGO_MANIFEST_DIR"), "assets"].iter().collect(),
+        [env!("CARGO_MANIFEST_DIR"), "..", "..", "assets"]
+            .iter()
+            .collect::<PathBuf>(),
+    ] {
+        for entry in fs::read_dir(&assets_path)? {
+            let path = entry?.path();
+            let is_sfs = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("sfs-"));
+            if !is_sfs {
+                continue;
+            }
+            if path.is_dir() {
+                for entry in fs::read_dir(&path)? {
+                    paths.push(entry?.path());
+                }
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[test]
+fn preprocess_json_does_not_panic_on_sfs_assets() -> anyhow::Result<()> {
+    let paths = sfs_assets()?;
+    assert!(!paths.is_empty(), "no SFS assets found");
+
+    let mut panicked = Vec::new();
+    let mut failed = Vec::new();
+    for path in paths {
+        let source = fs::read_to_string(&path)?;
+        for tables in [TableMode::Flatten, TableMode::Structure, TableMode::Exclude] {
+            let result = std::panic::catch_unwind(|| {
+                sfs_json::preprocess_json(&source, tables, CleanSteps::SFS, None)
+            });
+            match result {
+                Ok(Ok(_)) => (),
+                Ok(Err(error)) => {
+                    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                    failed.push(format!("{file_name} ({tables}): {error}"));
+                }
+                Err(_) => panicked.push(format!("{} ({tables})", path.display())),
+            }
+        }
+    }
+    assert_eq!(panicked, Vec::<String>::new());
+    // This document has no text in the data from Riksdagen
+    let expected = ["flatten", "structure", "exclude"].map(|tables| {
+        format!("sfs-1880-cds0riksb.json ({tables}): The 'html' field of the dokument is empty")
+    });
+    assert_eq!(failed, expected);
+    Ok(())
+}
@@ -0,0 +1,347 @@
+use std::{fmt, str::FromStr};
+
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+use crate::{Riksmote, RiksmoteError};
+
+#[cfg(test)]
+mod tests;
+
+/// A number in Svensk författningssamling, e.g. "1976:114", "1880:48 s.1" or "1909:bih. 29 s.1".
+///
+/// Older volumes printed several statutes under one number, `sida` is then the page,
+/// and some were printed in a supplement (`bihang`).
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct SfsNummer {
+    pub year: Option<u16>,
+    pub bihang: bool,
+    pub nummer: u32,
+    pub sida: Option<u32>,
+}
+
+/// The beteckning of a document, as used when citing it.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, SerializeDisplay, DeserializeFromStr,
+)]
+pub enum Beteckning {
+    /// "1976:114"
+    Sfs(SfsNummer),
+    /// "Prop. 2018/19:100"
+    Proposition { riksmote: Riksmote, nummer: u32 },
+    /// "2019/20:FiU11", the report of a committee (utskott)
+    Betankande {
+        riksmote: Riksmote,
+        utskott: String,
+        nummer: u32,
+    },
+    /// "Mot. 2019/20:3256", older motions are numbered per committee, "Mot. 1998/99:Bo208"
+    Motion {
+        riksmote: Riksmote,
+        utskott: Option<String>,
+        nummer: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BeteckningError {
+    #[error("'{0}' is not a valid beteckning")]
+    InvalidFormat(String),
+    #[error("'{0}' could be both a proposition and a motion, add 'Prop.' or 'Mot.'")]
+    Ambiguous(String),
+    #[error("beteckningar of documents of type '{0}' are not supported")]
+    UnsupportedTyp(String),
+    #[error(transparent)]
+    Riksmote(#[from] RiksmoteError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Proposition,
+    Betankande,
+    Motion,
+}
+
+impl Beteckning {
+    /// Parse the beteckning of a document from the `typ`, `rm` and `beteckning` fields.
+    ///
+    /// The fields of RD documents only hold the last part, e.g. typ "bet", rm "2019/20"
+    /// and beteckning "FiU11".
+    pub fn from_parts(typ: &str, rm: &str, beteckning: &str) -> Result<Self, BeteckningError> {
+        let kind = match typ.trim().to_lowercase().as_str() {
+            "sfs" => {
+                let year = parse_digits(rm.trim()).filter(|_| rm.trim().len() == 4);
+                return parse_sfs(beteckning, year)
+                    .map(Self::Sfs)
+                    .ok_or_else(|| BeteckningError::InvalidFormat(beteckning.to_string()));
+            }
+            "prop" => Kind::Proposition,
+            "bet" => Kind::Betankande,
+            "mot" => Kind::Motion,
+            _ => return Err(BeteckningError::UnsupportedTyp(typ.to_string())),
+        };
+        from_kind(Some(kind), rm.parse()?, beteckning)
+    }
+
+    pub fn riksmote(&self) -> Option<Riksmote> {
+        match self {
+            Self::Sfs(sfs) => sfs.year.map(Riksmote::calendar_year),
+            Self::Proposition { riksmote, .. }
+            | Self::Betankande { riksmote, .. }
+            | Self::Motion { riksmote, .. } => Some(*riksmote),
+        }
+    }
+
+    /// An id that is safe to use in file names and XML attributes, e.g. "bet-2019-20-fiu11".
+    pub fn canonical_id(&self) -> String {
+        match self {
+            Self::Sfs(sfs) => {
+                let mut id = String::from("sfs");
+                if let Some(year) = sfs.year {
+                    id.push_str(&format!("-{year}"));
+                }
+                if sfs.bihang {
+                    id.push_str("-bih");
+                }
+                id.push_str(&format!("-{}", sfs.nummer));
+                if let Some(sida) = sfs.sida {
+                    id.push_str(&format!("-s{sida}"));
+                }
+                id
+            }
+            Self::Proposition { riksmote, nummer } => {
+                format!("prop-{}-{nummer}", riksmote_id(riksmote))
+            }
+            Self::Betankande {
+                riksmote,
+                utskott,
+                nummer,
+            } => format!(
+                "bet-{}-{}{nummer}",
+                riksmote_id(riksmote),
+                utskott.to_lowercase()
+            ),
+            Self::Motion {
+                riksmote,
+                utskott,
+                nummer,
+            } => format!(
+                "mot-{}-{}{nummer}",
+                riksmote_id(riksmote),
+                utskott.as_deref().unwrap_or_default().to_lowercase()
+            ),
+        }
+    }
+
+    /// A key that sorts beteckningar of the same kind by year and number when compared as strings,
+    /// e.g. "sfs-1976-0-000114-000".
+    pub fn sort_key(&self) -> String {
+        match self {
+            Self::Sfs(sfs) => format!(
+                "sfs-{:04}-{}-{:06}-{:03}",
+                sfs.year.unwrap_or_default(),
+                u8::from(sfs.bihang),
+                sfs.nummer,
+                sfs.sida.unwrap_or_default()
+            ),
+            Self::Proposition { riksmote, nummer } => {
+                format!("prop-{:04}-{nummer:06}", riksmote.start_year())
+            }
+            Self::Betankande {
+                riksmote,
+                utskott,
+                nummer,
+            } => format!(
+                "bet-{:04}-{}-{nummer:06}",
+                riksmote.start_year(),
+                utskott.to_lowercase()
+            ),
+            Self::Motion {
+                riksmote,
+                utskott,
+                nummer,
+            } => format!(
+                "mot-{:04}-{}-{nummer:06}",
+                riksmote.start_year(),
+                utskott.as_deref().unwrap_or_default().to_lowercase()
+            ),
+        }
+    }
+}
+
+fn riksmote_id(riksmote: &Riksmote) -> String {
+    riksmote.to_string().replace(['/', ':'], "-")
+}
+
+fn parse_digits<T: FromStr>(s: &str) -> Option<T> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parse "1976:114", "1880:48 s.1" or "bih. 29 s.1", using `year` if the number has none.
+fn parse_sfs(s: &str, year: Option<u16>) -> Option<SfsNummer> {
+    let s = s.trim();
+    let s = s.strip_prefix("SFS").map(str::trim_start).unwrap_or(s);
+    let (year, rest) = match s.split_once(':') {
+        Some((year, rest)) if year.len() == 4 => (Some(parse_digits(year)?), rest.trim_start()),
+        Some(_) => return None,
+        None => (year, s),
+    };
+    let (bihang, rest) = match rest.strip_prefix("bih.") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, rest),
+    };
+    let (nummer, sida) = match rest.split_once("s.") {
+        Some((nummer, sida)) => (nummer.trim_end(), Some(parse_digits(sida.trim())?)),
+        None => (rest, None),
+    };
+    if year.is_none() && !bihang {
+        return None;
+    }
+    Some(SfsNummer {
+        year,
+        bihang,
+        nummer: parse_digits(nummer)?,
+        sida,
+    })
+}
+
+/// Split off "Prop.", "Bet." or "Mot." (or the long forms) from `s`.
+fn parse_kind(s: &str) -> (Option<Kind>, &str) {
+    let Some((prefix, rest)) = s.split_once(char::is_whitespace) else {
+        return (None, s);
+    };
+    let kind = match prefix.trim_end_matches('.').to_lowercase().as_str() {
+        "prop" | "proposition" => Kind::Proposition,
+        "bet" | "betänkande" => Kind::Betankande,
+        "mot" | "motion" => Kind::Motion,
+        _ => return (None, s),
+    };
+    (Some(kind), rest.trim_start())
+}
+
+/// Committee reports are numbered per committee, whose abbreviations end with 'U' ("FiU", "UU").
+fn is_committee(utskott: &str) -> bool {
+    utskott.len() > 1 && utskott.ends_with('U')
+}
+
+fn from_kind(
+    kind: Option<Kind>,
+    riksmote: Riksmote,
+    beteckning: &str,
+) -> Result<Beteckning, BeteckningError> {
+    let invalid = || BeteckningError::InvalidFormat(beteckning.to_string());
+    let beteckning = beteckning.trim();
+    let digits_start = beteckning
+        .find(|c: char| c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (utskott, nummer) = beteckning.split_at(digits_start);
+    let nummer = parse_digits(nummer).ok_or_else(invalid)?;
+    let utskott = utskott.trim_end();
+    if !utskott.chars().all(char::is_alphabetic) {
+        return Err(invalid());
+    }
+    let utskott = (!utskott.is_empty()).then(|| utskott.to_string());
+    match (kind, utskott) {
+        (Some(Kind::Proposition), None) => Ok(Beteckning::Proposition { riksmote, nummer }),
+        (Some(Kind::Betankande), Some(utskott)) => Ok(Beteckning::Betankande {
+            riksmote,
+            utskott,
+            nummer,
+        }),
+        (None, Some(utskott)) if is_committee(&utskott) => Ok(Beteckning::Betankande {
+            riksmote,
+            utskott,
+            nummer,
+        }),
+        (Some(Kind::Motion) | None, Some(utskott)) => Ok(Beteckning::Motion {
+            riksmote,
+            utskott: Some(utskott),
+            nummer,
+        }),
+        (Some(Kind::Motion), None) => Ok(Beteckning::Motion {
+            riksmote,
+            utskott: None,
+            nummer,
+        }),
+        (None, None) => Err(BeteckningError::Ambiguous(beteckning.to_string())),
+        (Some(Kind::Proposition), Some(_)) | (Some(Kind::Betankande), None) => Err(invalid()),
+    }
+}
+
+impl FromStr for SfsNummer {
+    type Err = BeteckningError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_sfs(s, None).ok_or_else(|| BeteckningError::InvalidFormat(s.to_string()))
+    }
+}
+
+impl fmt::Display for SfsNummer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(year) = self.year {
+            write!(f, "{year}:")?;
+        }
+        if self.bihang {
+            f.write_str("bih. ")?;
+        }
+        write!(f, "{}", self.nummer)?;
+        if let Some(sida) = self.sida {
+            write!(f, " s.{sida}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Beteckning {
+    type Err = BeteckningError;
+
+    /// Parse a beteckning as it is cited.
+    ///
+    /// "YYYY:N" without a prefix is taken as an SFS number, use "Prop. 1970:100" for older propositions.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(sfs) = parse_sfs(s, None) {
+            return Ok(Self::Sfs(sfs));
+        }
+        let (kind, rest) = parse_kind(s.trim());
+        let (rm, beteckning) = rest
+            .split_once(':')
+            .ok_or_else(|| BeteckningError::InvalidFormat(s.to_string()))?;
+        from_kind(kind, rm.parse()?, beteckning).map_err(|error| match error {
+            BeteckningError::InvalidFormat(_) => BeteckningError::InvalidFormat(s.to_string()),
+            BeteckningError::Ambiguous(_) => BeteckningError::Ambiguous(s.to_string()),
+            error => error,
+        })
+    }
+}
+
+impl fmt::Display for Beteckning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sfs(sfs) => write!(f, "{sfs}"),
+            Self::Proposition { riksmote, nummer } => write!(f, "Prop. {riksmote}:{nummer}"),
+            Self::Betankande {
+                riksmote,
+                utskott,
+                nummer,
+            } => {
+                if !is_committee(utskott) {
+                    f.write_str("Bet. ")?;
+                }
+                write!(f, "{riksmote}:{utskott}{nummer}")
+            }
+            Self::Motion {
+                riksmote,
+                utskott,
+                nummer,
+            } => write!(
+                f,
+                "Mot. {riksmote}:{}{nummer}",
+                utskott.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use rstest::rstest;
+
+use super::*;
+
+fn rm(s: &str) -> Riksmote {
+    s.parse().unwrap()
+}
+
+fn sfs(year: Option<u16>, bihang: bool, nummer: u32, sida: Option<u32>) -> Beteckning {
+    Beteckning::Sfs(SfsNummer {
+        year,
+        bihang,
+        nummer,
+        sida,
+    })
+}
+
+#[rstest]
+#[case("1976:114", sfs(Some(1976), false, 114, None))]
+#[case("1880:48 s.1", sfs(Some(1880), false, 48, Some(1)))]
+#[case("bih. 29 s.1", sfs(None, true, 29, Some(1)))]
+#[case("1909:bih. 29 s.1", sfs(Some(1909), true, 29, Some(1)))]
+#[case("2019/20:FiU11", Beteckning::Betankande { riksmote: rm("2019/20"), utskott: "FiU".into(), nummer: 11 })]
+#[case("Prop. 2018/19:100", Beteckning::Proposition { riksmote: rm("2018/19"), nummer: 100 })]
+#[case("Mot. 2019/20:3256", Beteckning::Motion { riksmote: rm("2019/20"), utskott: None, nummer: 3256 })]
+#[case("Mot. 1998/99:Bo208", Beteckning::Motion { riksmote: rm("1998/99"), utskott: Some("Bo".into()), nummer: 208 })]
+#[case("Bet. 1999/2000:u1", Beteckning::Betankande { riksmote: rm("1999/2000"), utskott: "u".into(), nummer: 1 })]
+fn parse_beteckning(#[case] given: &str, #[case] expected: Beteckning) {
+    let beteckning: Beteckning = given.parse().unwrap();
+
+    assert_eq!(beteckning, expected);
+    assert_eq!(beteckning.to_string(), given);
+}
+
+#[rstest]
+#[case("SFS 1976:114", "1976:114")]
+#[case("proposition 2018/19:100", "Prop. 2018/19:100")]
+#[case("2001/02:Fi39", "Mot. 2001/02:Fi39")]
+#[case("bet. 2006/07:AU1", "2006/07:AU1")]
+fn parse_beteckning_normalizes(#[case] given: &str, #[case] expected: &str) {
+    let beteckning: Beteckning = given.parse().unwrap();
+
+    assert_eq!(beteckning.to_string(), expected);
+}
+
+#[rstest]
+#[case("", BeteckningError::InvalidFormat("".into()))]
+#[case("257", BeteckningError::InvalidFormat("257".into()))]
+#[case("2019/20:", BeteckningError::InvalidFormat("2019/20:".into()))]
+#[case("Prop. 2018/19:FiU1", BeteckningError::InvalidFormat("Prop. 2018/19:FiU1".into()))]
+#[case("2010/11:-S68106", BeteckningError::InvalidFormat("2010/11:-S68106".into()))]
+#[case("2019/20:3256", BeteckningError::Ambiguous("2019/20:3256".into()))]
+#[case("2019/21:AU1", BeteckningError::Riksmote(RiksmoteError::YearsNotConsecutive("2019/21".into())))]
+fn parse_beteckning_fails(#[case] given: &str, #[case] expected: BeteckningError) {
+    assert_eq!(given.parse::<Beteckning>(), Err(expected));
+}
+
+#[rstest]
+#[case("sfs", "1976", "1976:257", "sfs-1976-257", "sfs-1976-0-000257-000")]
+#[case(
+    "sfs",
+    "1909",
+    "bih. 29 s.1",
+    "sfs-1909-bih-29-s1",
+    "sfs-1909-1-000029-001"
+)]
+#[case("bet", "2010/11", "AU1", "bet-2010-11-au1", "bet-2010-au-000001")]
+#[case("bet", "2010/11", "FöU11", "bet-2010-11-föu11", "bet-2010-föu-000011")]
+#[case("prop", "1980/81", "10", "prop-1980-81-10", "prop-1980-000010")]
+#[case("mot", "1979/80", "1833", "mot-1979-80-1833", "mot-1979--001833")]
+#[case(
+    "mot",
+    "1999/2000",
+    "Kr419",
+    "mot-1999-2000-kr419",
+    "mot-1999-kr-000419"
+)]
+fn from_parts_gives_canonical_id_and_sort_key(
+    #[case] typ: &str,
+    #[case] rm: &str,
+    #[case] beteckning: &str,
+    #[case] canonical_id: &str,
+    #[case] sort_key: &str,
+) {
+    let beteckning = Beteckning::from_parts(typ, rm, beteckning).unwrap();
+
+    assert_eq!(beteckning.canonical_id(), canonical_id);
+    assert_eq!(beteckning.sort_key(), sort_key);
+}
+
+#[test]
+fn from_parts_fails_on_unsupported_typ() {
+    assert_eq!(
+        Beteckning::from_parts("fr", "1997/98", "103"),
+        Err(BeteckningError::UnsupportedTyp("fr".into()))
+    );
+}
+
+#[test]
+fn sort_key_orders_by_number() {
+    let mut beteckningar: Vec<Beteckning> = ["1976:1000", "1976:114", "1880:48 s.2", "1880:48 s.1"]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    beteckningar.sort_by_key(Beteckning::sort_key);
+
+    assert_eq!(
+        beteckningar
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["1880:48 s.1", "1880:48 s.2", "1976:114", "1976:1000"]
+    );
+}
+
+#[test]
+fn beteckning_serde_roundtrip() -> anyhow::Result<()> {
+    let beteckning: Beteckning = "2019/20:FiU11".parse()?;
+
+    let json = serde_json::to_string(&beteckning)?;
+
+    assert_eq!(json, r#""2019/20:FiU11""#);
+    assert_eq!(serde_json::from_str::<Beteckning>(&json)?, beteckning);
+    Ok(())
+}
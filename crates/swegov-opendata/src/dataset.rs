@@ -1,4 +1,10 @@
 use crate::date_formats::SweDateTime;
+use crate::extra::Extra;
+use crate::riksmote::{Riksmote, RiksmoteRange};
+
+mod query;
+
+pub use query::DatasetQuery;
 
 #[cfg(test)]
 mod tests;
@@ -28,7 +34,7 @@ pub struct DatasetLista {
     yaserde::YaSerialize,
     yaserde::YaDeserialize,
 )]
-#[serde(deny_unknown_fields, rename = "dataset")]
+#[serde(rename = "dataset")]
 #[yaserde(rename = "dataset")]
 pub struct DataSet {
     pub namn: String,
@@ -50,6 +56,29 @@ pub struct DataSet {
     pub beskrivning: Option<String>,
     // pub upplysning: String,
     pub upplysning: Option<Upplysning>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+impl DataSet {
+    /// The riksmöten covered by this dataset.
+    ///
+    /// `rm` is either a riksmöte ("2019/20") or a span of years ("2018-2021", "2020-"),
+    /// `None` is returned for anything else.
+    pub fn riksmote_range(&self) -> Option<RiksmoteRange> {
+        let rm = self.rm.trim();
+        match rm.split_once('-') {
+            Some((from, to)) => {
+                let from = from.trim().parse().ok().map(Riksmote::starting_in)?;
+                let to = match to.trim() {
+                    "" => None,
+                    to => Some(to.parse().ok().map(Riksmote::starting_in)?),
+                };
+                Some(RiksmoteRange::new(Some(from), to))
+            }
+            None => rm.parse::<Riksmote>().ok().map(RiksmoteRange::from),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -73,68 +102,112 @@ pub struct YearCommentMap {
     year_comments: Vec<YearComment>,
 }
 
-impl yaserde::YaDeserialize for Upplysning {
-    fn deserialize<R: std::io::Read>(
+/// Errors from reading an [`Upplysning`] from XML.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum UpplysningError {
+    #[error("Wrong StartElement name: '{found}', expected: 'upplysning'")]
+    WrongElement { found: String },
+    #[error("StartElement missing, found {found}")]
+    StartElementMissing { found: String },
+    #[error("Unexpected end of document inside 'upplysning'")]
+    UnexpectedEndDocument,
+    #[error("Error reading XML: {0}")]
+    Xml(String),
+}
+
+impl Upplysning {
+    const ELEMENT_NAME: &'static str = "upplysning";
+
+    /// Read an `upplysning` element, leaving its `EndElement` to the caller.
+    ///
+    /// The text before the first `<br>` is the upplysning and the text after every `<br>` is
+    /// a `year: comment` pair. Other elements, e.g. links or `<b>`, only contribute their text.
+    pub fn read_xml<R: std::io::Read>(
         reader: &mut yaserde::de::Deserializer<R>,
-    ) -> Result<Self, String> {
-        let expected_name = "upplysning";
-        if let xml::reader::XmlEvent::StartElement { name, .. } = reader.peek()? {
-            if name.local_name != expected_name {
-                return Err(format!(
-                    "Wrong StartElement name: '{}', expected: '{}'",
-                    name, expected_name
-                ));
+    ) -> Result<Self, UpplysningError> {
+        use xml::reader::XmlEvent;
+
+        match reader.peek().map_err(UpplysningError::Xml)? {
+            XmlEvent::StartElement { name, .. } if name.local_name == Self::ELEMENT_NAME => {}
+            XmlEvent::StartElement { name, .. } => {
+                return Err(UpplysningError::WrongElement {
+                    found: name.to_string(),
+                })
+            }
+            event => {
+                return Err(UpplysningError::StartElementMissing {
+                    found: format!("{event:?}"),
+                })
             }
-            let _next = reader.next_event();
-        } else {
-            return Err("StartElement missing".to_string());
         }
+        reader.next_event().map_err(UpplysningError::Xml)?;
 
         let mut upplysning = String::default();
         let mut year_comment = YearCommentMap::default();
-        let mut found_br = false;
+        // The text after the latest `<br>`, `None` before the first one
+        let mut segment: Option<String> = None;
+        let mut depth = 0usize;
         loop {
-            match reader.peek()? {
-                xml::reader::XmlEvent::StartElement { name, .. } => {
-                    match name.local_name.as_ref() {
-                        "br" => {
-                            found_br = true;
+            match reader.peek().map_err(UpplysningError::Xml)? {
+                XmlEvent::EndElement { .. } if depth == 0 => break,
+                XmlEvent::EndDocument => return Err(UpplysningError::UnexpectedEndDocument),
+                _ => {}
+            }
+            match reader.next_event().map_err(UpplysningError::Xml)? {
+                XmlEvent::StartElement { name, .. } => {
+                    depth += 1;
+                    if name.local_name == "br" {
+                        if let Some(text) = segment.replace(String::new()) {
+                            year_comment.push_segment(&text);
                         }
-                        _ => todo!(),
                     }
-                    let _res = reader.next_event()?;
                 }
-                xml::reader::XmlEvent::Characters(raw_text) => {
-                    if found_br {
-                        let mut parts = raw_text.split(':');
-                        let year = parts.next().unwrap().to_string();
-                        let comment = parts.next().unwrap().trim_start().to_string();
-                        year_comment
-                            .year_comments
-                            .push(YearComment { year, comment });
-                    } else {
-                        upplysning = raw_text.to_string();
-                    }
-                    let _res = reader.next_event()?;
-                }
-                xml::reader::XmlEvent::EndElement { name } => {
-                    if name.local_name == expected_name {
-                        break;
-                    } else if name.local_name == "br" {
-                        let _next = reader.next_event()?;
-                        continue;
-                    } else {
-                        todo!("handle end='{}'", name)
-                    }
+                XmlEvent::EndElement { .. } => depth -= 1,
+                XmlEvent::Characters(text) | XmlEvent::Whitespace(text) | XmlEvent::CData(text) => {
+                    segment.as_mut().unwrap_or(&mut upplysning).push_str(&text);
                 }
-                e => todo!("handle event={:?}", e),
+                _ => {}
             }
         }
+        if let Some(text) = segment {
+            year_comment.push_segment(&text);
+        }
         Ok(Self {
             upplysning,
             year_comment,
         })
     }
+
+    pub fn year_comments(&self) -> &[YearComment] {
+        &self.year_comment.year_comments
+    }
+}
+
+impl YearCommentMap {
+    /// Add the text between two `<br>`.
+    ///
+    /// Text without a `:` is kept as a comment without a year, empty text is skipped.
+    fn push_segment(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let (year, comment) = match text.split_once(':') {
+            Some((year, comment)) => (year, comment.trim_start()),
+            None => ("", text),
+        };
+        self.year_comments.push(YearComment {
+            year: year.to_string(),
+            comment: comment.to_string(),
+        });
+    }
+}
+
+impl yaserde::YaDeserialize for Upplysning {
+    fn deserialize<R: std::io::Read>(
+        reader: &mut yaserde::de::Deserializer<R>,
+    ) -> Result<Self, String> {
+        Self::read_xml(reader).map_err(|error| error.to_string())
+    }
 }
 
 impl yaserde::YaSerialize for Upplysning {
@@ -157,10 +230,7 @@ impl yaserde::YaSerialize for Upplysning {
                 .write(xml::writer::XmlEvent::end_element())
                 .map_err(|e| e.to_string())?;
             writer
-                .write(xml::writer::XmlEvent::characters(&format!(
-                    "{}: {}",
-                    year_comment.year, year_comment.comment
-                )))
+                .write(xml::writer::XmlEvent::characters(&year_comment.to_string()))
                 .map_err(|e| e.to_string())?;
         }
         writer
@@ -190,6 +260,16 @@ pub struct YearComment {
     pub comment: String,
 }
 
+impl std::fmt::Display for YearComment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.year.is_empty() {
+            f.write_str(&self.comment)
+        } else {
+            write!(f, "{}: {}", self.year, self.comment)
+        }
+    }
+}
+
 #[derive(
     Default,
     Debug,
@@ -219,6 +299,18 @@ pub enum DataFormat {
     Xml,
 }
 
+impl std::str::FromStr for DataFormat {
+    type Err = UnknownDataFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_opt(s).ok_or_else(|| UnknownDataFormat(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown format '{0}', expected one of csv, csvt, html, json, sql, text or xml")]
+pub struct UnknownDataFormat(pub String);
+
 impl DataFormat {
     pub fn as_str(&self) -> &'static str {
         match self {
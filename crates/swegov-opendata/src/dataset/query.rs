@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+
+use crate::riksmote::RiksmoteRange;
+
+use super::{DataFormat, DataSet, DatasetLista};
+
+#[cfg(test)]
+mod tests;
+
+/// Select datasets from a [`DatasetLista`].
+///
+/// Every criterion that is set must match, an empty list or `None` matches everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DatasetQuery {
+    /// Only datasets with one of these `typ`, e.g. "prot" or "bet".
+    pub typ: Vec<String>,
+    /// Only datasets in one of these `samling`, e.g. "prot-2018-2021".
+    pub samling: Vec<String>,
+    /// Only datasets that cover any of these riksmöten.
+    pub riksmote_range: RiksmoteRange,
+    /// Only datasets in one of these formats.
+    pub format: Vec<DataFormat>,
+    /// Only datasets updated on or after this date.
+    pub uppdaterad_since: Option<NaiveDate>,
+    /// Only datasets of at least this size in bytes.
+    pub min_storlek_bytes: Option<u64>,
+    /// Only datasets of at most this size in bytes.
+    pub max_storlek_bytes: Option<u64>,
+}
+
+impl DatasetQuery {
+    pub fn matches(&self, dataset: &DataSet) -> bool {
+        (self.typ.is_empty() || self.typ.contains(&dataset.typ))
+            && (self.samling.is_empty() || self.samling.contains(&dataset.samling))
+            && (self.format.is_empty() || self.format.contains(&dataset.format))
+            && self
+                .uppdaterad_since
+                .is_none_or(|since| dataset.uppdaterad.date() >= since)
+            && self
+                .min_storlek_bytes
+                .is_none_or(|min| dataset.storlek_bytes >= min)
+            && self
+                .max_storlek_bytes
+                .is_none_or(|max| dataset.storlek_bytes <= max)
+            && self.in_riksmote_range(dataset)
+    }
+
+    /// Check if `dataset` covers any riksmöte in the requested range.
+    fn in_riksmote_range(&self, dataset: &DataSet) -> bool {
+        if self.riksmote_range.is_unbounded() {
+            return true;
+        }
+        match dataset.riksmote_range() {
+            Some(range) => self.riksmote_range.overlaps(&range),
+            None => {
+                tracing::debug!(rm = dataset.rm, "skipping dataset with unknown rm");
+                false
+            }
+        }
+    }
+}
+
+impl DatasetLista {
+    /// The datasets that match `query`.
+    pub fn select<'a>(&'a self, query: &'a DatasetQuery) -> impl Iterator<Item = &'a DataSet> {
+        self.dataset
+            .iter()
+            .filter(move |dataset| query.matches(dataset))
+    }
+}
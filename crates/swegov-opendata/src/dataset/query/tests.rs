@@ -0,0 +1,109 @@
+use chrono::NaiveDate;
+use rstest::rstest;
+
+use crate::{date_formats::SweDateTime, extra::Extra, FilFormat};
+
+use super::*;
+use crate::dataset::UnknownDataFormat;
+
+fn dataset(samling: &str, rm: &str, format: DataFormat, uppdaterad: (i32, u32, u32)) -> DataSet {
+    let (year, month, day) = uppdaterad;
+    let typ = samling.split('-').next().unwrap();
+    DataSet {
+        namn: "dokument".into(),
+        typ: typ.into(),
+        samling: samling.into(),
+        rm: rm.into(),
+        filnamn: format!("{samling}.{}.zip", format.as_str()),
+        storlek_bytes: 1000 * u64::from(day),
+        url: format!("/dataset/dokument/{samling}.{}.zip", format.as_str()),
+        format,
+        filformat: FilFormat::Zip,
+        uppdaterad: SweDateTime::from(
+            NaiveDate::from_ymd_opt(year, month, day)
+                .unwrap()
+                .and_hms_opt(2, 0, 0)
+                .unwrap(),
+        ),
+        description: None,
+        beskrivning: None,
+        upplysning: None,
+        extra: Extra::default(),
+    }
+}
+
+fn datasetlista() -> DatasetLista {
+    DatasetLista {
+        dataset: vec![
+            dataset(
+                "prot-2018-2021",
+                "2018-2021",
+                DataFormat::Json,
+                (2024, 3, 2),
+            ),
+            dataset("prot-2018-2021", "2018-2021", DataFormat::Xml, (2024, 3, 2)),
+            dataset("prot-2022-", "2022-", DataFormat::Xml, (2024, 9, 26)),
+            dataset("bet-1971-1979", "1971-1979", DataFormat::Xml, (2015, 4, 27)),
+            dataset("anforande-202324", "2023/24", DataFormat::Csv, (2024, 9, 1)),
+            dataset("votering-okand", "okänt", DataFormat::Json, (2024, 1, 1)),
+        ],
+    }
+}
+
+fn samlingar(query: &DatasetQuery) -> Vec<String> {
+    datasetlista()
+        .select(query)
+        .map(|dataset| format!("{}.{}", dataset.samling, dataset.format.as_str()))
+        .collect()
+}
+
+#[test]
+fn default_query_selects_everything() {
+    assert_eq!(samlingar(&DatasetQuery::default()).len(), 6);
+}
+
+#[rstest]
+#[case::typ(
+    DatasetQuery { typ: vec!["prot".into()], ..Default::default() },
+    &["prot-2018-2021.json", "prot-2018-2021.xml", "prot-2022-.xml"]
+)]
+#[case::samling(
+    DatasetQuery { samling: vec!["bet-1971-1979".into(), "prot-2022-".into()], ..Default::default() },
+    &["prot-2022-.xml", "bet-1971-1979.xml"]
+)]
+#[case::format(
+    DatasetQuery { format: vec![DataFormat::Json], ..Default::default() },
+    &["prot-2018-2021.json", "votering-okand.json"]
+)]
+#[case::riksmote_range(
+    DatasetQuery { riksmote_range: "2021/22..2023/24".parse().unwrap(), ..Default::default() },
+    &["prot-2018-2021.json", "prot-2018-2021.xml", "prot-2022-.xml", "anforande-202324.csv"]
+)]
+#[case::uppdaterad_since(
+    DatasetQuery { uppdaterad_since: NaiveDate::from_ymd_opt(2024, 9, 1), ..Default::default() },
+    &["prot-2022-.xml", "anforande-202324.csv"]
+)]
+#[case::storlek(
+    DatasetQuery { min_storlek_bytes: Some(2000), max_storlek_bytes: Some(26000), ..Default::default() },
+    &["prot-2018-2021.json", "prot-2018-2021.xml", "prot-2022-.xml"]
+)]
+#[case::combined(
+    DatasetQuery {
+        typ: vec!["prot".into()],
+        format: vec![DataFormat::Xml],
+        uppdaterad_since: NaiveDate::from_ymd_opt(2024, 1, 1),
+        ..Default::default()
+    },
+    &["prot-2018-2021.xml", "prot-2022-.xml"]
+)]
+fn select_datasets(#[case] query: DatasetQuery, #[case] expected: &[&str]) {
+    assert_eq!(samlingar(&query), expected);
+}
+
+#[rstest]
+#[case("json", Ok(DataFormat::Json))]
+#[case("csvt", Ok(DataFormat::CsvT))]
+#[case("JSON", Err(UnknownDataFormat("JSON".into())))]
+fn parse_data_format(#[case] given: &str, #[case] expected: Result<DataFormat, UnknownDataFormat>) {
+    assert_eq!(given.parse::<DataFormat>(), expected);
+}
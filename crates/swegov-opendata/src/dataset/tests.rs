@@ -1,4 +1,6 @@
 use chrono::NaiveDate;
+use proptest::prelude::*;
+use rstest::rstest;
 use xml::{EventReader, ParserConfig};
 use yaserde::YaDeserialize;
 
@@ -26,6 +28,7 @@ fn dataset_anforande_202324() -> DataSet {
         description: Some("Anföranden som ledamöter hållit i kammaren.".into()),
         beskrivning: Some("Anföranden för riksmöte 2023/24".into()),
         upplysning: upplysning_anforande_202334().into(),
+        extra: Extra::default(),
     }
 }
 fn dataset_anforande_202223() -> DataSet {
@@ -48,6 +51,7 @@ fn dataset_anforande_202223() -> DataSet {
         description: Some("Anföranden som ledamöter hållit i kammaren.".into()),
         beskrivning: None,
         upplysning: None,
+        extra: Extra::default(),
     }
 }
 fn dataset_bet_1971_1979() -> DataSet {
@@ -70,7 +74,8 @@ fn dataset_bet_1971_1979() -> DataSet {
         Some("Utskottens betänkanden och utlåtanden, inklusive rksdagens beslut, en sammanfattning av voteringsresultaten och Beslut i korthet. I vissa årgångar finns även debatten med i formaten JSON, SQL och XML.".into())
         ,
         beskrivning: None,
-        upplysning: Some(upplysning_bet_1971_1979())
+        upplysning: Some(upplysning_bet_1971_1979()),
+        extra: Extra::default(),
 
     }
 }
@@ -134,6 +139,158 @@ fn upplysning_ser_de_xml() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn read_upplysning(source: &str) -> Result<Upplysning, UpplysningError> {
+    let mut xml_reader = yaserde::de::Deserializer::new(EventReader::new_with_config(
+        source.as_bytes(),
+        ParserConfig::new().trim_whitespace(false),
+    ));
+    Upplysning::read_xml(&mut xml_reader)
+}
+
+fn year_comment(year: &str, comment: &str) -> YearComment {
+    YearComment {
+        year: year.into(),
+        comment: comment.into(),
+    }
+}
+
+#[rstest]
+#[case::empty("<upplysning />", "", vec![])]
+#[case::whitespace("<upplysning> </upplysning>", "", vec![])]
+#[case::link(
+    r#"<upplysning>Se <a href="https://data.riksdagen.se">data.riksdagen.se</a>.</upplysning>"#,
+    "Se data.riksdagen.se.",
+    vec![]
+)]
+#[case::bold_year(
+    "<upplysning>Saknade dokument: <br/><b>1975/76</b>: alla</upplysning>",
+    "Saknade dokument: ",
+    vec![year_comment("1975/76", "alla")]
+)]
+#[case::br_inside_markup(
+    "<upplysning><p>Saknas<br/>1976/77: FiU, <i>NU</i></p></upplysning>",
+    "Saknas",
+    vec![year_comment("1976/77", "FiU, NU")]
+)]
+#[case::colon_in_comment(
+    "<upplysning><br/>1990/91: se kl. 12:00</upplysning>",
+    "",
+    vec![year_comment("1990/91", "se kl. 12:00")]
+)]
+#[case::without_year(
+    "<upplysning>A<br/>B<br/><br/>2001/02: C<br/></upplysning>",
+    "A",
+    vec![year_comment("", "B"), year_comment("2001/02", "C")]
+)]
+#[case::cdata_and_comment(
+    "<upplysning><![CDATA[x < y]]><!-- ignored --></upplysning>",
+    "x < y",
+    vec![]
+)]
+fn upplysning_de_xml_with_markup(
+    #[case] source: &str,
+    #[case] expected_upplysning: &str,
+    #[case] expected_year_comments: Vec<YearComment>,
+) {
+    let actual = read_upplysning(source).unwrap();
+
+    similar_asserts::assert_eq!(actual.upplysning(), expected_upplysning);
+    similar_asserts::assert_eq!(actual.year_comments(), expected_year_comments.as_slice());
+}
+
+#[test]
+fn upplysning_de_xml_fails_on_wrong_element() {
+    similar_asserts::assert_eq!(
+        read_upplysning("<beskrivning />").unwrap_err(),
+        UpplysningError::WrongElement {
+            found: "beskrivning".into()
+        }
+    );
+}
+
+#[rstest]
+#[case::truncated("<upplysning>text<br/>")]
+#[case::mismatched("<upplysning><b>text</i></upplysning>")]
+fn upplysning_de_xml_fails_on_malformed_xml(#[case] source: &str) {
+    assert!(matches!(
+        read_upplysning(source),
+        Err(UpplysningError::Xml(_))
+    ));
+}
+
+#[test]
+fn upplysning_de_xml_inside_dataset_list_keeps_reading() -> anyhow::Result<()> {
+    let source = "<datasetlista><upplysning>Se <b>1975/76</b></upplysning><rest/></datasetlista>";
+    let mut xml_reader = yaserde::de::Deserializer::new(EventReader::new_with_config(
+        source.as_bytes(),
+        ParserConfig::new().trim_whitespace(false),
+    ));
+    // <datasetlista>
+    xml_reader.next_event().map_err(anyhow::Error::msg)?;
+
+    let actual = Upplysning::read_xml(&mut xml_reader)?;
+
+    similar_asserts::assert_eq!(actual.upplysning(), "Se 1975/76");
+    assert!(matches!(
+        xml_reader.next_event().map_err(anyhow::Error::msg)?,
+        xml::reader::XmlEvent::EndElement { name } if name.local_name == "upplysning"
+    ));
+    Ok(())
+}
+
+/// Upplysningar the serializer writes unambiguously: years without `:`, comments that don't
+/// start with whitespace and no blank texts, since whitespace-only text is skipped.
+fn upplysning_strategy() -> impl Strategy<Value = Upplysning> {
+    let year_comment = prop_oneof![
+        ("[^:\\p{C}]{1,10}", "([^\\s\\p{C}][^\\p{C}]{0,20})?")
+            .prop_map(|(year, comment)| YearComment { year, comment }),
+        "[^:\\s\\p{C}][^:\\p{C}]{0,19}".prop_map(|comment| YearComment {
+            year: String::new(),
+            comment
+        }),
+    ];
+    (
+        "([^\\s\\p{C}][^\\p{C}]{0,39})?",
+        prop::collection::vec(year_comment, 0..4),
+    )
+        .prop_map(|(upplysning, year_comments)| Upplysning {
+            upplysning,
+            year_comment: YearCommentMap { year_comments },
+        })
+}
+
+/// Fragments of XML that may or may not be well-formed.
+fn xml_fragment() -> impl Strategy<Value = String> {
+    let part = prop_oneof![
+        Just("<br/>".to_string()),
+        Just("<br>".to_string()),
+        Just("</br>".to_string()),
+        Just("<b>".to_string()),
+        Just("</b>".to_string()),
+        Just(r#"<a href="x">"#.to_string()),
+        Just("</a>".to_string()),
+        Just("</upplysning>".to_string()),
+        Just("<![CDATA[:]]>".to_string()),
+        Just("&amp;".to_string()),
+        "[^<&]{0,10}",
+    ];
+    prop::collection::vec(part, 0..12).prop_map(|parts| format!("<upplysning>{}", parts.concat()))
+}
+
+proptest! {
+    #[test]
+    fn upplysning_roundtrips_through_xml(value in upplysning_strategy()) {
+        let buffer = yaserde::ser::to_string(&value).unwrap();
+
+        prop_assert_eq!(read_upplysning(&buffer).unwrap(), value, "xml = {}", buffer);
+    }
+
+    #[test]
+    fn upplysning_de_xml_never_panics(source in xml_fragment()) {
+        let _ = read_upplysning(&source);
+    }
+}
+
 #[test]
 fn datalista_de_ser_xml() -> anyhow::Result<()> {
     let value = DatasetLista {
@@ -11,16 +11,22 @@ mod referens;
 mod shared;
 mod uppgift;
 
-pub use crate::dokument::aktivitet::Aktivitet;
-pub use crate::dokument::bilaga::Bilaga;
+pub use crate::dokument::aktivitet::{Aktivitet, AktivitetRef};
+pub use crate::dokument::bilaga::{Bilaga, BilagaRef};
+pub use crate::dokument::debatt::{Debatt, DebattAnforande, DebattAnforandeRef, DebattRef};
 pub use crate::dokument::dokument::{
-    DokAktivitet, DokBilaga, DokForslag, DokIntressent, DokIntressentRef, DokUppgift, Dokument,
+    DokAktivitet, DokAktivitetRef, DokBilaga, DokBilagaRef, DokForslag, DokForslagRef,
+    DokIntressent, DokIntressentRef, DokMotForslag, DokMotForslagRef, DokReferens, DokReferensRef,
+    DokUppgift, DokUppgiftRef, DokUtskottsForslag, DokUtskottsForslagRef, Dokument, DokumentRef,
     DokumentStatus, DokumentStatusPage, DokumentStatusPageRef, DokumentStatusRef,
 };
 pub use crate::dokument::dokument_lista::{
     DokumentLista, DokumentListaDokument, DokumentListaPage, Fil, FilBilaga, SokData,
 };
-pub use crate::dokument::forslag::Forslag;
+pub use crate::dokument::forslag::{
+    AnyValue, Forslag, ForslagRef, MotForslag, MotForslagRef, UtskottsForslag, UtskottsForslagRef,
+};
 pub use crate::dokument::intressent::{Intressent, IntressentRef};
-pub use crate::dokument::referens::Referens;
-pub use crate::dokument::uppgift::Uppgift;
+pub use crate::dokument::media::{Media, MediaRef, WebbMedia, WebbMediaRef};
+pub use crate::dokument::referens::{Referens, ReferensRef};
+pub use crate::dokument::uppgift::{Uppgift, UppgiftRef};
@@ -1,19 +1,18 @@
-use chrono::NaiveDateTime;
-
-use crate::date_formats::{self, SweDateTime};
+use crate::date_formats::SweDateTime;
+use crate::extra::Extra;
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DokAktivitet {
     pub aktivitet: Vec<Aktivitet>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Aktivitet {
     // #[serde(deserialize_with = "deserialize_number_from_string")]
     pub hangar_id: Option<String>,
@@ -24,24 +23,49 @@ pub struct Aktivitet {
     pub status: Option<String>,
     pub ordning: String,
     pub process: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DokAktivitetRef<'a> {
     pub aktivitet: Vec<AktivitetRef<'a>>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct AktivitetRef<'a> {
     // #[serde(deserialize_with = "deserialize_number_from_string")]
     pub hangar_id: Option<&'a str>,
     pub kod: &'a str,
     pub namn: &'a str,
-    #[serde(with = "date_formats::swe_date_format")]
-    pub datum: NaiveDateTime,
+    pub datum: SweDateTime,
     pub status: Option<&'a str>,
     pub ordning: &'a str,
     pub process: Option<&'a str>,
 }
+
+impl DokAktivitetRef<'_> {
+    pub fn to_owned(&self) -> DokAktivitet {
+        DokAktivitet {
+            aktivitet: self.aktivitet.iter().map(AktivitetRef::to_owned).collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl AktivitetRef<'_> {
+    pub fn to_owned(&self) -> Aktivitet {
+        Aktivitet {
+            hangar_id: self.hangar_id.map(ToString::to_string),
+            kod: self.kod.to_string(),
+            namn: self.namn.to_string(),
+            datum: self.datum,
+            status: self.status.map(ToString::to_string),
+            ordning: self.ordning.to_string(),
+            process: self.process.map(ToString::to_string),
+            extra: Extra::default(),
+        }
+    }
+}
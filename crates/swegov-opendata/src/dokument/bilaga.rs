@@ -3,22 +3,23 @@ use std::borrow::Cow;
 use serde_with::serde_as;
 use serde_with::{formats::PreferMany, OneOrMany};
 
-use crate::shared::optionals;
+use crate::extra::Extra;
+use crate::shared::{cow, optionals};
 
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DokBilaga {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub bilaga: Vec<Bilaga>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Bilaga {
     pub dok_id: String,
     pub fil_url: String,
@@ -28,25 +29,52 @@ pub struct Bilaga {
     pub subtitel: Option<String>,
     #[serde(deserialize_with = "optionals::deserialize_null_default")]
     pub titel: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DokBilagaRef<'a> {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub bilaga: Vec<BilagaRef<'a>>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct BilagaRef<'a> {
     pub dok_id: &'a str,
     pub fil_url: &'a str,
     pub filnamn: &'a str,
     pub filstorlek: &'a str,
     pub filtyp: &'a str,
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
     pub subtitel: Option<Cow<'a, str>>,
-    #[serde(deserialize_with = "optionals::deserialize_null_default", borrow)]
+    #[serde(borrow, deserialize_with = "cow::deserialize_null_default")]
     pub titel: Cow<'a, str>,
 }
+
+impl DokBilagaRef<'_> {
+    pub fn to_owned(&self) -> DokBilaga {
+        DokBilaga {
+            bilaga: self.bilaga.iter().map(BilagaRef::to_owned).collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl BilagaRef<'_> {
+    pub fn to_owned(&self) -> Bilaga {
+        Bilaga {
+            dok_id: self.dok_id.to_string(),
+            fil_url: self.fil_url.to_string(),
+            filnamn: self.filnamn.to_string(),
+            filstorlek: self.filstorlek.to_string(),
+            filtyp: self.filtyp.to_string(),
+            subtitel: self.subtitel.as_deref().map(ToString::to_string),
+            titel: self.titel.to_string(),
+            extra: Extra::default(),
+        }
+    }
+}
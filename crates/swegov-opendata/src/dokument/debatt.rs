@@ -1,31 +1,32 @@
 use std::borrow::Cow;
 
-use chrono::NaiveDateTime;
 use serde_aux::field_attributes::{
     deserialize_number_from_string, deserialize_option_number_from_string,
 };
 use serde_with::serde_as;
 use serde_with::{formats::PreferMany, OneOrMany};
 
-use crate::date_formats::{self, SweDateTime};
+use crate::date_formats::SweDateTime;
+use crate::extra::Extra;
 use crate::one_or_many::{
     string_or_seq_or_none_to_opt_cow_str, string_or_seq_or_none_to_opt_string,
 };
+use crate::shared::cow;
 
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Debatt {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub anforande: Vec<DebattAnforande>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DebattAnforande {
     pub anf_beteckning: String,
     // #[serde(with = "date_formats::swe_date_format")]
@@ -79,22 +80,23 @@ pub struct DebattAnforande {
     pub videostatus: Option<u64>,
     // pub videostatus: Option<String>,
     pub voteringspunkt: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DebattRef<'a> {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub anforande: Vec<DebattAnforandeRef<'a>>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DebattAnforandeRef<'a> {
     pub anf_beteckning: &'a str,
-    #[serde(with = "date_formats::swe_date_format")]
-    pub anf_datum: NaiveDateTime,
+    pub anf_datum: SweDateTime,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub anf_hangar_id: u64,
     pub anf_id: Option<&'a str>,
@@ -104,16 +106,16 @@ pub struct DebattAnforandeRef<'a> {
     pub anf_rm: &'a str,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub anf_sekunder: u64,
-    #[serde(borrow)]
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
     pub anf_text: Option<Cow<'a, str>>,
     pub anf_typ: &'a str,
     pub anf_video_id: &'a str,
     // pub anf_video_id: Uuid,
-    #[serde(with = "date_formats::swe_date_format")]
-    pub datumtid: NaiveDateTime,
+    pub datumtid: SweDateTime,
     pub debatt_id: Option<&'a str>,
     // pub debatt_id: Uuid,
-    pub debatt_titel: Option<&'a str>,
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
+    pub debatt_titel: Option<Cow<'a, str>>,
     pub debatt_typ: Option<&'a str>,
     pub dok_beteckning: Option<&'a str>,
     pub dok_id: Option<&'a str>,
@@ -126,12 +128,13 @@ pub struct DebattAnforandeRef<'a> {
     pub parti: &'a str,
     #[serde(deserialize_with = "deserialize_option_number_from_string", default)]
     pub startpos: Option<u64>,
-    #[serde(with = "date_formats::option_swe_date_format", default)]
-    pub systemdatum: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub systemdatum: Option<SweDateTime>,
     pub talare: &'a str,
     pub talare_kort: Option<&'a str>,
     #[serde(
         skip_serializing_if = "Option::is_none",
+        borrow,
         deserialize_with = "string_or_seq_or_none_to_opt_cow_str",
         default
     )]
@@ -146,3 +149,56 @@ pub struct DebattAnforandeRef<'a> {
     // pub videostatus: Option<&'a str>,
     pub voteringspunkt: Option<&'a str>,
 }
+
+impl DebattRef<'_> {
+    pub fn to_owned(&self) -> Debatt {
+        Debatt {
+            anforande: self
+                .anforande
+                .iter()
+                .map(DebattAnforandeRef::to_owned)
+                .collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl DebattAnforandeRef<'_> {
+    pub fn to_owned(&self) -> DebattAnforande {
+        DebattAnforande {
+            anf_beteckning: self.anf_beteckning.to_string(),
+            anf_datum: self.anf_datum,
+            anf_hangar_id: self.anf_hangar_id,
+            anf_id: self.anf_id.map(ToString::to_string),
+            anf_klockslag: self.anf_klockslag.to_string(),
+            anf_nummer: self.anf_nummer.to_string(),
+            anf_rm: self.anf_rm.to_string(),
+            anf_sekunder: self.anf_sekunder,
+            anf_text: self.anf_text.as_deref().map(ToString::to_string),
+            anf_typ: self.anf_typ.to_string(),
+            anf_video_id: self.anf_video_id.to_string(),
+            datumtid: self.datumtid,
+            debatt_id: self.debatt_id.map(ToString::to_string),
+            debatt_titel: self.debatt_titel.as_deref().map(ToString::to_string),
+            debatt_typ: self.debatt_typ.map(ToString::to_string),
+            dok_beteckning: self.dok_beteckning.map(ToString::to_string),
+            dok_id: self.dok_id.map(ToString::to_string),
+            dok_intressent: self.dok_intressent.map(ToString::to_string),
+            intressent_id: self.intressent_id.map(ToString::to_string),
+            kon: self.kon.map(ToString::to_string),
+            parent_id: self.parent_id.map(ToString::to_string),
+            parti: self.parti.to_string(),
+            startpos: self.startpos,
+            systemdatum: self.systemdatum,
+            talare: self.talare.to_string(),
+            talare_kort: self.talare_kort.map(ToString::to_string),
+            tumnagel: self.tumnagel.as_deref().map(ToString::to_string),
+            tumnagel_stor: self.tumnagel_stor.map(ToString::to_string),
+            video_id: self.video_id.to_string(),
+            video_url: self.video_url.to_string(),
+            videostatus: self.videostatus,
+            voteringspunkt: self.voteringspunkt.map(ToString::to_string),
+            extra: Extra::default(),
+        }
+    }
+}
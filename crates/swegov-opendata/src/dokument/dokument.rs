@@ -2,10 +2,14 @@ use std::borrow::Cow;
 
 use chrono::NaiveDate;
 
+use crate::beteckning::{Beteckning, BeteckningError};
 use crate::date_formats::{self, SweDateTime};
 use crate::dokument::shared;
+use crate::extra::Extra;
 use crate::one_or_many;
-use crate::shared::optionals;
+use crate::riksmote::{Riksmote, RiksmoteError};
+use crate::shared::{cow, optionals};
+use crate::tracked::Tracked;
 
 pub use crate::dokument::aktivitet::{DokAktivitet, DokAktivitetRef};
 pub use crate::dokument::bilaga::{DokBilaga, DokBilagaRef};
@@ -20,19 +24,41 @@ pub use crate::dokument::uppgift::{DokUppgift, DokUppgiftRef};
 pub use crate::dokument::{debatt::DebattRef, forslag::DokForslagRef};
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields)]
 pub struct DokumentStatusPage {
     pub dokumentstatus: DokumentStatus,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+/// Borrowing version of [`DokumentStatusPage`].
+///
+/// The `*Ref` models don't keep unknown fields in `extra`, since `#[serde(flatten)]` buffers
+/// the whole object and prevents borrowing. They are deserialized with [`Tracked`] instead,
+/// so unknown fields are still collected and logged, or an error in strict mode.
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct DokumentStatusPageRef<'a> {
     pub dokumentstatus: DokumentStatusRef<'a>,
 }
+
+impl<'de: 'a, 'a> serde::Deserialize<'de> for DokumentStatusPageRef<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(bound(deserialize = "'de: 'a"))]
+        struct Page<'a> {
+            dokumentstatus: DokumentStatusRef<'a>,
+        }
+
+        let Page { dokumentstatus } = Page::deserialize(Tracked::new(deserializer))?;
+        Ok(Self { dokumentstatus })
+    }
+}
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
 #[serde(rename = "dokumentstatus")]
+#[yaserde(rename = "dokumentstatus")]
 pub struct DokumentStatus {
     pub dokument: Dokument,
     pub dokuppgift: Option<DokUppgift>,
@@ -48,14 +74,12 @@ pub struct DokumentStatus {
     pub dokutskottsforslag: Option<DokUtskottsForslag>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dokmotforslag: Option<DokMotForslag>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(
-    rename = "dokumentstatus",
-    deny_unknown_fields,
-    bound(deserialize = "'de: 'a")
-)]
+#[serde(rename = "dokumentstatus", bound(deserialize = "'de: 'a"))]
 pub struct DokumentStatusRef<'a> {
     pub dokument: DokumentRef<'a>,
     pub dokuppgift: Option<DokUppgiftRef<'a>>,
@@ -73,11 +97,41 @@ pub struct DokumentStatusRef<'a> {
     pub dokmotforslag: Option<DokMotForslagRef<'a>>,
 }
 
+impl DokumentStatusPageRef<'_> {
+    pub fn to_owned(&self) -> DokumentStatusPage {
+        DokumentStatusPage {
+            dokumentstatus: self.dokumentstatus.to_owned(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl DokumentStatusRef<'_> {
+    pub fn to_owned(&self) -> DokumentStatus {
+        DokumentStatus {
+            dokument: self.dokument.to_owned(),
+            dokuppgift: self.dokuppgift.as_ref().map(DokUppgiftRef::to_owned),
+            dokbilaga: self.dokbilaga.as_ref().map(DokBilagaRef::to_owned),
+            dokintressent: self.dokintressent.as_ref().map(DokIntressentRef::to_owned),
+            debatt: self.debatt.as_ref().map(DebattRef::to_owned),
+            dokaktivitet: self.dokaktivitet.as_ref().map(DokAktivitetRef::to_owned),
+            dokforslag: self.dokforslag.as_ref().map(DokForslagRef::to_owned),
+            dokreferens: self.dokreferens.as_ref().map(DokReferensRef::to_owned),
+            webbmedia: self.webbmedia.as_ref().map(WebbMediaRef::to_owned),
+            dokutskottsforslag: self
+                .dokutskottsforslag
+                .as_ref()
+                .map(DokUtskottsForslagRef::to_owned),
+            dokmotforslag: self.dokmotforslag.as_ref().map(DokMotForslagRef::to_owned),
+            extra: Extra::default(),
+        }
+    }
+}
+
 // #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Dokument {
     pub dok_id: String,
     // #[serde(deserialize_with = "deserialize_number_from_string")]
@@ -147,6 +201,8 @@ pub struct Dokument {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<String>,
     avdelningar: Option<shared::Avdelningar>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 impl Dokument {
@@ -156,6 +212,16 @@ impl Dokument {
     pub fn rm(&self) -> &str {
         &self.rm
     }
+    pub fn riksmote(&self) -> Result<Riksmote, RiksmoteError> {
+        self.rm.parse()
+    }
+    pub fn parse_beteckning(&self) -> Result<Beteckning, BeteckningError> {
+        Beteckning::from_parts(
+            &self.typ,
+            &self.rm,
+            self.beteckning.as_deref().unwrap_or(""),
+        )
+    }
     pub fn datum(&self) -> NaiveDate {
         self.datum.as_inner().date()
     }
@@ -174,11 +240,11 @@ impl Dokument {
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DokumentRef<'a> {
     pub dok_id: &'a str,
     // #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub hangar_id: &'a str,
+    pub hangar_id: Option<&'a str>,
     pub rm: &'a str,
     pub beteckning: Option<&'a str>,
     pub typ: &'a str,
@@ -186,9 +252,10 @@ pub struct DokumentRef<'a> {
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
-        deserialize_with = "one_or_many::string_or_seq_or_none_to_opt_string"
+        borrow,
+        deserialize_with = "one_or_many::string_or_seq_or_none_to_opt_cow_str"
     )]
-    pub doktyp: Option<String>,
+    pub doktyp: Option<Cow<'a, str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub typrubrik: Option<&'a str>,
     #[serde(default)]
@@ -197,7 +264,12 @@ pub struct DokumentRef<'a> {
     pub debattnamn: Option<&'a str>,
     pub tempbeteckning: Option<&'a str>,
     pub organ: Option<&'a str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        borrow,
+        deserialize_with = "cow::deserialize_option",
+        default
+    )]
     pub mottagare: Option<Cow<'a, str>>,
     // #[serde(deserialize_with = "deserialize_tryparse_from_string")]
     // nummer: TryParse<u64>,
@@ -210,8 +282,9 @@ pub struct DokumentRef<'a> {
     pub publicerad: Option<SweDateTime>,
     // #[serde(with = "date_formats::swe_date_format")]
     pub systemdatum: SweDateTime,
-    #[serde(deserialize_with = "optionals::deserialize_null_default")]
-    pub titel: String,
+    #[serde(borrow, deserialize_with = "cow::deserialize_null_default")]
+    pub titel: Cow<'a, str>,
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
     pub subtitel: Option<Cow<'a, str>>,
     pub status: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -230,18 +303,35 @@ pub struct DokumentRef<'a> {
     pub dokumentstatus_url_xml: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub utskottsforslag_url_xml: Option<&'a str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub text: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        borrow,
+        deserialize_with = "cow::deserialize_option",
+        default
+    )]
+    pub text: Option<Cow<'a, str>>,
     // #[serde(skip_serializing_if = "Option::is_none")]
-    pub html: Option<String>,
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
+    pub html: Option<Cow<'a, str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pretext: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rubriker: Option<&'a str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        borrow,
+        deserialize_with = "cow::deserialize_option",
+        default
+    )]
     pub images: Option<Cow<'a, str>>,
-    #[serde(skip_serializing_if = "Option::is_none", borrow)]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        borrow,
+        deserialize_with = "cow::deserialize_option",
+        default
+    )]
     pub metadata: Option<Cow<'a, str>>,
+    avdelningar: Option<shared::Avdelningar>,
 }
 
 impl<'a> DokumentRef<'a> {
@@ -251,12 +341,59 @@ impl<'a> DokumentRef<'a> {
     pub fn rm(&self) -> &str {
         self.rm
     }
+    pub fn riksmote(&self) -> Result<Riksmote, RiksmoteError> {
+        self.rm.parse()
+    }
+    pub fn parse_beteckning(&self) -> Result<Beteckning, BeteckningError> {
+        Beteckning::from_parts(self.typ, self.rm, self.beteckning.unwrap_or(""))
+    }
     pub fn datum(&self) -> NaiveDate {
         self.datum.date()
     }
     pub fn titel(&self) -> &str {
         &self.titel
     }
+    pub fn to_owned(&self) -> Dokument {
+        Dokument {
+            dok_id: self.dok_id.to_string(),
+            hangar_id: self.hangar_id.map(ToString::to_string),
+            rm: self.rm.to_string(),
+            beteckning: self.beteckning.map(ToString::to_string),
+            typ: self.typ.to_string(),
+            subtyp: self.subtyp.map(ToString::to_string),
+            doktyp: self.doktyp.as_deref().map(ToString::to_string),
+            typrubrik: self.typrubrik.map(ToString::to_string),
+            dokumentnamn: self.dokumentnamn.to_string(),
+            debattnamn: self.debattnamn.map(ToString::to_string),
+            tempbeteckning: self.tempbeteckning.map(ToString::to_string),
+            organ: self.organ.map(ToString::to_string),
+            mottagare: self.mottagare.as_deref().map(ToString::to_string),
+            nummer: self.nummer.to_string(),
+            slutnummer: self.slutnummer.to_string(),
+            datum: self.datum,
+            publicerad: self.publicerad,
+            systemdatum: self.systemdatum,
+            titel: self.titel.to_string(),
+            subtitel: self.subtitel.as_deref().map(ToString::to_string),
+            status: self.status.map(ToString::to_string),
+            htmlformat: self.htmlformat.map(ToString::to_string),
+            relaterat_id: self.relaterat_id.map(ToString::to_string),
+            source: self.source.map(ToString::to_string),
+            sourceid: self.sourceid.map(ToString::to_string),
+            dokument_url_text: self.dokument_url_text.map(ToString::to_string),
+            dokument_url_html: self.dokument_url_html.map(ToString::to_string),
+            dokumentstatus_url_xml: self.dokumentstatus_url_xml.map(ToString::to_string),
+            utskottsforslag_url_xml: self.utskottsforslag_url_xml.map(ToString::to_string),
+            text: self.text.as_deref().map(ToString::to_string),
+            html: self.html.as_deref().map(ToString::to_string),
+            pretext: self.pretext.map(ToString::to_string),
+            rubriker: self.rubriker.map(ToString::to_string),
+            images: self.images.as_deref().map(ToString::to_string),
+            metadata: self.metadata.as_deref().map(ToString::to_string),
+            avdelningar: self.avdelningar.clone(),
+            extra: Extra::default(),
+        }
+    }
     // pub fn organ(&self) -> &str {
     //     &self.organ
     // }
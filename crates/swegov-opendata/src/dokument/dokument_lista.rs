@@ -4,19 +4,22 @@ use serde_with::DisplayFromStr;
 use crate::date_formats::SweDate;
 use crate::date_formats::SweDateTime;
 use crate::dokument::shared;
+use crate::extra::Extra;
+use crate::riksmote::{Riksmote, RiksmoteError};
 use crate::shared::optionals;
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields)]
 pub struct DokumentListaPage {
     dokumentlista: DokumentLista,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields, rename = "dokumentlista")]
+#[serde(rename = "dokumentlista")]
 #[yaserde(rename = "dokumentlista")]
 pub struct DokumentLista {
     #[serde(rename = "@dDt")]
@@ -71,13 +74,14 @@ pub struct DokumentLista {
     version: String,
     facettlista: Option<String>,
     pub dokument: Vec<DokumentListaDokument>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DokumentListaDokument {
     #[serde_as(as = "DisplayFromStr")]
     traff: u64,
@@ -146,6 +150,28 @@ pub struct DokumentListaDokument {
 
     dokumentnamn: String,
     avdelningar: Option<shared::Avdelningar>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+impl DokumentLista {
+    /// The number of documents matching the query, on all pages.
+    pub fn traffar(&self) -> u64 {
+        self.traffar
+    }
+    /// The number of pages.
+    pub fn sidor(&self) -> u64 {
+        self.sidor
+    }
+}
+
+impl DokumentListaDokument {
+    pub fn rm(&self) -> &str {
+        &self.rm
+    }
+    pub fn riksmote(&self) -> Result<Riksmote, RiksmoteError> {
+        self.rm.parse()
+    }
 }
 
 #[derive(
@@ -157,26 +183,27 @@ pub struct DokumentListaDokument {
     yaserde::YaDeserialize,
     yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct FilBilaga {
     #[serde(skip_serializing_if = "Option::is_none")]
     fil: Option<Fil>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Fil {
     typ: String,
     namn: String,
     storlek: u64,
     url: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct SokData {
     titel: String,
     undertitel: String,
@@ -196,4 +223,6 @@ pub struct SokData {
     parti_logotyp_img_alt: Option<String>,
     parti_mandat: Option<String>,
     kalenderprio: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
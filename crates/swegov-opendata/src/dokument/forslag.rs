@@ -3,9 +3,9 @@ use std::borrow::Cow;
 use serde_aux::field_attributes::deserialize_number_from_string;
 use serde_with::serde_as;
 use serde_with::{formats::PreferMany, OneOrMany};
-use uuid::Uuid;
 
-use crate::shared::optionals;
+use crate::extra::Extra;
+use crate::shared::{cow, optionals, xml_value};
 
 #[cfg(test)]
 mod tests;
@@ -14,35 +14,38 @@ mod tests;
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DokForslag {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub forslag: Vec<Forslag>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DokUtskottsForslag {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub utskottsforslag: Vec<UtskottsForslag>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DokMotForslag {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub motforslag: Vec<MotForslag>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DokForslagRef<'a> {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub forslag: Vec<ForslagRef<'a>>,
@@ -50,7 +53,7 @@ pub struct DokForslagRef<'a> {
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DokUtskottsForslagRef<'a> {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub utskottsforslag: Vec<UtskottsForslagRef<'a>>,
@@ -58,7 +61,7 @@ pub struct DokUtskottsForslagRef<'a> {
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DokMotForslagRef<'a> {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub motforslag: Vec<MotForslagRef<'a>>,
@@ -67,7 +70,6 @@ pub struct DokMotForslagRef<'a> {
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Forslag {
     // #[serde(deserialize_with = "deserialize_number_from_string")]
     pub hangar_id: Option<String>,
@@ -88,12 +90,13 @@ pub struct Forslag {
     pub avsnitt: Option<String>,
     pub grundforfattning: Option<String>,
     pub andringsforfattning: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct UtskottsForslag {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub punkt: u64,
@@ -118,12 +121,13 @@ pub struct UtskottsForslag {
     pub beslutsregelkvot: Option<String>,
     pub beslutsregelparagraf: Option<String>,
     pub punkttyp: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct MotForslag {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub nummer: u64,
@@ -134,19 +138,22 @@ pub struct MotForslag {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub utskottsforslag_punkt: u64,
     pub id: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct ForslagRef<'a> {
     // #[serde(deserialize_with = "deserialize_number_from_string")]
     pub hangar_id: Option<&'a str>,
     #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub nummer: usize,
+    pub nummer: u64,
     pub beteckning: Option<&'a str>,
-    #[serde(deserialize_with = "optionals::deserialize_null_default")]
+    #[serde(borrow, deserialize_with = "cow::deserialize_null_default")]
     pub lydelse: Cow<'a, str>,
-    pub lydelse2: Option<&'a str>,
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
+    pub lydelse2: Option<Cow<'a, str>>,
     pub utskottet: Option<&'a str>,
     pub kammaren: Option<&'a str>,
     pub behandlas_i: Option<&'a str>,
@@ -161,22 +168,22 @@ pub struct ForslagRef<'a> {
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct UtskottsForslagRef<'a> {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub punkt: u64,
     pub rubrik: &'a str,
-    #[serde(borrow)]
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
     pub forslag: Option<Cow<'a, str>>,
-    #[serde(borrow)]
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
     pub forslag_del2: Option<Cow<'a, str>>,
     pub beslut: Option<&'a str>,
     pub beslutstyp: Option<&'a str>,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub motforslag_nummer: u64,
-    #[serde(borrow)]
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
     pub motforslag_partier: Option<Cow<'a, str>>,
-    pub votering_id: Option<Uuid>,
+    pub votering_id: Option<&'a str>,
     pub votering_sammanfattning_html: Option<serde_json::Value>,
     pub votering_url_xml: Option<&'a str>,
     pub votering_ledamot_url_xml: Option<&'a str>,
@@ -213,12 +220,13 @@ pub struct UtskottsForslagRef<'a> {
 // }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct MotForslagRef<'a> {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub nummer: u64,
     pub rubrik: Option<&'a str>,
-    pub forslag: Option<&'a str>,
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
+    pub forslag: Option<Cow<'a, str>>,
     #[serde(borrow)]
     pub partier: Cow<'a, str>,
     pub typ: &'a str,
@@ -227,23 +235,130 @@ pub struct MotForslagRef<'a> {
     pub id: Option<&'a str>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-pub struct AnyValue(serde_json::Value);
+impl DokForslagRef<'_> {
+    pub fn to_owned(&self) -> DokForslag {
+        DokForslag {
+            forslag: self.forslag.iter().map(ForslagRef::to_owned).collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl DokUtskottsForslagRef<'_> {
+    pub fn to_owned(&self) -> DokUtskottsForslag {
+        DokUtskottsForslag {
+            utskottsforslag: self
+                .utskottsforslag
+                .iter()
+                .map(UtskottsForslagRef::to_owned)
+                .collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl DokMotForslagRef<'_> {
+    pub fn to_owned(&self) -> DokMotForslag {
+        DokMotForslag {
+            motforslag: self
+                .motforslag
+                .iter()
+                .map(MotForslagRef::to_owned)
+                .collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl ForslagRef<'_> {
+    pub fn to_owned(&self) -> Forslag {
+        Forslag {
+            hangar_id: self.hangar_id.map(ToString::to_string),
+            nummer: self.nummer,
+            beteckning: self.beteckning.map(ToString::to_string),
+            lydelse: self.lydelse.to_string(),
+            lydelse2: self.lydelse2.as_deref().map(ToString::to_string),
+            utskottet: self.utskottet.map(ToString::to_string),
+            kammaren: self.kammaren.map(ToString::to_string),
+            behandlas_i: self.behandlas_i.map(ToString::to_string),
+            behandlas_i_punkt: self.behandlas_i_punkt.map(ToString::to_string),
+            kammarbeslutstyp: self.kammarbeslutstyp.map(ToString::to_string),
+            intressent: self.intressent.map(ToString::to_string),
+            avsnitt: self.avsnitt.map(ToString::to_string),
+            grundforfattning: self.grundforfattning.map(ToString::to_string),
+            andringsforfattning: self.andringsforfattning.map(ToString::to_string),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl UtskottsForslagRef<'_> {
+    pub fn to_owned(&self) -> UtskottsForslag {
+        UtskottsForslag {
+            punkt: self.punkt,
+            rubrik: self.rubrik.to_string(),
+            forslag: self.forslag.as_deref().map(ToString::to_string),
+            forslag_del2: self.forslag_del2.as_deref().map(ToString::to_string),
+            beteckning: self.beteckning.map(ToString::to_string),
+            beslut: self.beslut.map(ToString::to_string),
+            beslutstyp: self.beslutstyp.map(ToString::to_string),
+            motforslag_nummer: self.motforslag_nummer,
+            motforslag_partier: self.motforslag_partier.as_deref().map(ToString::to_string),
+            votering_id: self.votering_id.map(ToString::to_string),
+            votering_sammanfattning_html: self.votering_sammanfattning_html.clone().map(AnyValue),
+            votering_ledamot_url_xml: self.votering_ledamot_url_xml.map(ToString::to_string),
+            votering_url_xml: self.votering_url_xml.map(ToString::to_string),
+            rm: self.rm.to_string(),
+            bet: self.bet.to_string(),
+            vinnare: self.vinnare.map(ToString::to_string),
+            voteringskrav: self.voteringskrav.map(ToString::to_string),
+            beslutsregelkvot: self.beslutsregelkvot.map(ToString::to_string),
+            beslutsregelparagraf: self.beslutsregelparagraf.map(ToString::to_string),
+            punkttyp: self.punkttyp.map(ToString::to_string),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl MotForslagRef<'_> {
+    pub fn to_owned(&self) -> MotForslag {
+        MotForslag {
+            nummer: self.nummer,
+            rubrik: self.rubrik.map(ToString::to_string),
+            forslag: self.forslag.as_deref().map(ToString::to_string),
+            partier: self.partier.to_string(),
+            typ: self.typ.to_string(),
+            utskottsforslag_punkt: self.utskottsforslag_punkt,
+            id: self.id.map(ToString::to_string),
+            extra: Extra::default(),
+        }
+    }
+}
+
+/// A JSON value, read from and written to XML as described in [`xml_value`].
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct AnyValue(pub serde_json::Value);
 
 impl yaserde::YaDeserialize for AnyValue {
     fn deserialize<R: std::io::Read>(
-        _reader: &mut yaserde::de::Deserializer<R>,
+        reader: &mut yaserde::de::Deserializer<R>,
     ) -> Result<Self, String> {
-        todo!()
+        xml_value::read_value(reader).map(AnyValue)
     }
 }
 
 impl yaserde::YaSerialize for AnyValue {
     fn serialize<W: std::io::Write>(
         &self,
-        _writer: &mut yaserde::ser::Serializer<W>,
+        writer: &mut yaserde::ser::Serializer<W>,
     ) -> Result<(), String> {
-        todo!()
+        if writer.skip_start_end() {
+            return xml_value::write_content(writer, &self.0);
+        }
+        let name = writer
+            .get_start_event_name()
+            .unwrap_or_else(|| "AnyValue".to_string());
+        xml_value::write_value(writer, &name, &self.0)
     }
     fn serialize_attributes(
         &self,
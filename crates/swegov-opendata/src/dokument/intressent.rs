@@ -1,37 +1,41 @@
 use serde_with::serde_as;
 use serde_with::{formats::PreferMany, OneOrMany};
 
+use crate::extra::Extra;
+
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DokIntressent {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub intressent: Vec<Intressent>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Intressent {
     pub roll: String,
     pub namn: String,
     pub partibet: Option<String>,
     pub intressent_id: String,
     pub ordning: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DokIntressentRef<'a> {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub intressent: Vec<IntressentRef<'a>>,
 }
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct IntressentRef<'a> {
     pub roll: &'a str,
     pub namn: &'a str,
@@ -39,3 +43,29 @@ pub struct IntressentRef<'a> {
     pub intressent_id: &'a str,
     pub ordning: &'a str,
 }
+
+impl DokIntressentRef<'_> {
+    pub fn to_owned(&self) -> DokIntressent {
+        DokIntressent {
+            intressent: self
+                .intressent
+                .iter()
+                .map(IntressentRef::to_owned)
+                .collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl IntressentRef<'_> {
+    pub fn to_owned(&self) -> Intressent {
+        Intressent {
+            roll: self.roll.to_string(),
+            namn: self.namn.to_string(),
+            partibet: self.partibet.map(ToString::to_string),
+            intressent_id: self.intressent_id.to_string(),
+            ordning: self.ordning.to_string(),
+            extra: Extra::default(),
+        }
+    }
+}
@@ -1,24 +1,24 @@
-use chrono::NaiveDateTime;
 use serde_aux::field_attributes::deserialize_number_from_string;
 use serde_with::serde_as;
 use serde_with::{formats::PreferMany, OneOrMany};
 
-use crate::date_formats::{self, SweDateTime};
+use crate::date_formats::SweDateTime;
+use crate::extra::Extra;
 
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct WebbMedia {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub media: Vec<Media>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Media {
     pub dok_id: String,
     pub url: String,
@@ -42,18 +42,20 @@ pub struct Media {
     pub inspelningstyp: String,
     // #[serde(with = "date_formats::swe_date_format")]
     pub systemdatum: SweDateTime,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct WebbMediaRef<'a> {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub media: Vec<MediaRef<'a>>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct MediaRef<'a> {
     pub dok_id: &'a str,
     pub url: &'a str,
@@ -67,14 +69,45 @@ pub struct MediaRef<'a> {
     pub debatt_rm: &'a str,
     pub debatt_typ: &'a str,
     pub dok_beteckning: &'a str,
-    #[serde(with = "date_formats::swe_date_format")]
-    pub datum: NaiveDateTime,
+    pub datum: SweDateTime,
     // #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
     // pub videostatus: Option<u64>,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub videostatus: u64,
     pub source: &'a str,
     pub inspelningstyp: &'a str,
-    #[serde(with = "date_formats::swe_date_format")]
-    pub systemdatum: NaiveDateTime,
+    pub systemdatum: SweDateTime,
+}
+
+impl WebbMediaRef<'_> {
+    pub fn to_owned(&self) -> WebbMedia {
+        WebbMedia {
+            media: self.media.iter().map(MediaRef::to_owned).collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl MediaRef<'_> {
+    pub fn to_owned(&self) -> Media {
+        Media {
+            dok_id: self.dok_id.to_string(),
+            url: self.url.to_string(),
+            videofileurl: self.videofileurl.to_string(),
+            audiofileurl: self.audiofileurl.to_string(),
+            downloadurl: self.downloadurl.to_string(),
+            thumbnailurl: self.thumbnailurl.to_string(),
+            debateurl: self.debateurl.to_string(),
+            debattsekunder: self.debattsekunder,
+            debatt_rm: self.debatt_rm.to_string(),
+            debatt_typ: self.debatt_typ.to_string(),
+            dok_beteckning: self.dok_beteckning.to_string(),
+            datum: self.datum,
+            videostatus: self.videostatus,
+            source: self.source.to_string(),
+            inspelningstyp: self.inspelningstyp.to_string(),
+            systemdatum: self.systemdatum,
+            extra: Extra::default(),
+        }
+    }
 }
@@ -1,21 +1,27 @@
+use std::borrow::Cow;
+
 use serde_aux::field_attributes::deserialize_default_from_null;
 use serde_with::serde_as;
 use serde_with::{formats::PreferMany, OneOrMany};
 
+use crate::beteckning::{Beteckning, BeteckningError};
+use crate::extra::Extra;
+use crate::shared::cow;
+
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DokReferens {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub referens: Vec<Referens>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Referens {
     // #[serde(deserialize_with = "deserialize_number_from_string")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -33,33 +39,83 @@ pub struct Referens {
     pub ref_dok_subtyp: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ref_dok_dokumentnamn: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+impl Referens {
+    pub fn parse_ref_dok_bet(&self) -> Result<Beteckning, BeteckningError> {
+        Beteckning::from_parts(
+            &self.ref_dok_typ,
+            self.ref_dok_rm.as_deref().unwrap_or(""),
+            self.ref_dok_bet.as_deref().unwrap_or(""),
+        )
+    }
 }
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DokReferensRef<'a> {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub referens: Vec<ReferensRef<'a>>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct ReferensRef<'a> {
     // #[serde(deserialize_with = "deserialize_number_from_string")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hangar_id: Option<&'a str>,
     pub referenstyp: Option<&'a str>,
-    pub uppgift: Option<&'a str>,
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
+    pub uppgift: Option<Cow<'a, str>>,
     pub ref_dok_id: &'a str,
     pub ref_dok_typ: &'a str,
     pub ref_dok_rm: Option<&'a str>,
     pub ref_dok_bet: Option<&'a str>,
-    #[serde(deserialize_with = "deserialize_default_from_null")]
-    pub ref_dok_titel: &'a str,
-    pub ref_dok_subtitel: Option<&'a str>,
+    #[serde(borrow, deserialize_with = "cow::deserialize_null_default")]
+    pub ref_dok_titel: Cow<'a, str>,
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
+    pub ref_dok_subtitel: Option<Cow<'a, str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ref_dok_subtyp: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ref_dok_dokumentnamn: Option<&'a str>,
 }
+
+impl DokReferensRef<'_> {
+    pub fn to_owned(&self) -> DokReferens {
+        DokReferens {
+            referens: self.referens.iter().map(ReferensRef::to_owned).collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl ReferensRef<'_> {
+    pub fn to_owned(&self) -> Referens {
+        Referens {
+            hangar_id: self.hangar_id.map(ToString::to_string),
+            referenstyp: self.referenstyp.map(ToString::to_string),
+            uppgift: self.uppgift.as_deref().map(ToString::to_string),
+            ref_dok_id: self.ref_dok_id.to_string(),
+            ref_dok_typ: self.ref_dok_typ.to_string(),
+            ref_dok_rm: self.ref_dok_rm.map(ToString::to_string),
+            ref_dok_bet: self.ref_dok_bet.map(ToString::to_string),
+            ref_dok_titel: self.ref_dok_titel.to_string(),
+            ref_dok_subtitel: self.ref_dok_subtitel.as_deref().map(ToString::to_string),
+            ref_dok_subtyp: self.ref_dok_subtyp.map(ToString::to_string),
+            ref_dok_dokumentnamn: self.ref_dok_dokumentnamn.map(ToString::to_string),
+            extra: Extra::default(),
+        }
+    }
+
+    pub fn parse_ref_dok_bet(&self) -> Result<Beteckning, BeteckningError> {
+        Beteckning::from_parts(
+            self.ref_dok_typ,
+            self.ref_dok_rm.unwrap_or(""),
+            self.ref_dok_bet.unwrap_or(""),
+        )
+    }
+}
@@ -1,19 +1,21 @@
 use std::borrow::Cow;
 
-use chrono::NaiveDateTime;
 use serde_with::serde_as;
 use serde_with::{formats::PreferMany, OneOrMany};
 
-use crate::date_formats::{self, SweDateTime};
+use crate::date_formats::SweDateTime;
+use crate::extra::Extra;
+use crate::shared::cow;
 
 #[serde_as]
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct DokUppgift {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub uppgift: Vec<Uppgift>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 impl DokUppgift {
@@ -30,7 +32,6 @@ impl DokUppgift {
 #[derive(
     Debug, Clone, serde::Deserialize, serde::Serialize, yaserde::YaDeserialize, yaserde::YaSerialize,
 )]
-#[serde(deny_unknown_fields)]
 pub struct Uppgift {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dok_id: Option<String>,
@@ -43,11 +44,13 @@ pub struct Uppgift {
     )]
     pub systemdatum: Option<SweDateTime>,
     pub text: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct DokUppgiftRef<'a> {
     #[serde_as(as = "OneOrMany<_, PreferMany>")]
     pub uppgift: Vec<UppgiftRef<'a>>,
@@ -65,18 +68,36 @@ impl<'a> DokUppgiftRef<'a> {
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-#[serde(deny_unknown_fields, bound(deserialize = "'de: 'a"))]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct UppgiftRef<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dok_id: Option<&'a str>,
     pub kod: &'a str,
     pub namn: &'a str,
-    #[serde(
-        skip_serializing_if = "Option::is_none",
-        with = "date_formats::option_swe_date_format",
-        default
-    )]
-    pub systemdatum: Option<NaiveDateTime>,
-    #[serde(borrow)]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub systemdatum: Option<SweDateTime>,
+    #[serde(borrow, deserialize_with = "cow::deserialize_option", default)]
     pub text: Option<Cow<'a, str>>,
 }
+
+impl DokUppgiftRef<'_> {
+    pub fn to_owned(&self) -> DokUppgift {
+        DokUppgift {
+            uppgift: self.uppgift.iter().map(UppgiftRef::to_owned).collect(),
+            extra: Extra::default(),
+        }
+    }
+}
+
+impl UppgiftRef<'_> {
+    pub fn to_owned(&self) -> Uppgift {
+        Uppgift {
+            dok_id: self.dok_id.map(ToString::to_string),
+            kod: self.kod.to_string(),
+            namn: self.namn.to_string(),
+            systemdatum: self.systemdatum,
+            text: self.text.as_deref().map(ToString::to_string),
+            extra: Extra::default(),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    ops::Deref,
+};
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::tracked::{self, Collected};
+
+#[cfg(test)]
+mod tests;
+
+/// How fields that aren't part of a model are handled when deserializing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeserializeMode {
+    /// Unknown fields are an error, use this to detect changes in the data from Riksdagen.
+    Strict,
+    /// Unknown fields are collected in `extra` and logged as a warning.
+    Lenient,
+}
+
+thread_local! {
+    static MODE: Cell<Option<DeserializeMode>> = const { Cell::new(None) };
+    static UNKNOWN_FIELDS: Collected = const { RefCell::new(None) };
+}
+
+impl DeserializeMode {
+    /// The mode used outside [`with_mode`], `Strict` if the `strict` feature is enabled.
+    pub const DEFAULT: Self = if cfg!(feature = "strict") {
+        Self::Strict
+    } else {
+        Self::Lenient
+    };
+
+    /// The mode in effect on this thread.
+    pub fn current() -> Self {
+        MODE.get().unwrap_or(Self::DEFAULT)
+    }
+}
+
+/// Deserialize with unknown fields handled according to `mode` while running `f`.
+pub fn with_mode<T>(mode: DeserializeMode, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<DeserializeMode>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            MODE.set(self.0);
+        }
+    }
+    let _restore = Restore(MODE.replace(Some(mode)));
+    f()
+}
+
+/// Run `f` and return the paths of all unknown fields found while deserializing in it.
+///
+/// A path is e.g. `dokumentstatus.dokument.nytt_falt` when deserializing with
+/// [`Tracked`](crate::Tracked), otherwise just the name of the field.
+pub fn collect_unknown_fields<T>(f: impl FnOnce() -> T) -> (T, BTreeSet<String>) {
+    tracked::collect(&UNKNOWN_FIELDS, f)
+}
+
+/// Handle the unknown fields `names` of the value being deserialized according to the mode.
+pub(crate) fn unknown_fields<E: de::Error>(names: &[&str]) -> Result<(), E> {
+    for name in names {
+        tracked::record(&UNKNOWN_FIELDS, Some(name));
+    }
+    match DeserializeMode::current() {
+        DeserializeMode::Strict => Err(E::custom(format_args!(
+            "unknown fields: {}",
+            names.join(", ")
+        ))),
+        DeserializeMode::Lenient => {
+            tracing::warn!(unknown_fields = ?names, "found unknown fields");
+            Ok(())
+        }
+    }
+}
+
+/// Fields of a model that aren't known, captured with `#[serde(flatten)]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(transparent)]
+pub struct Extra(BTreeMap<String, serde_json::Value>);
+
+impl Extra {
+    pub fn into_inner(self) -> BTreeMap<String, serde_json::Value> {
+        self.0
+    }
+}
+
+impl Deref for Extra {
+    type Target = BTreeMap<String, serde_json::Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<BTreeMap<String, serde_json::Value>> for Extra {
+    fn from(fields: BTreeMap<String, serde_json::Value>) -> Self {
+        Self(fields)
+    }
+}
+
+impl<'de> Deserialize<'de> for Extra {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let fields = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+        if !fields.is_empty() {
+            let names = fields.keys().map(String::as_str).collect::<Vec<_>>();
+            unknown_fields(&names)?;
+        }
+        Ok(Self(fields))
+    }
+}
+
+impl PartialOrd for Extra {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Extra {
+    /// `serde_json::Value` isn't `Ord`, so values are compared as JSON strings.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .iter()
+            .map(|(name, value)| (name, value.to_string()))
+            .cmp(
+                other
+                    .0
+                    .iter()
+                    .map(|(name, value)| (name, value.to_string())),
+            )
+    }
+}
+
+/// Unknown fields are only kept from JSON, in XML they are skipped.
+impl yaserde::YaDeserialize for Extra {
+    fn deserialize<R: std::io::Read>(
+        reader: &mut yaserde::de::Deserializer<R>,
+    ) -> Result<Self, String> {
+        let depth = reader.depth();
+        loop {
+            match reader.next_event()? {
+                xml::reader::XmlEvent::EndElement { .. } if reader.depth() == depth => break,
+                xml::reader::XmlEvent::EndDocument => break,
+                _ => {}
+            }
+        }
+        Ok(Self::default())
+    }
+}
+
+impl yaserde::YaSerialize for Extra {
+    fn serialize<W: std::io::Write>(
+        &self,
+        _writer: &mut yaserde::ser::Serializer<W>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn serialize_attributes(
+        &self,
+        attributes: Vec<xml::attribute::OwnedAttribute>,
+        namespace: xml::namespace::Namespace,
+    ) -> Result<
+        (
+            Vec<xml::attribute::OwnedAttribute>,
+            xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        Ok((attributes, namespace))
+    }
+}
@@ -0,0 +1,76 @@
+use super::*;
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+struct Model {
+    namn: String,
+    #[serde(flatten)]
+    extra: Extra,
+}
+
+#[test]
+fn lenient_mode_collects_unknown_fields() -> anyhow::Result<()> {
+    let model: Model = with_mode(DeserializeMode::Lenient, || {
+        serde_json::from_str(r#"{"namn": "prop", "nytt_falt": {"a": 1}, "annat": null}"#)
+    })?;
+
+    assert_eq!(model.namn, "prop");
+    assert_eq!(
+        model.extra.keys().collect::<Vec<_>>(),
+        ["annat", "nytt_falt"]
+    );
+    assert_eq!(model.extra["nytt_falt"], serde_json::json!({"a": 1}));
+    Ok(())
+}
+
+#[test]
+fn strict_mode_fails_on_unknown_fields() {
+    let result: Result<Model, _> = with_mode(DeserializeMode::Strict, || {
+        serde_json::from_str(r#"{"namn": "prop", "nytt_falt": 1}"#)
+    });
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.starts_with("unknown fields: nytt_falt"),
+        "unexpected error '{error}'"
+    );
+}
+
+#[test]
+fn strict_mode_accepts_known_fields() -> anyhow::Result<()> {
+    let model: Model = with_mode(DeserializeMode::Strict, || {
+        serde_json::from_str(r#"{"namn": "prop"}"#)
+    })?;
+
+    assert!(model.extra.is_empty());
+    assert_eq!(serde_json::to_string(&model)?, r#"{"namn":"prop"}"#);
+    Ok(())
+}
+
+#[test]
+fn with_mode_restores_previous_mode() {
+    with_mode(DeserializeMode::Strict, || {
+        with_mode(DeserializeMode::Lenient, || {
+            assert_eq!(DeserializeMode::current(), DeserializeMode::Lenient);
+        });
+        assert_eq!(DeserializeMode::current(), DeserializeMode::Strict);
+    });
+    assert_eq!(DeserializeMode::current(), DeserializeMode::DEFAULT);
+}
+
+#[test]
+fn collect_unknown_fields_gives_names() -> anyhow::Result<()> {
+    let (models, names) = collect_unknown_fields(|| {
+        with_mode(DeserializeMode::Lenient, || {
+            serde_json::from_str::<Vec<Model>>(
+                r#"[{"namn": "prop", "nytt_falt": 1}, {"namn": "bet", "annat": null}]"#,
+            )
+        })
+    });
+
+    assert_eq!(models?.len(), 2);
+    assert_eq!(
+        names.into_iter().collect::<Vec<_>>(),
+        ["annat", "nytt_falt"]
+    );
+    Ok(())
+}
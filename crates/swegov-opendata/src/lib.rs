@@ -1,16 +1,35 @@
+mod beteckning;
 mod dataset;
 pub mod date_formats;
 pub mod dokument;
+mod extra;
 pub mod one_or_many;
+mod person;
+mod riksmote;
 pub mod shared;
+pub mod streaming;
+mod tracked;
 pub mod try_parse;
 
-pub use dataset::{DataFormat, DataSet, DatasetLista, FilFormat};
+pub use beteckning::{Beteckning, BeteckningError, SfsNummer};
+pub use dataset::{
+    DataFormat, DataSet, DatasetLista, DatasetQuery, FilFormat, UnknownDataFormat, Upplysning,
+    UpplysningError, YearComment,
+};
 pub use dokument::{
-    Aktivitet, Bilaga, DokAktivitet, DokBilaga, DokForslag, DokIntressent, DokIntressentRef,
-    DokUppgift, Dokument, DokumentStatus, DokumentStatusPage, DokumentStatusPageRef,
-    DokumentStatusRef, Forslag, Intressent, IntressentRef, Referens, Uppgift,
+    Aktivitet, AktivitetRef, AnyValue, Bilaga, BilagaRef, Debatt, DebattAnforande,
+    DebattAnforandeRef, DebattRef, DokAktivitet, DokAktivitetRef, DokBilaga, DokBilagaRef,
+    DokForslag, DokForslagRef, DokIntressent, DokIntressentRef, DokMotForslag, DokMotForslagRef,
+    DokReferens, DokReferensRef, DokUppgift, DokUppgiftRef, DokUtskottsForslag,
+    DokUtskottsForslagRef, Dokument, DokumentRef, DokumentStatus, DokumentStatusPage,
+    DokumentStatusPageRef, DokumentStatusRef, Forslag, ForslagRef, Intressent, IntressentRef,
+    Media, MediaRef, MotForslag, MotForslagRef, Referens, ReferensRef, Uppgift, UppgiftRef,
+    UtskottsForslag, UtskottsForslagRef, WebbMedia, WebbMediaRef,
 };
 pub use dokument::{
     DokumentLista, DokumentListaDokument, DokumentListaPage, Fil, FilBilaga, SokData,
 };
+pub use extra::{collect_unknown_fields, with_mode, DeserializeMode, Extra};
+pub use person::{Person, PersonLista, PersonListaPage};
+pub use riksmote::{Riksmote, RiksmoteError, RiksmoteRange, FIRST_SPLIT_YEAR};
+pub use tracked::Tracked;
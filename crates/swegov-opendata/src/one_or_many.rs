@@ -86,6 +86,13 @@ where
             Ok(Some(Cow::Owned(value.into())))
         }
 
+        fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Some(Cow::Borrowed(value)))
+        }
+
         fn visit_seq<S>(self, visitor: S) -> Result<Self::Value, S::Error>
         where
             S: serde::de::SeqAccess<'de>,
@@ -0,0 +1,48 @@
+use serde_aux::field_attributes::deserialize_option_number_from_string;
+use serde_with::serde_as;
+use serde_with::{formats::PreferMany, OneOrMany};
+
+#[cfg(test)]
+mod tests;
+
+/// The person register (`personlista`) from data.riksdagen.se.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PersonListaPage {
+    pub personlista: PersonLista,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PersonLista {
+    #[serde_as(as = "OneOrMany<_, PreferMany>")]
+    #[serde(default)]
+    pub person: Vec<Person>,
+}
+
+/// A person in the register.
+///
+/// Only the fields needed to identify a speaker are kept, the assignments
+/// (`personuppdrag`) and details (`personuppgift`) are skipped.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Person {
+    pub intressent_id: String,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub fodd_ar: Option<u16>,
+    #[serde(default)]
+    pub kon: Option<String>,
+    pub tilltalsnamn: String,
+    pub efternamn: String,
+    #[serde(default)]
+    pub sorteringsnamn: Option<String>,
+    #[serde(default)]
+    pub parti: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl Person {
+    /// The name as it is written in debates, e.g. "Anna Lindh".
+    pub fn namn(&self) -> String {
+        format!("{} {}", self.tilltalsnamn.trim(), self.efternamn.trim())
+    }
+}
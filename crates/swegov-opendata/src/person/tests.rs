@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn deserialize_personlista() -> anyhow::Result<()> {
+    let source = r#"{
+        "personlista": {
+            "@hamtad": "2024-10-01 10:00:00",
+            "@systemdatum": "2024-10-01 09:00:00",
+            "person": {
+                "hangar_guid": "0c77a6b4-1a6f-4e6b-9d84-3e6e1c6fbb50",
+                "sourceid": "0980681405710",
+                "intressent_id": "0980681405710",
+                "hangar_id": "4400",
+                "fodd_ar": "1967",
+                "kon": "kvinna",
+                "efternamn": "Andersson",
+                "tilltalsnamn": "Magdalena",
+                "sorteringsnamn": "Andersson,Magdalena",
+                "iort": "",
+                "parti": "S",
+                "valkrets": "Stockholms kommun",
+                "status": "Tjänstgörande riksdagsledamot",
+                "person_url_xml": "http://data.riksdagen.se/person/0980681405710",
+                "bild_url_80": "https://data.riksdagen.se/filarkiv/bilder/ledamot/x_80.jpg",
+                "personuppdrag": null,
+                "personuppgift": null
+            }
+        }
+    }"#;
+    let PersonListaPage { personlista } = serde_json::from_str(source)?;
+
+    assert_eq!(personlista.person.len(), 1);
+    let person = &personlista.person[0];
+    assert_eq!(person.intressent_id, "0980681405710");
+    assert_eq!(person.fodd_ar, Some(1967));
+    assert_eq!(person.kon.as_deref(), Some("kvinna"));
+    assert_eq!(person.namn(), "Magdalena Andersson");
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use std::{fmt, str::FromStr};
+
+use chrono::NaiveDate;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+#[cfg(test)]
+mod tests;
+
+/// The first riksmöte that spans two calendar years (1975/76).
+pub const FIRST_SPLIT_YEAR: u16 = 1975;
+
+/// A riksmöte (session of the Riksdag), e.g. "2019/20", "1880" or "2007/08:1".
+///
+/// Riksmöten up to 1975 follow the calendar year ("1880"), later ones run from
+/// September to the following summer ("2019/20"). A trailing ":1" numbers one
+/// of several riksmöten in the same year.
+///
+/// Riksmöten are ordered by when they start, so "2019" comes before "2019/20". Use
+/// [`RiksmoteRange`] to check whether riksmöten of both kinds overlap.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct Riksmote {
+    start_year: u16,
+    split: bool,
+    nummer: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RiksmoteError {
+    #[error("'{0}' is not a valid riksmöte, expected e.g. '2019/20' or '1880'")]
+    InvalidFormat(String),
+    #[error("'{0}' is not a valid riksmöte, the second year must follow the first")]
+    YearsNotConsecutive(String),
+}
+
+impl Riksmote {
+    /// A riksmöte that follows the calendar year, e.g. "1880".
+    pub fn calendar_year(year: u16) -> Self {
+        Self {
+            start_year: year,
+            split: false,
+            nummer: None,
+        }
+    }
+
+    /// A riksmöte that spans two years, e.g. "2019/20" for `start_year` 2019.
+    pub fn split_year(start_year: u16) -> Self {
+        Self {
+            start_year,
+            split: true,
+            nummer: None,
+        }
+    }
+
+    /// The riksmöte that started in `year`.
+    pub fn starting_in(year: u16) -> Self {
+        if year < FIRST_SPLIT_YEAR {
+            Self::calendar_year(year)
+        } else {
+            Self::split_year(year)
+        }
+    }
+
+    pub fn start_year(&self) -> u16 {
+        self.start_year
+    }
+
+    pub fn end_year(&self) -> u16 {
+        if self.split {
+            self.start_year + 1
+        } else {
+            self.start_year
+        }
+    }
+
+    pub fn nummer(&self) -> Option<u16> {
+        self.nummer
+    }
+
+    /// The riksmöte without its number, "2007/08:1" becomes "2007/08".
+    pub fn without_nummer(&self) -> Self {
+        Self {
+            nummer: None,
+            ..*self
+        }
+    }
+
+    /// The first day of the riksmöte.
+    ///
+    /// The exact opening day varies, so split riksmöten are taken to start on 1 September.
+    pub fn start_date(&self) -> NaiveDate {
+        let (month, day) = if self.split { (9, 1) } else { (1, 1) };
+        NaiveDate::from_ymd_opt(self.start_year.into(), month, day).expect("valid date")
+    }
+
+    /// The last day of the riksmöte, the day before the next riksmöte starts.
+    pub fn end_date(&self) -> NaiveDate {
+        let (month, day) = if self.split { (8, 31) } else { (12, 31) };
+        NaiveDate::from_ymd_opt(self.end_year().into(), month, day).expect("valid date")
+    }
+}
+
+impl FromStr for Riksmote {
+    type Err = RiksmoteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RiksmoteError::InvalidFormat(s.to_string());
+        let trimmed = s.trim();
+        let (years, nummer) = match trimmed.split_once(':') {
+            Some((years, nummer)) => (years, Some(parse_digits(nummer).ok_or_else(invalid)?)),
+            None => (trimmed, None),
+        };
+        let riksmote = match years.split_once('/') {
+            None if years.len() == 4 => {
+                Self::calendar_year(parse_digits(years).ok_or_else(invalid)?)
+            }
+            Some((start, end)) if start.len() == 4 && (end.len() == 2 || end.len() == 4) => {
+                let start_year = parse_digits(start).ok_or_else(invalid)?;
+                let end_year = parse_digits(end).ok_or_else(invalid)?;
+                let expected = if end.len() == 2 {
+                    (start_year + 1) % 100
+                } else {
+                    start_year + 1
+                };
+                if end_year != expected {
+                    return Err(RiksmoteError::YearsNotConsecutive(s.to_string()));
+                }
+                Self::split_year(start_year)
+            }
+            _ => return Err(invalid()),
+        };
+        Ok(Self { nummer, ..riksmote })
+    }
+}
+
+fn parse_digits(s: &str) -> Option<u16> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+impl fmt::Display for Riksmote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.start_year)?;
+        if self.split {
+            let end_year = self.end_year();
+            if end_year % 100 == 0 {
+                // The Riksdag writes "1999/2000"
+                write!(f, "/{end_year}")?;
+            } else {
+                write!(f, "/{:02}", end_year % 100)?;
+            }
+        }
+        if let Some(nummer) = self.nummer {
+            write!(f, ":{nummer}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An inclusive range of riksmöten that can be open at either end, e.g. "2018/19..2021/22".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, SerializeDisplay, DeserializeFromStr)]
+pub struct RiksmoteRange {
+    pub from: Option<Riksmote>,
+    pub to: Option<Riksmote>,
+}
+
+impl RiksmoteRange {
+    pub fn new(from: Option<Riksmote>, to: Option<Riksmote>) -> Self {
+        Self { from, to }
+    }
+
+    /// Check if the range is open at both ends, i.e. contains every riksmöte.
+    pub fn is_unbounded(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+
+    /// Check if `riksmote` is in the range, i.e. if any of its days are.
+    ///
+    /// The days are compared, so the calendar year "2018" is in "2018/19.." and numbered
+    /// riksmöten count as their year.
+    pub fn contains(&self, riksmote: &Riksmote) -> bool {
+        self.overlaps(&RiksmoteRange::from(*riksmote))
+    }
+
+    /// Check if the ranges have any day in common.
+    ///
+    /// Comparing the days lets ranges of calendar years, like the year spans of the SFS
+    /// search, overlap ranges of split riksmöten.
+    pub fn overlaps(&self, other: &RiksmoteRange) -> bool {
+        let starts_before_other_ends = match (self.from, other.to) {
+            (Some(from), Some(to)) => from.start_date() <= to.end_date(),
+            _ => true,
+        };
+        let ends_after_other_starts = match (self.to, other.from) {
+            (Some(to), Some(from)) => from.start_date() <= to.end_date(),
+            _ => true,
+        };
+        starts_before_other_ends && ends_after_other_starts
+    }
+}
+
+impl From<Riksmote> for RiksmoteRange {
+    fn from(riksmote: Riksmote) -> Self {
+        Self::new(Some(riksmote), Some(riksmote))
+    }
+}
+
+impl FromStr for RiksmoteRange {
+    type Err = RiksmoteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_bound = |bound: &str| -> Result<Option<Riksmote>, RiksmoteError> {
+            let bound = bound.trim();
+            if bound.is_empty() {
+                Ok(None)
+            } else {
+                bound.parse().map(Some)
+            }
+        };
+        match s.split_once("..") {
+            Some((from, to)) => Ok(Self::new(parse_bound(from)?, parse_bound(to)?)),
+            None => Ok(s.parse::<Riksmote>()?.into()),
+        }
+    }
+}
+
+impl fmt::Display for RiksmoteRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(from) = self.from {
+            write!(f, "{from}")?;
+        }
+        f.write_str("..")?;
+        if let Some(to) = self.to {
+            write!(f, "{to}")?;
+        }
+        Ok(())
+    }
+}
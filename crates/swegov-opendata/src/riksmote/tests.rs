@@ -0,0 +1,140 @@
+use rstest::rstest;
+
+use super::*;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[rstest]
+#[case("2019/20", 2019, 2020, None)]
+#[case("1975/76", 1975, 1976, None)]
+#[case("1999/2000", 1999, 2000, None)]
+#[case("1880", 1880, 1880, None)]
+#[case("2007/08:1", 2007, 2008, Some(1))]
+#[case(" 2022/23 ", 2022, 2023, None)]
+fn parse_riksmote(
+    #[case] given: &str,
+    #[case] start_year: u16,
+    #[case] end_year: u16,
+    #[case] nummer: Option<u16>,
+) {
+    let riksmote: Riksmote = given.parse().unwrap();
+
+    assert_eq!(riksmote.start_year(), start_year);
+    assert_eq!(riksmote.end_year(), end_year);
+    assert_eq!(riksmote.nummer(), nummer);
+    assert_eq!(riksmote.to_string(), given.trim());
+}
+
+#[rstest]
+#[case("")]
+#[case("201213")]
+#[case("2012-2013")]
+#[case("19/20")]
+#[case("2019/")]
+#[case("2019/20:")]
+#[case("2019/20:a")]
+#[case("+201/20")]
+fn parse_riksmote_fails_on_invalid_format(#[case] given: &str) {
+    assert_eq!(
+        given.parse::<Riksmote>(),
+        Err(RiksmoteError::InvalidFormat(given.to_string()))
+    );
+}
+
+#[rstest]
+#[case("2019/21")]
+#[case("2019/2021")]
+fn parse_riksmote_fails_on_non_consecutive_years(#[case] given: &str) {
+    assert_eq!(
+        given.parse::<Riksmote>(),
+        Err(RiksmoteError::YearsNotConsecutive(given.to_string()))
+    );
+}
+
+#[test]
+fn riksmote_is_ordered() {
+    let mut riksmoten: Vec<Riksmote> = ["2019/20", "1880", "2007/08:1", "1975/76", "2007/08"]
+        .into_iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    riksmoten.sort();
+
+    assert_eq!(
+        riksmoten
+            .iter()
+            .map(Riksmote::to_string)
+            .collect::<Vec<_>>(),
+        ["1880", "1975/76", "2007/08", "2007/08:1", "2019/20"]
+    );
+}
+
+#[rstest]
+#[case("2019/20", date(2019, 9, 1), date(2020, 8, 31))]
+#[case("1880", date(1880, 1, 1), date(1880, 12, 31))]
+fn riksmote_has_start_and_end_date(
+    #[case] given: Riksmote,
+    #[case] start_date: NaiveDate,
+    #[case] end_date: NaiveDate,
+) {
+    assert_eq!(given.start_date(), start_date);
+    assert_eq!(given.end_date(), end_date);
+}
+
+#[rstest]
+#[case(1974, "1974")]
+#[case(1975, "1975/76")]
+#[case(2019, "2019/20")]
+fn riksmote_starting_in(#[case] year: u16, #[case] expected: &str) {
+    assert_eq!(Riksmote::starting_in(year).to_string(), expected);
+}
+
+#[test]
+fn riksmote_serde_roundtrip() -> anyhow::Result<()> {
+    let riksmote: Riksmote = serde_json::from_str(r#""2007/08:1""#)?;
+    assert_eq!(serde_json::to_string(&riksmote)?, r#""2007/08:1""#);
+    Ok(())
+}
+
+#[rstest]
+#[case("2018/19..2021/22", "2018/19", true)]
+#[case("2018/19..2021/22", "2021/22:1", true)]
+#[case("2018/19..2021/22", "2022/23", false)]
+#[case("2018/19..", "2025/26", true)]
+#[case("..1974", "1880", true)]
+#[case("..1974", "1975/76", false)]
+#[case("2019/20", "2019/20", true)]
+#[case("2018/19..", "2018", true)]
+#[case("2019/20..", "2018", false)]
+#[case("..2018/19", "2019", true)]
+#[case("..2018/19", "2020", false)]
+#[case("2018..2020", "2019/20", true)]
+#[case("2018..2020", "2020/21", true)]
+#[case("2018..2020", "2021/22", false)]
+fn riksmote_range_contains(
+    #[case] range: RiksmoteRange,
+    #[case] given: Riksmote,
+    #[case] expected: bool,
+) {
+    assert_eq!(range.contains(&given), expected);
+}
+
+#[rstest]
+#[case("2018/19..2021/22", "2021/22..", true)]
+#[case("2018/19..2021/22", "2022/23..", false)]
+#[case("..1974", "1971..1979", true)]
+#[case("2000/01..2001/02", "..1999/2000", false)]
+#[case("..", "2019/20", true)]
+#[case("2001..2020", "2020/21..", true)]
+#[case("2001..2020", "2021/22..", false)]
+#[case("1981..2000", "..1980/81", true)]
+#[case("2021..2025", "2019/20..2020/21", true)]
+fn riksmote_range_overlaps(
+    #[case] range: RiksmoteRange,
+    #[case] other: RiksmoteRange,
+    #[case] expected: bool,
+) {
+    assert_eq!(range.overlaps(&other), expected);
+    assert_eq!(other.overlaps(&range), expected);
+}
@@ -1,3 +1,5 @@
+pub mod cow;
 pub mod defaults;
 pub mod optionals;
 pub mod strings;
+pub mod xml_value;
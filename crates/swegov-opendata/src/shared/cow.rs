@@ -0,0 +1,28 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer};
+
+#[cfg(test)]
+mod tests;
+
+/// A string that borrows from the input unless it contains escapes.
+#[derive(Deserialize)]
+struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+/// Deserialize an optional string, borrowing from the input when possible.
+///
+/// serde only borrows a bare `Cow<str>`, so `Option<Cow<str>>` is otherwise always owned.
+pub fn deserialize_option<'de, D>(deserializer: D) -> Result<Option<Cow<'de, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Borrowed<'de>>::deserialize(deserializer)?.map(|borrowed| borrowed.0))
+}
+
+/// Deserialize a string that may be null (as empty), borrowing from the input when possible.
+pub fn deserialize_null_default<'de, D>(deserializer: D) -> Result<Cow<'de, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(deserialize_option(deserializer)?.unwrap_or_default())
+}
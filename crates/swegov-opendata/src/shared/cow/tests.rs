@@ -0,0 +1,40 @@
+use super::*;
+
+use rstest::rstest;
+
+#[derive(Deserialize)]
+struct Model<'a> {
+    #[serde(borrow, deserialize_with = "deserialize_option", default)]
+    text: Option<Cow<'a, str>>,
+    #[serde(borrow, deserialize_with = "deserialize_null_default")]
+    titel: Cow<'a, str>,
+}
+
+#[test]
+fn borrows_unescaped_strings() -> anyhow::Result<()> {
+    let model: Model = serde_json::from_str(r#"{"text": "anförande", "titel": "Betänkande"}"#)?;
+
+    assert!(matches!(model.text, Some(Cow::Borrowed("anförande"))));
+    assert!(matches!(model.titel, Cow::Borrowed("Betänkande")));
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    r#"{"text": "<p class=\"x\">", "titel": "\"a\""}"#,
+    Some("<p class=\"x\">"),
+    "\"a\""
+)]
+#[case(r#"{"text": null, "titel": null}"#, None, "")]
+#[case(r#"{"titel": ""}"#, None, "")]
+fn handles_escapes_and_null(
+    #[case] source: &str,
+    #[case] text: Option<&str>,
+    #[case] titel: &str,
+) -> anyhow::Result<()> {
+    let model: Model = serde_json::from_str(source)?;
+
+    assert_eq!(model.text.as_deref(), text);
+    assert_eq!(model.titel, titel);
+    Ok(())
+}
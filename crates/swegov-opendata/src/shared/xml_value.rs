@@ -0,0 +1,126 @@
+//! Convert between XML elements and JSON values the way the JSON endpoint of riksdagen does.
+//!
+//! An empty element is `null`, an element with only text is a string and an element with
+//! child elements is an object with a key per child, where repeated children become an array.
+//! Attributes are dropped. Text mixed with child elements is kept under the key [`TEXT_KEY`].
+
+use std::io::{Read, Write};
+
+use serde_json::{map::Entry, Map, Value};
+use xml::{reader::XmlEvent as ReadEvent, writer::XmlEvent as WriteEvent};
+use yaserde::{de::Deserializer, ser::Serializer};
+
+#[cfg(test)]
+mod tests;
+
+/// Key of the text of an element that also has child elements.
+pub const TEXT_KEY: &str = "#text";
+
+/// Read the element at `reader` as a value.
+///
+/// Like the derived `YaDeserialize` impls the `EndElement` of the element is left to the caller.
+pub fn read_value<R: Read>(reader: &mut Deserializer<R>) -> Result<Value, String> {
+    match reader.next_event()? {
+        ReadEvent::StartElement { .. } => {}
+        event => return Err(format!("Expected StartElement, found {event:?}")),
+    }
+    let mut text = String::new();
+    let mut children = Map::new();
+    loop {
+        match reader.peek()? {
+            ReadEvent::StartElement { name, .. } => {
+                let name = name.local_name.clone();
+                let value = read_value(reader)?;
+                // the EndElement of the child
+                reader.next_event()?;
+                insert_child(&mut children, name, value);
+            }
+            ReadEvent::Characters(_) => {
+                if let ReadEvent::Characters(characters) = reader.next_event()? {
+                    text.push_str(&characters);
+                }
+            }
+            ReadEvent::EndElement { .. } => break,
+            ReadEvent::EndDocument => return Err("Unexpected EndDocument".to_string()),
+            _ => {
+                reader.next_event()?;
+            }
+        }
+    }
+    Ok(match (children.is_empty(), text.is_empty()) {
+        (true, true) => Value::Null,
+        (true, false) => Value::String(text),
+        (false, true) => Value::Object(children),
+        (false, false) => {
+            children.insert(TEXT_KEY.to_string(), Value::String(text));
+            Value::Object(children)
+        }
+    })
+}
+
+fn insert_child(children: &mut Map<String, Value>, name: String, value: Value) {
+    match children.entry(name) {
+        Entry::Vacant(entry) => {
+            entry.insert(value);
+        }
+        Entry::Occupied(mut entry) => match entry.get_mut() {
+            Value::Array(values) => values.push(value),
+            existing => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+        },
+    }
+}
+
+/// Write `value` as an element called `name`, or as one element per item if it is an array.
+pub fn write_value<W: Write>(
+    writer: &mut Serializer<W>,
+    name: &str,
+    value: &Value,
+) -> Result<(), String> {
+    if let Value::Array(values) = value {
+        for value in values {
+            write_value(writer, name, value)?;
+        }
+        return Ok(());
+    }
+    writer
+        .write(WriteEvent::start_element(name))
+        .map_err(|e| e.to_string())?;
+    write_content(writer, value)?;
+    writer
+        .write(WriteEvent::end_element())
+        .map_err(|e| e.to_string())
+}
+
+/// Write `value` as the content of the current element.
+pub fn write_content<W: Write>(writer: &mut Serializer<W>, value: &Value) -> Result<(), String> {
+    match value {
+        Value::Null => Ok(()),
+        Value::String(text) => write_characters(writer, text),
+        Value::Bool(_) | Value::Number(_) => write_characters(writer, &value.to_string()),
+        Value::Array(values) => {
+            for value in values {
+                write_content(writer, value)?;
+            }
+            Ok(())
+        }
+        Value::Object(children) => {
+            for (name, value) in children {
+                if name == TEXT_KEY {
+                    write_content(writer, value)?;
+                } else {
+                    write_value(writer, name, value)?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn write_characters<W: Write>(writer: &mut Serializer<W>, text: &str) -> Result<(), String> {
+    writer
+        .write(WriteEvent::characters(text))
+        .map_err(|e| e.to_string())
+}
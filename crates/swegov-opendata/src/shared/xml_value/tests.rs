@@ -0,0 +1,84 @@
+use proptest::prelude::*;
+use rstest::rstest;
+use serde_json::json;
+use similar_asserts::assert_eq;
+
+use super::*;
+
+fn to_xml(value: &Value) -> String {
+    let mut writer = Serializer::new_for_inner(Vec::new());
+    write_value(&mut writer, "root", value).unwrap();
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+fn from_xml(source: &str) -> Result<Value, String> {
+    let mut reader = Deserializer::new_from_reader(source.as_bytes());
+    read_value(&mut reader)
+}
+
+#[rstest]
+#[case::empty("<root/>", json!(null))]
+#[case::text("<root>Parti</root>", json!("Parti"))]
+#[case::children(
+    "<root><td><h4>Omröstning</h4><p>Förslag</p></td></root>",
+    json!({"td": {"h4": "Omröstning", "p": "Förslag"}})
+)]
+#[case::repeated(
+    "<root><th>Parti</th><th>Ja</th><th/></root>",
+    json!({"th": ["Parti", "Ja", null]})
+)]
+#[case::attributes_are_dropped(
+    r#"<root><td class="parti">S</td></root>"#,
+    json!({"td": "S"})
+)]
+#[case::mixed("<root>text<br/></root>", json!({"#text": "text", "br": null}))]
+fn reads_elements_as_values(#[case] source: &str, #[case] expected: Value) {
+    assert_eq!(from_xml(source).unwrap(), expected);
+}
+
+#[test]
+fn reads_votering_sammanfattning() {
+    let expected = json!({
+        "table": {"tr": [
+            {"td": {"h4": "Omröstning i sakfrågan", "p": "Utskottets förslag mot reservation 1 (SD)"}},
+            {"th": ["Parti", "Ja", "Nej", "Avstående", "Frånvarande"]},
+            {"td": ["S", "0", "0", "93", "7"]},
+            {"td": {"h4": null}}
+        ]},
+        "br": null
+    });
+
+    assert_eq!(from_xml(&to_xml(&expected)).unwrap(), expected);
+}
+
+#[test]
+fn fails_on_truncated_element() {
+    assert!(from_xml("<root><td>S</td>").is_err());
+}
+
+/// Values that XML can represent: no empty strings, objects or arrays, no arrays of arrays
+/// and no whitespace around text.
+fn xml_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        "[a-zA-Z0-9åäöÅÄÖ<>&'\"(),.:;/-]([a-zA-Z0-9åäöÅÄÖ<>&'\"(),.:;/ -]{0,20}[a-zA-Z0-9åäöÅÄÖ<>&'\"(),.:;/-])?"
+            .prop_map(Value::String),
+    ];
+    leaf.prop_recursive(4, 32, 4, |inner| {
+        let child = prop_oneof![
+            3 => inner.clone(),
+            1 => prop::collection::vec(inner.clone(), 2..4).prop_map(Value::Array),
+        ];
+        prop::collection::btree_map("[a-z][a-z0-9_]{0,8}", child, 1..4)
+            .prop_map(|children| Value::Object(children.into_iter().collect()))
+    })
+}
+
+proptest! {
+    #[test]
+    fn roundtrips_through_xml(value in xml_value()) {
+        let xml = to_xml(&value);
+
+        prop_assert_eq!(from_xml(&xml).unwrap(), value, "xml = {}", xml);
+    }
+}
@@ -0,0 +1,433 @@
+//! Stream a dokumentstatus document without holding the large text fields in memory.
+//!
+//! The `html` of protocols and SOUs can be tens of MB. [`read_dokumentstatus`] reads the
+//! document from a reader, hands `dokument.html` and every `debatt.anforande[].anf_text` to a
+//! callback as soon as they are read, and keeps only the rest of the document (the metadata)
+//! around. At most one payload is held at a time.
+
+use std::{collections::BTreeMap, fmt, io};
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+
+use crate::DokumentStatusPageRef;
+
+#[cfg(test)]
+mod tests;
+
+/// A large text field of a dokumentstatus document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Payload<'p> {
+    /// `dokumentstatus.dokument.html`
+    Html(&'p str),
+    /// `dokumentstatus.debatt.anforande[index].anf_text`
+    AnfText { index: usize, text: &'p str },
+}
+
+impl<'p> Payload<'p> {
+    pub fn text(&self) -> &'p str {
+        match self {
+            Payload::Html(text) | Payload::AnfText { text, .. } => text,
+        }
+    }
+}
+
+/// A dokumentstatus document read by [`read_dokumentstatus`].
+#[derive(Debug)]
+pub struct StreamedDokumentStatus<T> {
+    /// The document without the payload fields.
+    pub metadata: Metadata,
+    /// What the callback returned for `dokument.html`.
+    pub html: Option<T>,
+    /// What the callback returned for `anf_text`, by the index of the anförande.
+    pub anf_text: BTreeMap<usize, T>,
+}
+
+/// The JSON of a dokumentstatus document with the payload fields removed.
+#[derive(Debug, Clone)]
+pub struct Metadata(String);
+
+impl Metadata {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Deserialize the metadata, borrowing from `self`.
+    pub fn page(&self) -> serde_json::Result<DokumentStatusPageRef<'_>> {
+        serde_json::from_str(&self.0)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StreamError<E> {
+    #[error("Error reading JSON")]
+    Json(#[source] serde_json::Error),
+    #[error("Error handling payload")]
+    Payload(#[source] E),
+}
+
+/// Read a dokumentstatus document from `reader`, passing every [`Payload`] to `on_payload`.
+///
+/// A leading byte order mark is skipped.
+pub fn read_dokumentstatus<R, T, E, F>(
+    mut reader: R,
+    on_payload: F,
+) -> Result<StreamedDokumentStatus<T>, StreamError<E>>
+where
+    R: io::BufRead,
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    skip_bom(&mut reader).map_err(|error| StreamError::Json(serde_json::Error::io(error)))?;
+    let mut handler = Handler {
+        on_payload,
+        html: None,
+        anf_text: BTreeMap::new(),
+        error: None,
+    };
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let page = match PageSeed(&mut handler).deserialize(&mut deserializer) {
+        Ok(page) => page,
+        Err(error) => {
+            return Err(match handler.error.take() {
+                Some(error) => StreamError::Payload(error),
+                None => StreamError::Json(error),
+            })
+        }
+    };
+    deserializer.end().map_err(StreamError::Json)?;
+    let metadata = serde_json::to_string(&page).map_err(StreamError::Json)?;
+    Ok(StreamedDokumentStatus {
+        metadata: Metadata(metadata),
+        html: handler.html,
+        anf_text: handler.anf_text,
+    })
+}
+
+fn skip_bom(reader: &mut impl io::BufRead) -> io::Result<()> {
+    const BOM: &[u8] = "\u{feff}".as_bytes();
+    // The BOM may be split over several reads
+    let mut matched = 0;
+    while matched < BOM.len() {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        let len = buf.len().min(BOM.len() - matched);
+        if buf[..len] != BOM[matched..matched + len] {
+            break;
+        }
+        reader.consume(len);
+        matched += len;
+    }
+    if matched == 0 || matched == BOM.len() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "incomplete byte order mark",
+        ))
+    }
+}
+
+struct Handler<T, E, F> {
+    on_payload: F,
+    html: Option<T>,
+    anf_text: BTreeMap<usize, T>,
+    error: Option<E>,
+}
+
+impl<T, E, F> Handler<T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    fn handle<DE: de::Error>(&mut self, payload: Payload<'_>) -> Result<(), DE> {
+        match (self.on_payload)(payload) {
+            Ok(value) => {
+                match payload {
+                    Payload::Html(_) => self.html = Some(value),
+                    Payload::AnfText { index, .. } => {
+                        self.anf_text.insert(index, value);
+                    }
+                }
+                Ok(())
+            }
+            Err(error) => {
+                self.error = Some(error);
+                Err(DE::custom("payload handler failed"))
+            }
+        }
+    }
+}
+
+/// `{"dokumentstatus": ...}`
+struct PageSeed<'h, T, E, F>(&'h mut Handler<T, E, F>);
+
+impl<'de, T, E, F> DeserializeSeed<'de> for PageSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T, E, F> Visitor<'de> for PageSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a dokumentstatus page")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut page = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = if key == "dokumentstatus" {
+                map.next_value_seed(StatusSeed(&mut *self.0))?
+            } else {
+                map.next_value()?
+            };
+            page.insert(key, value);
+        }
+        Ok(Value::Object(page))
+    }
+}
+
+/// The sections of `dokumentstatus`.
+struct StatusSeed<'h, T, E, F>(&'h mut Handler<T, E, F>);
+
+impl<'de, T, E, F> DeserializeSeed<'de> for StatusSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T, E, F> Visitor<'de> for StatusSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a dokumentstatus")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut status = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = match key.as_str() {
+                "dokument" => map.next_value_seed(ObjectSeed {
+                    handler: &mut *self.0,
+                    field: PayloadField::Html,
+                })?,
+                "debatt" => map.next_value_seed(DebattSeed(&mut *self.0))?,
+                _ => map.next_value()?,
+            };
+            status.insert(key, value);
+        }
+        Ok(Value::Object(status))
+    }
+}
+
+/// `debatt`, which holds one or many `anforande`.
+struct DebattSeed<'h, T, E, F>(&'h mut Handler<T, E, F>);
+
+impl<'de, T, E, F> DeserializeSeed<'de> for DebattSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, T, E, F> Visitor<'de> for DebattSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a debatt or null")
+    }
+
+    fn visit_unit<Er: de::Error>(self) -> Result<Value, Er> {
+        Ok(Value::Null)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut debatt = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = if key == "anforande" {
+                map.next_value_seed(AnforandeSeed(&mut *self.0))?
+            } else {
+                map.next_value()?
+            };
+            debatt.insert(key, value);
+        }
+        Ok(Value::Object(debatt))
+    }
+}
+
+/// One `anforande` or a list of them.
+struct AnforandeSeed<'h, T, E, F>(&'h mut Handler<T, E, F>);
+
+impl<'de, T, E, F> DeserializeSeed<'de> for AnforandeSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, T, E, F> Visitor<'de> for AnforandeSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("one or many anforande")
+    }
+
+    fn visit_unit<Er: de::Error>(self) -> Result<Value, Er> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut anforanden = Vec::new();
+        while let Some(anforande) = seq.next_element_seed(ObjectSeed {
+            handler: &mut *self.0,
+            field: PayloadField::AnfText(anforanden.len()),
+        })? {
+            anforanden.push(anforande);
+        }
+        Ok(Value::Array(anforanden))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Value, A::Error> {
+        ObjectSeed {
+            handler: self.0,
+            field: PayloadField::AnfText(0),
+        }
+        .visit_map(map)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PayloadField {
+    Html,
+    AnfText(usize),
+}
+
+impl PayloadField {
+    fn key(self) -> &'static str {
+        match self {
+            PayloadField::Html => "html",
+            PayloadField::AnfText(_) => "anf_text",
+        }
+    }
+
+    fn payload(self, text: &str) -> Payload<'_> {
+        match self {
+            PayloadField::Html => Payload::Html(text),
+            PayloadField::AnfText(index) => Payload::AnfText { index, text },
+        }
+    }
+}
+
+/// An object that has a payload `field`, which is passed to the handler instead of being kept.
+struct ObjectSeed<'h, T, E, F> {
+    handler: &'h mut Handler<T, E, F>,
+    field: PayloadField,
+}
+
+impl<'de, T, E, F> DeserializeSeed<'de> for ObjectSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, T, E, F> Visitor<'de> for ObjectSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an object or null")
+    }
+
+    fn visit_unit<Er: de::Error>(self) -> Result<Value, Er> {
+        Ok(Value::Null)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut object = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.field.key() {
+                map.next_value_seed(PayloadSeed {
+                    handler: &mut *self.handler,
+                    field: self.field,
+                })?;
+            } else {
+                object.insert(key, map.next_value()?);
+            }
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+struct PayloadSeed<'h, T, E, F> {
+    handler: &'h mut Handler<T, E, F>,
+    field: PayloadField,
+}
+
+impl<'de, T, E, F> DeserializeSeed<'de> for PayloadSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, T, E, F> Visitor<'de> for PayloadSeed<'_, T, E, F>
+where
+    F: FnMut(Payload<'_>) -> Result<T, E>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string or null")
+    }
+
+    fn visit_unit<Er: de::Error>(self) -> Result<(), Er> {
+        Ok(())
+    }
+
+    fn visit_str<Er: de::Error>(self, v: &str) -> Result<(), Er> {
+        self.handler.handle(self.field.payload(v))
+    }
+}
@@ -0,0 +1,64 @@
+use std::io::BufReader;
+
+use rstest::rstest;
+use similar_asserts::assert_eq;
+
+use super::*;
+
+use crate::DokumentStatusPage;
+
+#[rstest]
+#[case("assets/bet-2018-2021-h601au1.json")]
+#[case("assets/kammakt-2018-2021-h6c120190118zz.json")]
+#[case("assets/prot-1990-1997-ge091.json")]
+fn streams_payloads_and_metadata(#[case] path: &str) -> anyhow::Result<()> {
+    let source = fs_err::read_to_string(path)?;
+    let expected: DokumentStatusPage = serde_json::from_str(source.trim_start_matches('\u{feff}'))?;
+
+    // A small buffer makes sure that nothing depends on the size of the reads
+    let reader = BufReader::with_capacity(16, source.as_bytes());
+    let streamed = read_dokumentstatus(reader, |payload| {
+        Ok::<_, std::convert::Infallible>(payload.text().to_string())
+    })?;
+
+    let expected_anf_text: BTreeMap<usize, String> = expected
+        .dokumentstatus
+        .debatt
+        .iter()
+        .flat_map(|debatt| debatt.anforande.iter())
+        .enumerate()
+        .filter_map(|(index, anforande)| anforande.anf_text.clone().map(|text| (index, text)))
+        .collect();
+    assert_eq!(streamed.html, expected.dokumentstatus.dokument.html);
+    assert_eq!(streamed.anf_text, expected_anf_text);
+
+    let page = streamed.metadata.page()?;
+    assert_eq!(page.dokumentstatus.dokument.html, None);
+    assert_eq!(
+        page.dokumentstatus.dokument.dok_id,
+        expected.dokumentstatus.dokument.dok_id
+    );
+    Ok(())
+}
+
+#[test]
+fn reports_handler_errors() {
+    let source = r#"{"dokumentstatus": {"dokument": {"html": "<p>text</p>"}}}"#;
+
+    let result = read_dokumentstatus(source.as_bytes(), |_payload| Err::<(), _>("failed"));
+
+    assert!(matches!(result, Err(StreamError::Payload("failed"))));
+}
+
+#[rstest]
+#[case("\u{feff}{\"dokumentstatus\": {}}")]
+#[case("{\"dokumentstatus\": {\"dokument\": {\"html\": null}, \"debatt\": null}}")]
+fn reads_documents_without_payloads(#[case] source: &str) -> anyhow::Result<()> {
+    let streamed = read_dokumentstatus(source.as_bytes(), |payload| {
+        Ok::<_, std::convert::Infallible>(payload.text().len())
+    })?;
+
+    assert_eq!(streamed.html, None);
+    assert!(streamed.anf_text.is_empty());
+    Ok(())
+}
@@ -0,0 +1,367 @@
+//! Keep track of where in a document a value is deserialized, so that unknown fields and values
+//! the models can't parse are collected with their path, e.g. `dokumentstatus.dokument.datum`.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    fmt,
+    thread::LocalKey,
+};
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+
+use crate::extra;
+
+#[cfg(test)]
+mod tests;
+
+thread_local! {
+    static PATH: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static FAILED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Paths collected on this thread while a collector is set, see [`collect`].
+pub(crate) type Collected = RefCell<Option<BTreeSet<String>>>;
+
+/// Run `f` and return the paths added to `collected` in it.
+pub(crate) fn collect<T>(
+    collected: &'static LocalKey<Collected>,
+    f: impl FnOnce() -> T,
+) -> (T, BTreeSet<String>) {
+    struct Restore(&'static LocalKey<Collected>, Option<BTreeSet<String>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            self.0.replace(self.1.take());
+        }
+    }
+    let restore = Restore(collected, collected.replace(Some(BTreeSet::new())));
+    let value = f();
+    let paths = collected
+        .replace(None)
+        .expect("collector is set until restored");
+    drop(restore);
+    (value, paths)
+}
+
+/// Add the path of the field `name` to `collected` if a collector is set.
+///
+/// `name` is `None` for the value being deserialized itself.
+pub(crate) fn record(collected: &'static LocalKey<Collected>, name: Option<&str>) {
+    collected.with_borrow_mut(|collected| {
+        if let Some(collected) = collected {
+            collected.insert(path_to(name));
+        }
+    });
+}
+
+/// The path of the field `name` in the value being deserialized, the names of the fields leading
+/// to it joined with `.`.
+///
+/// Elements of an array share the path of the array. Outside [`Tracked`] the path is just `name`.
+pub(crate) fn path_to(name: Option<&str>) -> String {
+    PATH.with_borrow(|path| {
+        path.iter()
+            .map(String::as_str)
+            .chain(name)
+            .collect::<Vec<_>>()
+            .join(".")
+    })
+}
+
+/// Wraps a [`Deserializer`] to keep track of the path to the value being deserialized.
+///
+/// Fields that a model without `extra` doesn't know are handled as the unknown fields in
+/// `extra`, i.e. collected and logged, or an error in strict mode.
+///
+/// Values that serde buffers, e.g. for `#[serde(flatten)]` or `untagged`, aren't tracked inside,
+/// everything in them gets the path of the buffered value. Neither are values of enums.
+pub struct Tracked<D>(D);
+
+impl<D> Tracked<D> {
+    pub fn new(deserializer: D) -> Self {
+        FAILED.set(false);
+        Self(deserializer)
+    }
+
+    fn wrap<V>(self, visitor: V) -> (D, TrackedVisitor<V>) {
+        (self.0, TrackedVisitor(visitor))
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*))*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+                let (deserializer, visitor) = self.wrap(visitor);
+                deserializer.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+macro_rules! forward_deserializer_methods {
+    () => {
+        forward_deserialize! {
+            deserialize_any() deserialize_bool()
+            deserialize_i8() deserialize_i16() deserialize_i32() deserialize_i64() deserialize_i128()
+            deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64() deserialize_u128()
+            deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string()
+            deserialize_bytes() deserialize_byte_buf() deserialize_option() deserialize_unit()
+            deserialize_unit_struct(name: &'static str)
+            deserialize_newtype_struct(name: &'static str)
+            deserialize_seq() deserialize_tuple(len: usize)
+            deserialize_tuple_struct(name: &'static str, len: usize)
+            deserialize_map()
+            deserialize_struct(name: &'static str, fields: &'static [&'static str])
+            deserialize_enum(name: &'static str, variants: &'static [&'static str])
+            deserialize_identifier()
+        }
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Tracked<D> {
+    type Error = D::Error;
+
+    forward_deserializer_methods!();
+
+    /// Serde ignores the values of the fields that a model without `extra` doesn't know.
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        if let Some(name) = PATH.with_borrow_mut(Vec::pop) {
+            let result = extra::unknown_fields(&[&name]);
+            PATH.with_borrow_mut(|path| path.push(name));
+            result?;
+        }
+        // Not tracked inside, the fields of an ignored value aren't unknown themselves
+        self.0.deserialize_ignored_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty))*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<Self::Value, E> {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+macro_rules! forward_visit_scalars {
+    () => {
+        forward_visit! {
+            visit_bool(bool) visit_i8(i8) visit_i16(i16) visit_i32(i32) visit_i64(i64) visit_i128(i128)
+            visit_u8(u8) visit_u16(u16) visit_u32(u32) visit_u64(u64) visit_u128(u128)
+            visit_f32(f32) visit_f64(f64) visit_char(char)
+            visit_bytes(&[u8]) visit_borrowed_bytes(&'de [u8]) visit_byte_buf(Vec<u8>)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            self.0.visit_none()
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            self.0.visit_unit()
+        }
+
+        fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+            self.0.visit_enum(data)
+        }
+    };
+}
+
+struct TrackedVisitor<V>(V);
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for TrackedVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    forward_visit_scalars!();
+
+    forward_visit! {
+        visit_str(&str) visit_borrowed_str(&'de str) visit_string(String)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.visit_some(Tracked(deserializer))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        self.0.visit_newtype_struct(Tracked(deserializer))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(TrackedSeq(seq))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_map(TrackedMap { map, key: None })
+    }
+}
+
+struct TrackedSeed<S>(S);
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for TrackedSeed<S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.deserialize(Tracked(deserializer))
+    }
+}
+
+struct TrackedSeq<A>(A);
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for TrackedSeq<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.0.next_element_seed(TrackedSeed(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+struct TrackedMap<A> {
+    map: A,
+    /// The key of the value to deserialize next, if it is a string.
+    key: Option<String>,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for TrackedMap<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        self.key = None;
+        self.map.next_key_seed(KeySeed {
+            seed,
+            key: &mut self.key,
+        })
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        struct Pop;
+        impl Drop for Pop {
+            fn drop(&mut self) {
+                PATH.with_borrow_mut(Vec::pop);
+            }
+        }
+        let key = self.key.take().unwrap_or_else(|| "?".to_string());
+        PATH.with_borrow_mut(|path| path.push(key));
+        let _pop = Pop;
+        let result = self.map.next_value_seed(TrackedSeed(seed));
+        // Only the innermost value that fails is where the models couldn't parse the document
+        if result.is_err() && !FAILED.replace(true) {
+            crate::try_parse::record_unparsed();
+        }
+        result
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint()
+    }
+}
+
+/// Deserializes a key of a map and keeps it if it is a string.
+struct KeySeed<'k, S> {
+    seed: S,
+    key: &'k mut Option<String>,
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for KeySeed<'_, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.seed.deserialize(Key {
+            deserializer,
+            key: self.key,
+        })
+    }
+}
+
+struct Key<'k, D> {
+    deserializer: D,
+    key: &'k mut Option<String>,
+}
+
+impl<'k, D> Key<'k, D> {
+    fn wrap<V>(self, visitor: V) -> (D, KeyVisitor<'k, V>) {
+        (self.deserializer, KeyVisitor(visitor, self.key))
+    }
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Key<'_, D> {
+    type Error = D::Error;
+
+    forward_deserializer_methods!();
+    forward_deserialize!(deserialize_ignored_any());
+
+    fn is_human_readable(&self) -> bool {
+        self.deserializer.is_human_readable()
+    }
+}
+
+struct KeyVisitor<'k, V>(V, &'k mut Option<String>);
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for KeyVisitor<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    forward_visit_scalars!();
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        *self.1 = Some(v.to_string());
+        self.0.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        *self.1 = Some(v.to_string());
+        self.0.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        *self.1 = Some(v.clone());
+        self.0.visit_string(v)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.visit_some(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        self.0.visit_newtype_struct(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(seq)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_map(map)
+    }
+}
@@ -0,0 +1,109 @@
+use serde::Deserialize;
+
+use super::*;
+use crate::{
+    collect_unknown_fields,
+    date_formats::SweDateTime,
+    try_parse::{collect_unparsed_fields, deserialize_tryparse_from_string, TryParse},
+    with_mode, DeserializeMode, Extra,
+};
+
+#[derive(Debug, serde::Deserialize)]
+struct Status {
+    dokument: Dokument,
+    #[serde(flatten)]
+    extra: Extra,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Dokument {
+    #[serde(deserialize_with = "deserialize_tryparse_from_string")]
+    nummer: TryParse<u64>,
+    datum: Option<SweDateTime>,
+    rader: Vec<Rad>,
+    #[serde(flatten)]
+    extra: Extra,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Rad {
+    text: String,
+    #[serde(flatten)]
+    extra: Extra,
+}
+
+fn deserialize_tracked(json: &str) -> (serde_json::Result<Status>, BTreeSet<String>) {
+    collect_unknown_fields(|| {
+        with_mode(DeserializeMode::Lenient, || {
+            let mut deserializer = serde_json::Deserializer::from_str(json);
+            Status::deserialize(Tracked::new(&mut deserializer))
+        })
+    })
+}
+
+#[test]
+fn tracked_gives_paths_of_unknown_fields() -> anyhow::Result<()> {
+    let (status, paths) = deserialize_tracked(
+        r#"{"dokument": {"nummer": "1", "datum": null, "text": "okänt", "rader": [{"text": "a"}, {"text": "b", "text2": "c"}]}, "text": 1}"#,
+    );
+
+    let status = status?;
+    assert!(matches!(status.dokument.nummer, TryParse::Parsed(1)));
+    assert!(status.dokument.datum.is_none());
+    assert!(status.dokument.extra.contains_key("text"));
+    assert_eq!(status.dokument.rader[1].text, "b");
+    assert_eq!(
+        status.dokument.rader[1].extra.keys().collect::<Vec<_>>(),
+        ["text2"]
+    );
+    assert!(status.extra.contains_key("text"));
+    assert_eq!(
+        paths.into_iter().collect::<Vec<_>>(),
+        ["dokument.rader.text2", "dokument.text", "text"]
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_gives_names_of_unknown_fields() -> anyhow::Result<()> {
+    let (status, names) = collect_unknown_fields(|| {
+        with_mode(DeserializeMode::Lenient, || {
+            serde_json::from_str::<Status>(
+                r#"{"dokument": {"nummer": "1", "datum": null, "rader": [{"text": "b", "text2": "c"}]}}"#,
+            )
+        })
+    });
+
+    status?;
+    assert_eq!(names.into_iter().collect::<Vec<_>>(), ["text2"]);
+    Ok(())
+}
+
+#[test]
+fn tracked_gives_paths_of_unparsed_values() {
+    let ((status, _), paths) = collect_unparsed_fields(|| {
+        deserialize_tracked(
+            r#"{"dokument": {"nummer": "1 s.2", "datum": "igår", "rader": [{"text": "a"}]}}"#,
+        )
+    });
+
+    assert!(status.is_err());
+    assert_eq!(
+        paths.into_iter().collect::<Vec<_>>(),
+        ["dokument.datum", "dokument.nummer"]
+    );
+}
+
+#[test]
+fn tracked_keeps_borrowed_strings() -> anyhow::Result<()> {
+    #[derive(Debug, serde::Deserialize)]
+    struct Borrowed<'a> {
+        text: &'a str,
+    }
+    let mut deserializer = serde_json::Deserializer::from_str(r#"{"text": "lånad"}"#);
+
+    let borrowed = Borrowed::deserialize(Tracked::new(&mut deserializer))?;
+
+    assert_eq!(borrowed.text, "lånad");
+    Ok(())
+}
@@ -2,11 +2,35 @@
 
 use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::fmt::{self, Display};
 use std::str::FromStr;
 use xml::writer::XmlEvent;
 use yaserde::{YaDeserialize, YaSerialize};
 
+use crate::shared::xml_value;
+use crate::tracked::{self, Collected};
+
+#[cfg(test)]
+mod tests;
+
+thread_local! {
+    static UNPARSED_FIELDS: Collected = const { RefCell::new(None) };
+}
+
+/// Run `f` and return the paths of all values that the models couldn't parse while deserializing
+/// in it, i.e. [`TryParse::Unparsed`] values and the value that made deserializing fail.
+///
+/// The paths are only known when deserializing with [`Tracked`](crate::Tracked).
+pub fn collect_unparsed_fields<T>(f: impl FnOnce() -> T) -> (T, BTreeSet<String>) {
+    tracked::collect(&UNPARSED_FIELDS, f)
+}
+
+pub(crate) fn record_unparsed() {
+    tracked::record(&UNPARSED_FIELDS, None);
+}
+
 #[derive(Debug)]
 pub enum TryParse<T> {
     Parsed(T),
@@ -30,7 +54,10 @@ impl<'de, T: DeserializeOwned> Deserialize<'de> for TryParse<T> {
             None => Ok(TryParse::NotPresent),
             Some(value) => match T::deserialize(&value) {
                 Ok(t) => Ok(TryParse::Parsed(t)),
-                Err(_) => Ok(TryParse::Unparsed(value)),
+                Err(_) => {
+                    record_unparsed();
+                    Ok(TryParse::Unparsed(value))
+                }
             },
         }
     }
@@ -81,11 +108,7 @@ impl<T: Display> YaSerialize for TryParse<T> {
                     .write(XmlEvent::characters(&v.to_string()))
                     .map_err(|e| e.to_string())?;
             }
-            TryParse::Unparsed(v) => {
-                writer
-                    .write(XmlEvent::characters(&v.to_string()))
-                    .map_err(|e| e.to_string())?;
-            }
+            TryParse::Unparsed(v) => xml_value::write_content(writer, v)?,
         }
         if !writer.skip_start_end() {
             writer
@@ -118,19 +141,11 @@ where
     fn deserialize<R: std::io::Read>(
         reader: &mut yaserde::de::Deserializer<R>,
     ) -> Result<Self, String> {
-        loop {
-            match reader.peek()? {
-                xml::reader::XmlEvent::StartElement { .. } => {}
-                xml::reader::XmlEvent::Characters(ref text) => {
-                    return TryParse::<T>::from_str(text).map_err(|e| e.to_string());
-                }
-                _ => {
-                    break;
-                }
-            }
-            let _next = reader.next_event();
+        match xml_value::read_value(reader)? {
+            Value::Null => Ok(TryParse::NotPresent),
+            Value::String(text) => TryParse::<T>::from_str(&text).map_err(|e| e.to_string()),
+            value => Ok(TryParse::Unparsed(value)),
         }
-        Err("Unable to parse TryParse".to_string())
     }
 }
 
@@ -160,7 +175,13 @@ where
     }
 
     match StringOrTryParse::<T>::deserialize(deserializer)? {
-        StringOrTryParse::String(s) => s.parse::<TryParse<T>>().map_err(serde::de::Error::custom),
+        StringOrTryParse::String(s) => {
+            let parsed = s.parse::<TryParse<T>>().map_err(serde::de::Error::custom)?;
+            if let TryParse::Unparsed(_) = parsed {
+                record_unparsed();
+            }
+            Ok(parsed)
+        }
         StringOrTryParse::Value(t) => Ok(TryParse::Parsed(t)),
     }
 }
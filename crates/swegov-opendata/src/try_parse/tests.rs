@@ -0,0 +1,35 @@
+use proptest::prelude::*;
+use rstest::rstest;
+use serde_json::json;
+
+use super::*;
+
+fn roundtrip(value: &TryParse<u64>) -> TryParse<u64> {
+    let xml = yaserde::ser::to_string_content(value).unwrap();
+    yaserde::de::from_str(&xml).unwrap()
+}
+
+#[rstest]
+#[case::parsed(TryParse::Parsed(48), json!(48))]
+#[case::unparsed("48 s.1".parse().unwrap(), json!("48 s.1"))]
+#[case::not_present(TryParse::NotPresent, json!(null))]
+#[case::unparsed_object(TryParse::Unparsed(json!({"nummer": "48", "tillagg": "s.1"})), json!({"nummer": "48", "tillagg": "s.1"}))]
+fn roundtrips_through_xml(#[case] value: TryParse<u64>, #[case] expected: Value) {
+    let actual = roundtrip(&value);
+
+    assert_eq!(serde_json::to_value(&actual).unwrap(), expected);
+}
+
+proptest! {
+    #[test]
+    fn keeps_text_through_xml(text in "[0-9a-z.:]([0-9a-z .:]{0,10}[0-9a-z.:])?") {
+        let value: TryParse<u64> = text.parse().unwrap();
+
+        let actual = roundtrip(&value);
+
+        prop_assert_eq!(
+            serde_json::to_value(&actual).unwrap(),
+            serde_json::to_value(&value).unwrap()
+        );
+    }
+}
@@ -1,2 +1,3 @@
 mod rd_examples;
 mod sfs_examples;
+mod xml_parity;
@@ -1,9 +1,14 @@
+use std::borrow::Cow;
+
 use fs_err as fs;
 
 use anyhow::Context;
 use rstest::rstest;
 
-use swegov_opendata::{DatasetLista, DokumentStatusPage, DokumentStatusPageRef};
+use swegov_opendata::{
+    collect_unknown_fields, with_mode, DatasetLista, DeserializeMode, DokumentStatusPage,
+    DokumentStatusPageRef,
+};
 
 #[rstest]
 #[case("assets/Riksdagens diarium-2014-2017-h5d2467.json")]
@@ -41,8 +46,10 @@ fn rd_example(#[case] filename: &str) -> anyhow::Result<()> {
     let source = fs::read_to_string(filename)?;
     let source = without_bom(&source);
 
+    // Strict, to notice when Riksdagen changes the schema
     let dokumentstatus: DokumentStatusPage =
-        serde_json::from_str(source).with_context(|| "Failed deserialize to DokumentStatusPage")?;
+        with_mode(DeserializeMode::Strict, || serde_json::from_str(source))
+            .with_context(|| "Failed deserialize to DokumentStatusPage")?;
     dbg!(&dokumentstatus);
     Ok(())
 }
@@ -84,8 +91,84 @@ fn rd_example_ref(#[case] filename: &str) -> anyhow::Result<()> {
     let source = without_bom(&source);
 
     let dokumentstatus: DokumentStatusPageRef<'_> =
-        serde_json::from_str(source).with_context(|| "Failed deserialize to DokumentStatusPage")?;
-    dbg!(&dokumentstatus);
+        with_mode(DeserializeMode::Strict, || serde_json::from_str(source))
+            .with_context(|| "Failed deserialize to DokumentStatusPageRef")?;
+    let owned: DokumentStatusPage = serde_json::from_str(source)?;
+
+    // The Ref models must hold the same data as the owned ones
+    assert_eq!(
+        serde_json::to_value(dokumentstatus.to_owned())?,
+        serde_json::to_value(&owned)?
+    );
+    Ok(())
+}
+
+#[test]
+fn rd_example_ref_borrows_text() -> anyhow::Result<()> {
+    let source = fs::read_to_string("assets/bet-2018-2021-h601au1.json")?;
+    let source = without_bom(&source);
+
+    let dokumentstatus: DokumentStatusPageRef<'_> = serde_json::from_str(source)?;
+
+    let dokument = &dokumentstatus.dokumentstatus.dokument;
+    assert!(matches!(dokument.titel, Cow::Borrowed(_)));
+    assert!(matches!(dokument.subtitel, Some(Cow::Borrowed(_))));
+    Ok(())
+}
+
+#[test]
+fn rd_example_with_unknown_fields() -> anyhow::Result<()> {
+    let source = fs::read_to_string("assets/bet-2018-2021-h601au1.json")?;
+    let mut value: serde_json::Value = serde_json::from_str(without_bom(&source))?;
+    value["dokumentstatus"]["dokument"]["nytt_falt"] = "värde".into();
+    let source = serde_json::to_string(&value)?;
+
+    let dokumentstatus: DokumentStatusPage =
+        with_mode(DeserializeMode::Lenient, || serde_json::from_str(&source))?;
+    let dokument_extra = &dokumentstatus.dokumentstatus.dokument.extra;
+    assert_eq!(dokument_extra.get("nytt_falt"), Some(&"värde".into()));
+
+    // The Ref models don't keep unknown fields
+    let dokumentstatus: DokumentStatusPageRef<'_> =
+        with_mode(DeserializeMode::Lenient, || serde_json::from_str(&source))?;
+    assert!(dokumentstatus
+        .to_owned()
+        .dokumentstatus
+        .dokument
+        .extra
+        .is_empty());
+
+    let result: Result<DokumentStatusPage, _> =
+        with_mode(DeserializeMode::Strict, || serde_json::from_str(&source));
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn rd_example_ref_with_unknown_fields() -> anyhow::Result<()> {
+    let source = fs::read_to_string("assets/bet-2018-2021-h601au1.json")?;
+    let mut value: serde_json::Value = serde_json::from_str(without_bom(&source))?;
+    value["dokumentstatus"]["dokument"]["nytt_falt"] = "värde".into();
+    let source = serde_json::to_string(&value)?;
+
+    let (result, unknown_fields) = collect_unknown_fields(|| {
+        with_mode(DeserializeMode::Lenient, || {
+            serde_json::from_str::<DokumentStatusPageRef<'_>>(&source)
+        })
+    });
+    result?;
+    assert_eq!(
+        unknown_fields.into_iter().collect::<Vec<_>>(),
+        ["dokumentstatus.dokument.nytt_falt"]
+    );
+
+    let result: Result<DokumentStatusPageRef<'_>, _> =
+        with_mode(DeserializeMode::Strict, || serde_json::from_str(&source));
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.starts_with("unknown fields: nytt_falt"),
+        "unexpected error '{error}'"
+    );
     Ok(())
 }
 
@@ -0,0 +1,96 @@
+use fs_err as fs;
+
+use rstest::rstest;
+use serde_json::Value;
+use similar_asserts::assert_eq;
+
+use swegov_opendata::{DokumentStatus, DokumentStatusPage};
+
+/// XML can't tell an empty string from a missing value, and the XML parser trims text and
+/// normalizes line breaks.
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::String(text) => {
+            let text = text.replace("\r\n", "\n").replace('\r', "\n");
+            match text.trim() {
+                "" => Value::Null,
+                text => Value::String(text.to_string()),
+            }
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(normalize).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name, normalize(value)))
+                .filter(|(_, value)| !value.is_null())
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[rstest]
+#[case("assets/Riksdagens diarium-2014-2017-h5d2467.json")]
+#[case("assets/bet-1998-2001-gp01bou1.json")]
+#[case("assets/bet-2006-2009-gu01au1.json")]
+#[case("assets/bet-2006-2009-gw01fiu3.json")]
+#[case("assets/bet-2010-2013-gy01au1.json")]
+#[case("assets/bet-2010-2013-h101föu11.json")]
+#[case("assets/bet-2018-2021-h601au1.json")]
+#[case("assets/bet-2018-2021-h601au6.json")]
+#[case("assets/bet-2022-2025-ha01au6.json")]
+#[case("assets/frsrdg-2018-2021-h604nr1.json")]
+#[case("assets/ip-2006-2009-gu10110.json")]
+#[case("assets/ip-2006-2009-gu10379.json")]
+#[case("assets/ip-2014-2017-h210566.json")]
+#[case("assets/ip-2014-2017-h210692.json")]
+#[case("assets/ip-2014-2017-h410591.json")]
+#[case("assets/kammakt-2018-2021-h6c120190118zz.json")]
+#[case("assets/kammakt-2018-2021-h9c120220419bu.json")]
+#[case("assets/kom-2010-2014-h2b643.json")]
+#[case("assets/kom-2020--h8b6447.json")]
+#[case("assets/mot-1971-1979-g3021833.json")]
+#[case("assets/mot-1998-2001-gm02bo208.json")]
+#[case("assets/mot-2010-2013-gy02a1.json")]
+#[case("assets/mot-2010-2013-gy02a245.json")]
+#[case("assets/mot-2010-2013-gy02x-s68106.json")]
+#[case("assets/mot-2010-2013-h102xs24006.json")]
+#[case("assets/mot-2014-2017-h2021148.json")]
+#[case("assets/prop-2018-2021-h603100.json")]
+#[case("assets/prot-1990-1997-ge091.json")]
+#[case("assets/utskottsdokument-2002-2005-gta1aureg.json")]
+#[case("assets/yttr-2018-2021-h605au1y.json")]
+fn dokumentstatus_json_roundtrips_through_xml(#[case] path: &str) -> anyhow::Result<()> {
+    let source = fs::read_to_string(path)?;
+    let page: DokumentStatusPage = serde_json::from_str(source.trim_start_matches('\u{feff}'))?;
+
+    let xml = yaserde::ser::to_string(&page.dokumentstatus).map_err(anyhow::Error::msg)?;
+    let from_xml: DokumentStatus = yaserde::de::from_str(&xml).map_err(anyhow::Error::msg)?;
+
+    assert_eq!(
+        normalize(serde_json::to_value(&from_xml)?),
+        normalize(serde_json::to_value(&page.dokumentstatus)?)
+    );
+    Ok(())
+}
+
+#[rstest]
+#[case("assets/dokumentstatus.xml")]
+fn dokumentstatus_xml_matches_json(#[case] path: &str) -> anyhow::Result<()> {
+    let source = fs::read_to_string(path)?;
+    let from_xml: DokumentStatus = yaserde::de::from_str(&source).map_err(anyhow::Error::msg)?;
+
+    // XML -> model -> JSON must be readable as the JSON endpoint
+    let json = serde_json::to_value(&from_xml)?;
+    let from_json: DokumentStatus = serde_json::from_value(json.clone())?;
+    assert_eq!(serde_json::to_value(&from_json)?, json);
+
+    // and writing the model as XML must keep everything
+    let xml = yaserde::ser::to_string(&from_xml).map_err(anyhow::Error::msg)?;
+    let roundtripped: DokumentStatus = yaserde::de::from_str(&xml).map_err(anyhow::Error::msg)?;
+    assert_eq!(
+        normalize(serde_json::to_value(&roundtripped)?),
+        normalize(json)
+    );
+    Ok(())
+}